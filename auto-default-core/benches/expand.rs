@@ -0,0 +1,75 @@
+//! Expansion time of very large items, such as the ones produced by `bindgen`
+//!
+//! Run with `cargo bench -p auto-default-core`, and with `--features full-parse`
+//! to measure the parser that uses `syn`
+//!
+//! Before and after identifiers were compared without allocating, and the default value
+//! was created once per item:
+//!
+//! ```text
+//! struct with 5000 fields                   16.7ms => 12.9ms
+//! struct with 5000 fields, infer_literals   15.3ms =>  9.4ms
+//! enum with 5000 variants                   23.5ms => 13.4ms
+//! ```
+//!
+//! With `full-parse`, the struct with 5000 fields took over a minute when `syn` was given the rest
+//! of the item for every field, and takes about 33ms since it is only given the field's tokens
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use proc_macro2::TokenStream;
+
+/// How many fields, or variants, the generated items have
+const SIZE: usize = 5000;
+
+/// How many times each item is expanded. The fastest expansion is reported,
+/// since it is the least affected by other work on the machine
+const ITERATIONS: u32 = 200;
+
+fn main() {
+    let mut large_struct = String::from("pub struct Large {");
+    for i in 0..SIZE {
+        large_struct.push_str(&format!(
+            "#[doc = \"field\"] pub field_{i}: Vec<u{}>,",
+            8 << (i % 4)
+        ));
+    }
+    large_struct.push('}');
+
+    let mut public_struct = String::from("pub struct Large {");
+    for i in 0..SIZE {
+        public_struct.push_str(&format!("pub(crate) field_{i}: Option<u8>,"));
+    }
+    public_struct.push('}');
+
+    let mut large_enum = String::from("pub enum Large {");
+    for i in 0..SIZE {
+        large_enum.push_str(&format!("Variant{i} {{ a: u8, b: Option<u32> = None }},"));
+    }
+    large_enum.push('}');
+
+    bench("struct with 5000 fields", "", &large_struct);
+    bench(
+        "struct with 5000 fields, infer_literals",
+        "infer_literals",
+        &large_struct,
+    );
+    bench("struct with 5000 public fields", "", &public_struct);
+    bench("enum with 5000 variants", "", &large_enum);
+}
+
+fn bench(name: &str, args: &str, item: &str) {
+    let args = args.parse::<TokenStream>().unwrap();
+    let item = item.parse::<TokenStream>().unwrap();
+
+    let mut fastest = Duration::MAX;
+    for _ in 0..ITERATIONS {
+        let (args, item) = (args.clone(), item.clone());
+        let start = Instant::now();
+        black_box(auto_default_core::expand(args, item).unwrap());
+        fastest = fastest.min(start.elapsed());
+    }
+
+    println!("{name}: {fastest:?}");
+}
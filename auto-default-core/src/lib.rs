@@ -0,0 +1,275 @@
+//! The transformation behind the [`#[auto_default]`](https://docs.rs/auto-default) attribute,
+//! for authors of other macros
+//!
+//! [`expand`](fn@expand) adds a default field value of `Default::default()` to fields that don't have one.
+//! It works on [`proc_macro2::TokenStream`], so a derive or attribute macro can apply it
+//! to items that it generates:
+//!
+//! ```rust
+//! # fn main() -> Result<(), auto_default_core::Error> {
+//! let item = "struct User { age: u8, is_admin: bool = false }".parse().unwrap();
+//!
+//! let expanded = auto_default_core::expand(proc_macro2::TokenStream::new(), item)?;
+//!
+//! assert_eq!(
+//!     expanded.to_string(),
+//!     "struct User { age : u8 = :: core :: default :: Default :: default () , is_admin : bool = false }"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! This crate shares its source code with the `auto-default` crate, so the expansion is always
+//! the same as the one of `#[auto_default]`.
+//!
+//! The only difference is that warnings, such as the one about `#[auto_default]` doing nothing,
+//! are not reported, because warnings can't be emitted from stable proc macros
+
+// The modules are shared with the `auto-default` crate, and refer to `proc_macro`
+extern crate proc_macro2 as proc_macro;
+
+#[path = "../../src/args.rs"]
+mod args;
+#[path = "../../src/assert_default.rs"]
+mod assert_default;
+#[path = "../../src/derives.rs"]
+mod derives;
+#[path = "../../src/diagnostic.rs"]
+mod diagnostic;
+#[path = "../../src/expand.rs"]
+mod expand;
+#[path = "../../src/extern_c.rs"]
+mod extern_c;
+#[cfg(feature = "full-parse")]
+#[allow(
+    clippy::useless_conversion,
+    reason = "converts between `proc_macro` and `proc_macro2`, which are the same in this crate"
+)]
+#[path = "../../src/full_parse.rs"]
+mod full_parse;
+#[path = "../../src/generics.rs"]
+mod generics;
+#[path = "../../src/impl_default.rs"]
+mod impl_default;
+#[path = "../../src/methods.rs"]
+mod methods;
+#[path = "../../src/policy.rs"]
+mod policy;
+#[path = "../../src/pretty.rs"]
+mod pretty;
+#[path = "../../src/self_test.rs"]
+mod self_test;
+#[path = "../../src/tokens.rs"]
+mod tokens;
+#[path = "../../src/wasm.rs"]
+mod wasm;
+
+use std::path::{Path, PathBuf};
+use std::{env, fmt};
+
+use proc_macro2::{Literal, TokenStream, TokenTree};
+
+use args::Args;
+use diagnostic::CompileError;
+#[cfg(feature = "testing")]
+use diagnostic::ErrorCode;
+use expand::Expansion;
+#[cfg(feature = "testing")]
+use expand::take_container_args;
+use policy::Policy;
+use tokens::{parenthesized, tokens};
+
+/// Adds default field values to `item`, in the same way as `#[auto_default(args)]`
+///
+/// `args` are the arguments that would be passed to the attribute, such as `infer_literals`,
+/// and they may be empty. `#[auto_default(...)]` attributes on fields and variants are removed
+///
+/// When called by a proc macro, the policy file of the crate that is being compiled applies,
+/// and the expansion includes it so that `cargo` compiles the crate again when it changes
+pub fn expand(args: TokenStream, item: TokenStream) -> Result<TokenStream, Error> {
+    // `cargo` sets this for `rustc`, and proc macros run inside of it
+    let Some(policy) = env::var_os("CARGO_MANIFEST_DIR")
+        .and_then(|manifest_dir| policy_path(Path::new(&manifest_dir)))
+    else {
+        return expand_with_policy(args, item, None);
+    };
+
+    // const _: &str = include_str!("/path/to/auto_default.toml");
+    let tracking = tokens![
+        "const _: &str = ::core::include_str!",
+        parenthesized(tokens![TokenTree::Literal(Literal::string(
+            &policy.to_string_lossy()
+        ))]),
+        ";"
+    ];
+
+    match expand_with_policy(args, item, Some(&policy)) {
+        Ok(mut item) => {
+            item.extend(tracking);
+            Ok(item)
+        }
+        Err(mut error) => {
+            error.item.extend(tracking);
+            Err(error)
+        }
+    }
+}
+
+/// The same as [`expand`](fn@expand), with the policy file at `policy` instead of the one of the crate
+/// that is being compiled
+///
+/// The expansion doesn't include the policy file
+pub fn expand_with_policy(
+    args: TokenStream,
+    item: TokenStream,
+    policy: Option<&Path>,
+) -> Result<TokenStream, Error> {
+    let mut errors = Vec::new();
+
+    let mut args = Args::parse(args, &mut errors);
+    args.set_policy(policy.and_then(|policy| Policy::load(policy, &mut errors)));
+    let Expansion {
+        item,
+        noop: _,
+        warnings: _,
+    } = expand::expand(item, &args, &mut errors);
+
+    if args.debug {
+        eprintln!(
+            "#[auto_default(debug)] expanded to:\n{}",
+            pretty::item_to_string(item.clone())
+        );
+    }
+
+    if errors.is_empty() {
+        Ok(item)
+    } else {
+        Err(Error { item, errors })
+    }
+}
+
+/// Path to the policy file of the crate in `manifest_dir`, the directory of its `Cargo.toml`
+///
+/// This is `auto_default.toml` in that directory, or the path in the `AUTO_DEFAULT_CONFIG`
+/// environment variable relative to it. Returns `None` if the crate has no policy file
+pub fn policy_path(manifest_dir: &Path) -> Option<PathBuf> {
+    policy::path(manifest_dir, env::var_os(policy::ENV_VAR).as_deref())
+}
+
+/// Expands every item marked `#[auto_default]` in `source`, returning the formatted code
+///
+/// Items without the attribute are kept as they are. This is intended for snapshot tests,
+/// such as with [`insta`](https://docs.rs/insta), to pin down what an item expands to:
+///
+/// ```rust
+/// let expanded = auto_default_core::expand_to_string(
+///     "
+///     #[auto_default(infer_literals)]
+///     struct User {
+///         age: u8,
+///         name: String,
+///     }
+///     ",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     expanded,
+///     "\
+/// struct User {
+///     age: u8 = 0,
+///     name: String = ::core::default::Default::default(),
+/// }"
+/// );
+/// ```
+///
+/// The policy file of the crate doesn't apply, so the output doesn't depend on where it is called.
+/// Use [`expand_to_string_with_policy`] to expand with a policy file
+#[cfg(feature = "testing")]
+pub fn expand_to_string(source: &str) -> Result<String, Error> {
+    expand_to_string_with_policy(source, None)
+}
+
+/// The same as [`expand_to_string`], with the policy file at `policy`
+#[cfg(feature = "testing")]
+pub fn expand_to_string_with_policy(source: &str, policy: Option<&Path>) -> Result<String, Error> {
+    let source = source.parse::<TokenStream>().map_err(|error| Error {
+        item: TokenStream::new(),
+        errors: vec![CompileError::new(
+            ErrorCode::InvalidItem,
+            error.span(),
+            error.to_string(),
+        )],
+    })?;
+
+    let mut output = TokenStream::new();
+    let mut item = TokenStream::new();
+
+    for tt in source {
+        // struct Foo { ... }
+        //            ^^^^^^^ end of an item with fields
+        // struct Foo;
+        //           ^ end of an item without fields
+        let is_end = match &tt {
+            proc_macro2::TokenTree::Group(group) => {
+                group.delimiter() == proc_macro2::Delimiter::Brace
+            }
+            proc_macro2::TokenTree::Punct(punct) => punct.as_char() == ';',
+            _ => false,
+        };
+        item.extend([tt]);
+
+        if is_end {
+            output.extend(match take_container_args(std::mem::take(&mut item)) {
+                (Some(args), item) => expand_with_policy(args, item, policy)?,
+                (None, item) => item,
+            });
+        }
+    }
+    output.extend(item);
+
+    Ok(pretty::item_to_string(output))
+}
+
+/// Errors in the arguments, or in the item passed to [`expand`](fn@expand)
+pub struct Error {
+    /// The item, with as much of it expanded as possible
+    item: TokenStream,
+    /// All of the errors. There is at least one
+    errors: Vec<CompileError>,
+}
+
+impl Error {
+    /// The item followed by a `compile_error!` for each of the errors
+    ///
+    /// Emitting the item along with the errors avoids follow-up errors wherever the item is used
+    pub fn to_compile_error(&self) -> TokenStream {
+        let mut tokens = self.item.clone();
+        for error in &self.errors {
+            tokens.extend(error.clone());
+        }
+        tokens
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.errors.iter().map(ToString::to_string))
+            .finish()
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,146 @@
+//! The parser works on tokens, so it must not panic on token streams that `rustc` would reject,
+//! or that other macros generate, such as groups without delimiters
+
+use std::panic;
+
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+
+const ITEMS: &[(&str, &str)] = &[
+    (
+        "infer_literals, reset, is_default",
+        "#[derive(Debug)] pub struct Foo<T: Clone> where T: Copy {
+            #[auto_default(skip)] a: T,
+            b: u8 = 4,
+            #[auto_default(value = b * 2)] c: u8,
+        }",
+    ),
+    (
+        "first_variant_default, self_test",
+        "enum Mode { Fast { a: u8 }, Slow(u8) = 1, #[auto_default(skip)] Custom { b: Vec<u8> }, Unit }",
+    ),
+    (
+        "skip_fields(prefix = \"_\"), with_methods, impl_default",
+        "#[auto_default::skip] struct Tuple(u8); struct Named { _a: u8, b: String }",
+    ),
+];
+
+/// Expands `item`, and fails the test if the expansion panics
+fn expand(args: &TokenStream, item: TokenStream) {
+    let expansion =
+        panic::catch_unwind(|| auto_default_core::expand(args.clone(), item.clone()).map(drop));
+    assert!(expansion.is_ok(), "the expansion panicked on:\n{item}");
+}
+
+/// Expands every item of [`ITEMS`], once for each token tree, after it is changed by `edit`
+///
+/// `edit` is called with the token trees of the group that contains the token tree,
+/// and its index
+fn expand_edited(edit: impl Fn(&mut Vec<TokenTree>, usize)) {
+    for (args, item) in ITEMS {
+        let args = args.parse::<TokenStream>().unwrap();
+        let item = item.parse::<TokenStream>().unwrap();
+        let mut paths = Vec::new();
+        collect_paths(&item, &mut Vec::new(), &mut paths);
+        for path in paths {
+            expand(&args, edit_at(item.clone(), &path, &edit));
+        }
+    }
+}
+
+/// Paths to every token tree of `stream`, and of the groups inside of it
+fn collect_paths(stream: &TokenStream, path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+    for (i, tt) in stream.clone().into_iter().enumerate() {
+        path.push(i);
+        paths.push(path.clone());
+        if let TokenTree::Group(group) = tt {
+            collect_paths(&group.stream(), path, paths);
+        }
+        path.pop();
+    }
+}
+
+/// `stream` with the group that contains the token tree at `path` changed by `edit`
+fn edit_at(
+    stream: TokenStream,
+    path: &[usize],
+    edit: &impl Fn(&mut Vec<TokenTree>, usize),
+) -> TokenStream {
+    let mut tts = stream.into_iter().collect::<Vec<_>>();
+    match path {
+        [] => {}
+        [i] => edit(&mut tts, *i),
+        [i, rest @ ..] => {
+            if let TokenTree::Group(group) = &tts[*i] {
+                let mut changed =
+                    Group::new(group.delimiter(), edit_at(group.stream(), rest, edit));
+                changed.set_span(group.span());
+                tts[*i] = TokenTree::Group(changed);
+            }
+        }
+    }
+    tts.into_iter().collect()
+}
+
+#[test]
+fn truncated_items() {
+    expand_edited(|tts, i| tts.truncate(i));
+}
+
+#[test]
+fn removed_tokens() {
+    expand_edited(|tts, i| {
+        tts.remove(i);
+    });
+}
+
+#[test]
+fn repeated_tokens() {
+    expand_edited(|tts, i| tts.insert(i, tts[i].clone()));
+}
+
+#[test]
+fn undelimited_groups() {
+    // `macro_rules!` wraps fragments such as `$ty:ty` in groups without delimiters
+    expand_edited(|tts, i| {
+        tts[i] = TokenTree::Group(Group::new(Delimiter::None, tts[i].clone().into()));
+    });
+}
+
+#[test]
+fn arguments() {
+    let item = "struct Foo { a: u8 }".parse::<TokenStream>().unwrap();
+    for args in [
+        "skip_fields(prefix)",
+        "skip_fields(prefix =)",
+        "skip_fields(prefix = \"_\" \"_\")",
+        "skip_fields(prefix = , suffix = )",
+        "alias = , helper = ",
+        "with = ,",
+        "= reset",
+        ", , ,",
+        "reset(",
+        "bound = \"\"",
+        "const_if = \"\"",
+        "const_if = \"(\"",
+    ] {
+        let Ok(args) = args.parse::<TokenStream>() else {
+            continue;
+        };
+        expand(&args, item.clone());
+    }
+}
+
+#[test]
+fn reemits_item() {
+    let item = "#[derive(Debug)] struct Foo { #[auto_default(skip)] a: u8, # b: u8 }";
+    let error = auto_default_core::expand(TokenStream::new(), item.parse().unwrap()).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "[AD006] expected `[...]` after `#`, found `b`"
+    );
+
+    // The item is emitted as it was written, without `#[auto_default(skip)]`
+    let compile_error = error.to_compile_error().to_string();
+    assert!(compile_error.starts_with("# [derive (Debug)] struct Foo { a : u8 , # b : u8 }"));
+}
@@ -0,0 +1,576 @@
+use proc_macro2::TokenStream;
+
+fn tokens(code: &str) -> TokenStream {
+    code.parse().unwrap()
+}
+
+#[test]
+fn adds_default_field_values() {
+    let expanded = auto_default_core::expand(
+        tokens("infer_literals"),
+        tokens(
+            "
+            pub struct Foo<T> {
+                a: u8,
+                b: Vec<T>,
+                #[auto_default(skip)]
+                c: T,
+                d: bool = true,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            pub struct Foo<T> {
+                a: u8 = 0,
+                b: Vec<T> = ::core::default::Default::default(),
+                c: T,
+                d: bool = true,
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn generates_methods() {
+    let expanded =
+        auto_default_core::expand(tokens("is_default"), tokens("struct Foo { a: u8 }")).unwrap();
+
+    assert!(expanded.to_string().contains("fn is_default"));
+}
+
+#[test]
+fn reports_errors() {
+    let error =
+        auto_default_core::expand(tokens("unknown"), tokens("struct Foo { a: u8 }")).unwrap_err();
+
+    assert_eq!(error.to_string(), "[AD001] unknown argument `unknown`");
+
+    // the item is emitted along with the error
+    let compile_error = error.to_compile_error().to_string();
+    assert!(compile_error.starts_with("struct Foo"));
+    assert!(compile_error.contains("compile_error"));
+
+    let error = auto_default_core::expand(TokenStream::new(), tokens("fn foo() {}")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "[AD006] `#[auto_default]` does not support functions\n\
+         help: `#[auto_default]` supports structs with named fields, \
+         and enums whose variants have named fields"
+    );
+
+    // notes and help are added to the message
+    let error = auto_default_core::expand(
+        TokenStream::new(),
+        tokens("struct Foo { #[auto_default(skip)] #[auto_default(skip)] a: u8 }"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "[AD002] duplicate `#[auto_default(skip)]`\nnote: first `#[auto_default(skip)]` is here"
+    );
+}
+
+#[test]
+fn normalizes_default_values() {
+    let expanded = auto_default_core::expand(
+        tokens("normalize"),
+        tokens(
+            "
+            struct Foo {
+                a: u8 = Default::default(),
+                b: Vec<u8> = <Vec<u8>>::default(),
+                c: String = String::default(),
+                d: bool = <bool as ::core::default::Default>::default(),
+                e: u16 = std::default::Default::default(),
+                f: u8 = u16::default() as u8,
+                g: u8 = 4,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Foo {
+                a: u8 = ::core::default::Default::default(),
+                b: Vec<u8> = ::core::default::Default::default(),
+                c: String = ::core::default::Default::default(),
+                d: bool = ::core::default::Default::default(),
+                e: u16 = ::core::default::Default::default(),
+                f: u8 = u16::default() as u8,
+                g: u8 = 4,
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn short_default_values() {
+    let expanded = auto_default_core::expand(
+        tokens("short_default, normalize"),
+        tokens(
+            "
+            struct Foo {
+                a: u8,
+                b: String = String::default(),
+                c: bool = Default::default(),
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Foo {
+                a: u8 = Default::default(),
+                b: String = Default::default(),
+                c: bool = Default::default(),
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn clap_default_values() {
+    let expanded = auto_default_core::expand(
+        tokens("clap, infer_literals"),
+        tokens(
+            "
+            struct Cli {
+                #[arg(short)]
+                retries: u8,
+                name: String = String::new(),
+                #[arg(long, default_value_t = 4)]
+                jobs: u8,
+                #[command(flatten)]
+                verbosity: Verbosity,
+                path: Option<String>,
+                files: ::std::vec::Vec<String>,
+                #[auto_default(skip)]
+                input: String,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Cli {
+                #[arg(short)]
+                #[arg(default_value_t = 0)]
+                retries: u8 = 0,
+                #[arg(default_value_t = String::new())]
+                name: String = String::new(),
+                #[arg(long, default_value_t = 4)]
+                jobs: u8 = 0,
+                #[command(flatten)]
+                verbosity: Verbosity = ::core::default::Default::default(),
+                path: Option<String> = ::core::default::Default::default(),
+                files: ::std::vec::Vec<String> = ::core::default::Default::default(),
+                input: String,
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn annotates_fields() {
+    let expanded = auto_default_core::expand(
+        tokens("annotate(serde(default), schemars(default))"),
+        tokens(
+            "
+            struct Foo {
+                a: u8,
+                #[auto_default(skip)]
+                b: u8,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Foo {
+                #[serde(default)]
+                #[schemars(default)]
+                a: u8 = ::core::default::Default::default(),
+                b: u8,
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn reports_misplaced_attribute_macros() {
+    let item = "
+        #[derive(Debug)]
+        #[pin_project::pin_project(project = Projection)]
+        struct Foo {
+            #[pin]
+            a: u8,
+        }
+    ";
+    let error = auto_default_core::expand(TokenStream::new(), tokens(item)).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "[AD004] `#[pin_project]` must be placed above `#[auto_default]`\n\
+         help: `#[pin_project]` can't parse default field values, \
+         so it has to be applied before `#[auto_default]` adds them"
+    );
+
+    // the item is emitted unchanged, so `#[pin_project]` can still parse it
+    let compile_error = error.to_compile_error().to_string();
+    assert!(compile_error.starts_with(&tokens(item).to_string()));
+}
+
+#[test]
+fn keeps_order_of_attributes() {
+    let expanded = auto_default_core::expand(
+        TokenStream::new(),
+        tokens(
+            "
+            #[derive(Default)]
+            #[serde(rename_all = \"kebab-case\")]
+            #[doc = \"Foo\"]
+            struct Foo {
+                #[serde(default)]
+                #[doc = \"a\"]
+                a: u8,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            #[derive(Default)]
+            #[serde(rename_all = \"kebab-case\")]
+            #[doc = \"Foo\"]
+            struct Foo {
+                #[serde(default)]
+                #[doc = \"a\"]
+                a: u8 = ::core::default::Default::default(),
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn implements_default() {
+    let expanded = auto_default_core::expand(
+        tokens("impl_default"),
+        tokens(
+            "
+            struct Foo<T, U> where T: Clone {
+                a: Vec<T>,
+                #[auto_default(skip)]
+                b: U,
+                #[auto_default(skip)]
+                c: u8,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Foo<T, U> where T: Clone {
+                a: Vec<T> = ::core::default::Default::default(),
+                b: U,
+                c: u8,
+            }
+            impl<T, U> ::core::default::Default for Foo<T, U>
+            where
+                T: Clone,
+                U: ::core::default::Default,
+            {
+                fn default() -> Self {
+                    Foo {
+                        b: ::core::default::Default::default(),
+                        c: ::core::default::Default::default(),
+                        ..
+                    }
+                }
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn uses_serde_defaults() {
+    let expanded = auto_default_core::expand(
+        tokens("from_serde, infer_literals"),
+        tokens(
+            "
+            struct Foo {
+                #[serde(rename = \"p\", default = \"default_port\")]
+                port: u16,
+                #[serde(default)]
+                retries: u8,
+                #[serde(default = \"ignored\")]
+                #[auto_default(with = timeout)]
+                timeout: u32,
+                verbose: bool,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Foo {
+                #[serde(rename = \"p\", default = \"default_port\")]
+                port: u16 = default_port(),
+                #[serde(default)]
+                retries: u8 = ::core::default::Default::default(),
+                #[serde(default = \"ignored\")]
+                timeout: u32 = timeout(),
+                verbose: bool = false,
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn exports_serde_defaults() {
+    let expanded = auto_default_core::expand(
+        tokens("serde_defaults"),
+        tokens(
+            "
+            struct Foo<T> {
+                port: u16 = 8080,
+                #[auto_default(with = timeout)]
+                timeout: u32,
+                #[serde(default = \"default_host\")]
+                host: String = default_host(),
+                retries: u8,
+                #[auto_default(skip)]
+                items: T,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Foo<T> {
+                #[serde(default = \"Foo::<T>::__port_field_default\")]
+                port: u16 = 8080,
+                #[serde(default = \"Foo::<T>::__timeout_field_default\")]
+                timeout: u32 = timeout(),
+                #[serde(default = \"default_host\")]
+                host: String = default_host(),
+                retries: u8 = ::core::default::Default::default(),
+                items: T,
+            }
+            impl<T> Foo<T> {
+                #[doc(hidden)]
+                fn __port_field_default() -> u16 { 8080 }
+                #[doc(hidden)]
+                fn __timeout_field_default() -> u32 { timeout() }
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn reads_helper_attributes() {
+    let expanded = auto_default_core::expand(
+        tokens("helper = adf"),
+        tokens(
+            "
+            struct Foo {
+                #[adf(skip)]
+                a: u8,
+                #[auto_default(other)]
+                b: u8,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    // `#[auto_default(...)]` is left to the other macro
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            struct Foo {
+                a: u8,
+                #[auto_default(other)]
+                b: u8 = ::core::default::Default::default(),
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn translates_foreign_derives() {
+    let expanded = auto_default_core::expand(
+        tokens("from_derives"),
+        tokens(
+            r#"
+            #[derive(Debug, SmartDefault)]
+            struct Smart {
+                #[default(4)]
+                a: u8,
+                #[default(_code = "[1, 2]")]
+                b: [u8; 2],
+                c: u8,
+            }
+            "#,
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            #[derive(Debug, ::core::default::Default)]
+            struct Smart {
+                a: u8 = 4,
+                b: [u8; 2] = [1, 2],
+                c: u8 = ::core::default::Default::default(),
+            }
+            "
+        )
+        .to_string()
+    );
+
+    let expanded = auto_default_core::expand(
+        tokens("from_derives"),
+        tokens(
+            r#"
+            #[derive(Derivative)]
+            #[derivative(Debug, Default)]
+            enum Theme {
+                Light,
+                #[derivative(Default)]
+                Dark {
+                    #[derivative(Default(value = "u8::MAX"))]
+                    contrast: u8,
+                },
+            }
+            "#,
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            #[derive(Derivative)]
+            #[derivative(Debug)]
+            #[derive(::core::default::Default)]
+            enum Theme {
+                Light,
+                #[default]
+                Dark {
+                    contrast: u8 = u8::MAX,
+                },
+            }
+            "
+        )
+        .to_string()
+    );
+}
+
+#[test]
+fn generates_wasm_constructor() {
+    let expanded = auto_default_core::expand(
+        tokens("wasm(setters)"),
+        tokens(
+            "
+            pub struct Config {
+                port: u16 = 8080,
+                #[auto_default(skip)]
+                token: String,
+            }
+            ",
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded.to_string(),
+        tokens(
+            "
+            pub struct Config {
+                port: u16 = 8080,
+                token: String,
+            }
+            #[::wasm_bindgen::prelude::wasm_bindgen]
+            impl Config {
+                #[wasm_bindgen(constructor)]
+                pub fn new() -> Config {
+                    Config { token: ::core::default::Default::default(), .. }
+                }
+                #[wasm_bindgen(setter)]
+                pub fn set_token(&mut self, token: String) {
+                    self.token = token;
+                }
+            }
+            "
+        )
+        .to_string()
+    );
+    let error = auto_default_core::expand(tokens("wasm(getters)"), tokens("struct Foo { a: u8 }"))
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "[AD001] unknown option `getters`, expected `setters`"
+    );
+}
@@ -0,0 +1,98 @@
+//! The policy is read from the environment, so these tests are in their own process
+
+use proc_macro2::TokenStream;
+
+fn tokens(code: &str) -> TokenStream {
+    code.parse().unwrap()
+}
+
+/// The tokens of `code`, ignoring their spacing
+fn without_spaces(code: &str) -> String {
+    code.split_whitespace().collect()
+}
+
+#[test]
+fn policy_file() {
+    // SAFETY: this is the only test in this process
+    unsafe { std::env::set_var("AUTO_DEFAULT_CONFIG", "tests/policy/auto_default.toml") };
+
+    let expanded = auto_default_core::expand(
+        TokenStream::new(),
+        tokens(
+            "
+            struct Foo {
+                fd: RawFd,
+                handle: Option<Handle>,
+                other: Option<Other>,
+                timeout: std::time::Duration,
+                bytes: Vec<u8>,
+                map: HashMap<u8, u8>,
+                label: Label,
+                #[auto_default(skip)]
+                skipped: Duration,
+            }
+            ",
+        ),
+    )
+    .unwrap()
+    .to_string();
+
+    let (item, tracking) = expanded.split_once("const _").unwrap();
+    assert_eq!(
+        without_spaces(item),
+        without_spaces(
+            "
+            struct Foo {
+                fd: RawFd,
+                handle: Option<Handle>,
+                other: Option<Other> = ::core::default::Default::default(),
+                timeout: std::time::Duration = Duration::ZERO,
+                bytes: Vec<u8> = Vec::new(),
+                map: HashMap<u8, u8>,
+                label: Label = Label::new(\"a, \\\"b\\\"\"),
+                skipped: Duration,
+            }
+            "
+        )
+    );
+    // `cargo` compiles the crate again when the policy file changes
+    assert!(tracking.contains("include_str"));
+    assert!(tracking.contains("auto_default.toml"));
+
+    // strict = true
+    let error = auto_default_core::expand(
+        TokenStream::new(),
+        tokens("struct Foo { a: u8 = Default::default() }"),
+    )
+    .unwrap_err();
+    assert!(error.to_string().starts_with("[AD008]"), "{error}");
+
+    // warn_redundant still turns it into a warning
+    auto_default_core::expand(
+        tokens("warn_redundant"),
+        tokens("struct Foo { a: u8 = Default::default() }"),
+    )
+    .unwrap();
+
+    unsafe { std::env::set_var("AUTO_DEFAULT_CONFIG", "tests/policy/invalid.toml") };
+    let error =
+        auto_default_core::expand(TokenStream::new(), tokens("struct Foo { a: u8 }")).unwrap_err();
+    assert!(
+        error.to_string().starts_with("[AD001] invalid policy file"),
+        "{error}"
+    );
+    assert!(
+        error
+            .to_string()
+            .ends_with("line 2: `strict` must be `true` or `false`"),
+        "{error}"
+    );
+
+    unsafe { std::env::set_var("AUTO_DEFAULT_CONFIG", "tests/policy/missing.toml") };
+    let error =
+        auto_default_core::expand(TokenStream::new(), tokens("struct Foo { a: u8 }")).unwrap_err();
+    assert!(
+        error.to_string().starts_with("[AD001] invalid policy file"),
+        "{error}"
+    );
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "testing")]
+
+use auto_default_core::expand_to_string;
+
+#[test]
+fn expands_marked_items() {
+    let expanded = expand_to_string(
+        "
+        /// Not marked
+        #[derive(Debug)]
+        struct Unchanged {
+            a: u8,
+        }
+
+        #[auto_default]
+        #[derive(Debug)]
+        pub enum Shape {
+            Circle { radius: f32 },
+            Point,
+        }
+
+        #[auto_default(infer_literals, reset)]
+        struct Settings<T> {
+            volume: u8 = 4,
+            items: Vec<T>,
+        }
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded,
+        "\
+#[doc = \" Not marked\"]
+#[derive(Debug)]
+struct Unchanged {
+    a: u8,
+}
+#[derive(Debug)]
+pub enum Shape {
+    Circle {
+        radius: f32 = ::core::default::Default::default()
+    },
+    Point,
+}
+struct Settings<T> {
+    volume: u8 = 4,
+    items: Vec<T> = ::core::default::Default::default(),
+}
+impl<T> Settings<T> {
+    #[doc = \"Resets every field to its default value\"]
+    pub fn reset_to_default(&mut self) {
+        *self = Self { .. };
+    }
+    #[doc = \"Resets `volume` to its default value\"]
+    pub fn reset_volume(&mut self) {
+        self.volume = 4;
+    }
+    #[doc = \"Resets `items` to its default value\"]
+    pub fn reset_items(&mut self) {
+        self.items = ::core::default::Default::default();
+    }
+}"
+    );
+}
+
+#[test]
+fn reports_errors() {
+    let error = expand_to_string("#[auto_default(unknown)] struct Foo { a: u8 }").unwrap_err();
+    assert_eq!(error.to_string(), "[AD001] unknown argument `unknown`");
+
+    assert!(expand_to_string("struct Foo {").is_err());
+}
+
+#[test]
+fn separates_colons_from_paths() {
+    let expanded = expand_to_string(
+        "
+        #[auto_default(impl_default, assert_default, is_default)]
+        struct Cache<T> {
+            value: Option<T>,
+            #[auto_default(skip)]
+            hits: u32,
+            #[auto_default(runtime = vec![1])]
+            ids: Vec<u8>,
+        }
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded,
+        "\
+struct Cache<T> {
+    value: Option<T> = ::core::default::Default::default(),
+    hits: u32,
+    ids: Vec<u8>,
+}
+impl<T> Cache<T> {
+    #[doc = \"Returns `true` if every field that has a default value is equal to it\"]
+    pub fn is_default(&self) -> bool where Option<T>: ::core::cmp::PartialEq,
+    {
+        {
+            let default: Option<T> = ::core::default::Default::default();
+            if self.value != default {
+                return false;
+            }
+        }
+        true
+    }
+}
+const _: () = { fn assert_default < T: ::core::default::Default > () {} fn assert_fields < T > () { assert_default::<Option<T>>(); } };
+impl<T> ::core::default::Default for Cache<T> {
+    fn default() -> Self {
+        Cache {
+            hits: ::core::default::Default::default(),
+            ids: vec![1],
+            ..
+        }
+    }
+}"
+    );
+}
+
+#[test]
+fn fences_default_values_with_backticks() {
+    let expanded = expand_to_string(
+        r#"
+        #[auto_default(doc_defaults)]
+        struct Markdown {
+            code: &'static str = "`a` and ``b``",
+            tick: char = '`',
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        expanded,
+        r#"struct Markdown {
+    #[doc = ""]
+    #[doc = "Defaults to ```\"`a` and ``b``\"```"]
+    code: &'static str = "`a` and ``b``",
+    #[doc = ""]
+    #[doc = "Defaults to ``'`'``"]
+    tick: char = '`',
+}"#
+    );
+}
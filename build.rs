@@ -0,0 +1,61 @@
+//! Checks if the compiler supports the unstable APIs of `proc_macro` that the macros use
+//!
+//! If it does, `auto_default_nightly` is set, and the macros report errors and warnings with
+//! `proc_macro::Diagnostic`. Otherwise, such as on stable, errors are reported with
+//! `compile_error!`, and warnings are not reported
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Uses every unstable API that the macros need
+const PROBE: &str = r#"
+#![feature(proc_macro_diagnostic)]
+#![feature(proc_macro_span)]
+#![feature(proc_macro_tracked_env)]
+#![feature(proc_macro_tracked_path)]
+
+extern crate proc_macro;
+
+use proc_macro::{Diagnostic, Level, Span};
+
+pub fn probe(span: Span) -> Option<Span> {
+    Diagnostic::spanned(span, Level::Warning, "")
+        .span_note(span, "")
+        .help("")
+        .emit();
+    let _ = proc_macro::tracked::env_var("");
+    proc_macro::tracked::path(std::path::Path::new(""));
+    span.join(span)
+}
+"#;
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(auto_default_nightly)");
+    println!("cargo::rerun-if-changed=build.rs");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("set by cargo"));
+    let probe = out_dir.join("probe.rs");
+    fs::write(&probe, PROBE).expect("`OUT_DIR` is writable");
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let compiles = Command::new(rustc)
+        .args([
+            "--edition",
+            "2024",
+            "--crate-type",
+            "lib",
+            "--emit",
+            "metadata",
+        ])
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&probe)
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+
+    if compiles {
+        println!("cargo::rustc-cfg=auto_default_nightly");
+    }
+}
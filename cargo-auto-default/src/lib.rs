@@ -0,0 +1,632 @@
+//! `cargo auto-default` rewrites structs that spell out `= Default::default()` on many of their
+//! fields to use `#[auto_default]` instead:
+//!
+//! ```text
+//! struct Config {
+//!     retries: u8 = Default::default(),
+//!     verbose: bool = Default::default(),
+//!     path: String,
+//! }
+//! ```
+//!
+//! The above is rewritten into this:
+//!
+//! ```text
+//! #[auto_default::auto_default]
+//! struct Config {
+//!     retries: u8,
+//!     verbose: bool,
+//!     #[auto_default(skip)]
+//!     path: String,
+//! }
+//! ```
+//!
+//! Fields without a default value are marked `#[auto_default(skip)]`, so that the struct
+//! means the same as before. Only the rewritten parts of the source are changed,
+//! so comments and formatting are kept as they are.
+//!
+//! `cargo auto-default --eject` does the reverse, and replaces every item marked
+//! `#[auto_default]` with what it expands to
+use std::fmt::{self, Write as _};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Delimiter, Group, Ident, LexError, Spacing, Span, TokenStream, TokenTree};
+
+/// An item that is rewritten
+pub struct Rewrite {
+    /// Lines of the item in the original source, counting from 0
+    pub lines: Range<usize>,
+    /// New text of the lines
+    pub text: String,
+    /// Bytes of the lines in the original source
+    bytes: Range<usize>,
+}
+
+/// Finds the structs in `source` with at least `min_fields` fields whose default value is
+/// `Default::default()`, and rewrites them to use `#[auto_default]`
+///
+/// Structs that already have `#[auto_default]` are skipped
+pub fn migrate(source: &str, min_fields: usize) -> Result<Vec<Rewrite>, Error> {
+    Migration::new(
+        source,
+        Mode::Migrate {
+            min_fields: min_fields.max(1),
+        },
+    )
+    .run()
+}
+
+/// Replaces the items in `source` that are marked `#[auto_default]` with what they expand to,
+/// and removes `use auto_default::...;` imports
+///
+/// `policy` is the policy file of the crate, which [`auto_default_core::policy_path`] finds.
+/// Comments inside of the expanded items are not kept, since the items are formatted again
+pub fn eject(source: &str, policy: Option<&Path>) -> Result<Vec<Rewrite>, Error> {
+    Migration::new(
+        source,
+        Mode::Eject {
+            policy: policy.map(Path::to_path_buf),
+        },
+    )
+    .run()
+}
+
+/// `source` with the `rewrites` applied
+pub fn apply(source: &str, rewrites: &[Rewrite]) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut end = 0;
+    for rewrite in rewrites {
+        output.push_str(&source[end..rewrite.bytes.start]);
+        output.push_str(&rewrite.text);
+        end = rewrite.bytes.end;
+    }
+    output.push_str(&source[end..]);
+    output
+}
+
+/// Unified diff of the `rewrites` of the file at `path`, which contains `source`
+pub fn diff(path: &str, source: &str, rewrites: &[Rewrite]) -> String {
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+
+    // How many lines the previous rewrites have added
+    let mut offset = 0_isize;
+
+    for rewrite in rewrites {
+        let old = source[rewrite.bytes.clone()].lines().collect::<Vec<_>>();
+        let new = rewrite.text.lines().collect::<Vec<_>>();
+
+        let _ = writeln!(
+            diff,
+            "@@ -{},{} +{},{} @@",
+            rewrite.lines.start + 1,
+            old.len(),
+            rewrite.lines.start.saturating_add_signed(offset) + 1,
+            new.len()
+        );
+        for line in &old {
+            let _ = writeln!(diff, "-{line}");
+        }
+        for line in &new {
+            let _ = writeln!(diff, "+{line}");
+        }
+
+        offset += new.len() as isize - old.len() as isize;
+    }
+
+    diff
+}
+
+/// Why a file can't be rewritten
+#[derive(Debug)]
+pub enum Error {
+    /// The file is not valid Rust
+    Parse(LexError),
+    /// An item marked `#[auto_default]` can't be expanded
+    Expand(auto_default_core::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "{error}"),
+            Self::Expand(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// What [`Migration`] does to the items
+enum Mode {
+    /// Rewrite structs to use `#[auto_default]`
+    Migrate {
+        /// How many fields need `= Default::default()` for the struct to be rewritten
+        min_fields: usize,
+    },
+    /// Replace items marked `#[auto_default]` with what they expand to
+    Eject {
+        /// Policy file of the crate
+        policy: Option<PathBuf>,
+    },
+}
+
+/// State of [`migrate`] and [`eject`]
+struct Migration<'a> {
+    /// The whole file
+    source: &'a str,
+    mode: Mode,
+    /// Rewritten items, in the order they appear in `source`
+    rewrites: Vec<Rewrite>,
+    /// The first item that failed to expand
+    error: Option<auto_default_core::Error>,
+}
+
+/// Text that replaces `range` of the source
+struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+
+/// A named field
+///
+/// ```text
+/// #[attr] pub field: Type = value,
+/// ```
+struct Field {
+    /// Where the field starts, including its attributes
+    start: usize,
+    /// Where the type of the field ends
+    ty_end: usize,
+    /// Type of the field, without whitespace
+    ty: String,
+    /// Default value of the field without whitespace, and where it ends
+    value: Option<(String, usize)>,
+}
+
+impl<'a> Migration<'a> {
+    fn new(source: &'a str, mode: Mode) -> Self {
+        Self {
+            source,
+            mode,
+            rewrites: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn run(mut self) -> Result<Vec<Rewrite>, Error> {
+        let tokens = self.source.parse::<TokenStream>().map_err(Error::Parse)?;
+        self.visit(tokens);
+        match self.error {
+            Some(error) => Err(Error::Expand(error)),
+            None => Ok(self.rewrites),
+        }
+    }
+
+    /// Looks for items in `tokens`, and in every group inside of them,
+    /// such as the bodies of modules and functions
+    fn visit(&mut self, tokens: TokenStream) {
+        let tokens = tokens.into_iter().collect::<Vec<_>>();
+
+        // First token of the current item, which is where its attributes start
+        let mut item_start = 0;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            match &tokens[i] {
+                // #![feature(default_field_values)]
+                // ^^ inner attributes don't belong to the next item
+                TokenTree::Punct(pound)
+                    if pound.as_char() == '#'
+                        && tokens.get(i + 1).is_some_and(|tt| is_punct(tt, '!')) =>
+                {
+                    i += 3;
+                    item_start = i;
+                    continue;
+                }
+                // struct Foo<T> where T: Trait { ... }
+                // enum Foo { ... }
+                TokenTree::Ident(kw) if kw == "struct" || kw == "enum" => {
+                    let Some(TokenTree::Ident(_)) = tokens.get(i + 1) else {
+                        i += 1;
+                        continue;
+                    };
+
+                    // Tuple and unit structs end with `;`, and have no named fields
+                    let mut end = i + 2;
+                    let body = loop {
+                        match tokens.get(end) {
+                            Some(TokenTree::Group(group))
+                                if group.delimiter() == Delimiter::Brace =>
+                            {
+                                break Some(group);
+                            }
+                            Some(tt) if is_punct(tt, ';') => break None,
+                            Some(_) => end += 1,
+                            None => break None,
+                        }
+                    };
+
+                    if let Some(body) = body {
+                        let prefix = &tokens[item_start..i];
+                        match self.mode {
+                            Mode::Migrate { min_fields } if kw == "struct" => {
+                                self.rewrite_struct(prefix, kw, body, min_fields);
+                            }
+                            Mode::Migrate { .. } => {}
+                            Mode::Eject { .. } => self.eject_item(prefix, kw, body),
+                        }
+                    }
+
+                    i = end + 1;
+                    item_start = i;
+                    continue;
+                }
+                // use auto_default::auto_default;
+                TokenTree::Ident(kw) if kw == "use" && matches!(self.mode, Mode::Eject { .. }) => {
+                    let mut end = i + 1;
+                    while tokens.get(end).is_some_and(|tt| !is_punct(tt, ';')) {
+                        end += 1;
+                    }
+
+                    let imports_auto_default = tokens[i + 1..end]
+                        .iter()
+                        .find(|tt| !is_punct(tt, ':'))
+                        .is_some_and(
+                            |tt| matches!(tt, TokenTree::Ident(krate) if krate == "auto_default"),
+                        );
+                    if imports_auto_default && let Some(semi) = tokens.get(end) {
+                        let start = tokens[item_start..=i][0].span().byte_range().start;
+                        self.rewrite(start..semi.span().byte_range().end, String::new());
+                    }
+
+                    i = end + 1;
+                    item_start = i;
+                    continue;
+                }
+                TokenTree::Punct(semi) if semi.as_char() == ';' => item_start = i + 1,
+                TokenTree::Group(group) => {
+                    self.visit(group.stream());
+                    if group.delimiter() == Delimiter::Brace {
+                        item_start = i + 1;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// Rewrites the struct with `fields`, if at least `min_fields` of its fields have
+    /// the default value `Default::default()`
+    ///
+    /// `prefix` contains the attributes and the visibility of the struct, which come before
+    /// the `struct` keyword `kw`
+    fn rewrite_struct(
+        &mut self,
+        prefix: &[TokenTree],
+        kw: &Ident,
+        fields: &Group,
+        min_fields: usize,
+    ) {
+        let attrs = attrs(prefix);
+        if attrs.iter().any(|attr| is_auto_default(attr)) {
+            return;
+        }
+
+        let parsed = parse_fields(fields);
+        if parsed
+            .iter()
+            .filter(|field| {
+                field
+                    .value
+                    .as_ref()
+                    .is_some_and(|(value, _)| is_default_call(value, &field.ty))
+            })
+            .count()
+            < min_fields
+        {
+            return;
+        }
+
+        // #[auto_default::auto_default] goes above every attribute except for documentation,
+        // so that attribute macros are applied to the struct with default field values
+        let insert_at = match attrs.iter().position(|attr| !is_doc(attr)) {
+            Some(attr) => start(prefix[attr * 2].span()),
+            // pub struct Foo
+            // ^^^
+            None => prefix
+                .get(attrs.len() * 2)
+                .map_or_else(|| start(kw.span()), |tt| start(tt.span())),
+        };
+
+        let mut edits = vec![Edit {
+            range: insert_at..insert_at,
+            text: self.attribute_before("#[auto_default::auto_default]", insert_at),
+        }];
+
+        for field in &parsed {
+            match &field.value {
+                // field: Type = Default::default(),
+                //            ^^^^^^^^^^^^^^^^^^^^^^ removed
+                Some((value, end)) if is_default_call(value, &field.ty) => edits.push(Edit {
+                    range: field.ty_end..*end,
+                    text: String::new(),
+                }),
+                Some(_) => {}
+                // Fields without a default value keep not having one
+                None => edits.push(Edit {
+                    range: field.start..field.start,
+                    text: self.attribute_before("#[auto_default(skip)]", field.start),
+                }),
+            }
+        }
+
+        let start = prefix.first().map_or(insert_at, |tt| start(tt.span()));
+        let end = fields.span().byte_range().end;
+
+        let mut text = self.source[start..end].to_string();
+        for edit in edits.iter().rev() {
+            text.replace_range(edit.range.start - start..edit.range.end - start, &edit.text);
+        }
+
+        self.rewrite(start..end, text);
+    }
+
+    /// Replaces the item with `body`, if it is marked `#[auto_default]`, with what it expands to
+    ///
+    /// `prefix` contains the attributes and the visibility of the item, which come before
+    /// the keyword `kw`
+    fn eject_item(&mut self, prefix: &[TokenTree], kw: &Ident, body: &Group) {
+        let attrs = attrs(prefix);
+        if !attrs.iter().any(|attr| is_auto_default(attr)) {
+            return;
+        }
+
+        // Documentation before the other attributes is kept as it is, instead of
+        // being expanded into `#[doc = "..."]`
+        let docs = attrs.iter().take_while(|attr| is_doc(attr)).count();
+        let start = prefix
+            .get(docs * 2)
+            .map_or_else(|| start(kw.span()), |tt| start(tt.span()));
+        let end = body.span().byte_range().end;
+
+        let policy = match &self.mode {
+            Mode::Eject { policy } => policy.as_deref(),
+            Mode::Migrate { .. } => None,
+        };
+        let expanded =
+            match auto_default_core::expand_to_string_with_policy(&self.source[start..end], policy)
+            {
+                Ok(expanded) => expanded,
+                Err(error) => {
+                    self.error.get_or_insert(error);
+                    return;
+                }
+            };
+
+        // The expanded item is indented as much as the line it starts on
+        let line = &self.source[self.line_start(start)..];
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let mut text = String::new();
+        for (i, line) in expanded.lines().enumerate() {
+            if i > 0 {
+                text.push('\n');
+                if !line.is_empty() {
+                    text.push_str(indent);
+                }
+            }
+            text.push_str(line);
+        }
+
+        self.rewrite(start..end, text);
+    }
+
+    /// Replaces `range` of the source with `text`
+    ///
+    /// The rewrite is extended to whole lines, so that it can be shown in a diff. If nothing is
+    /// left on the lines, they are removed
+    fn rewrite(&mut self, range: Range<usize>, text: String) {
+        let bytes = self.line_start(range.start)..self.line_end(range.end);
+
+        // Another item on the same lines was already rewritten
+        if self
+            .rewrites
+            .last()
+            .is_some_and(|last| last.bytes.end > bytes.start)
+        {
+            return;
+        }
+
+        let mut text = format!(
+            "{}{text}{}",
+            &self.source[bytes.start..range.start],
+            &self.source[range.end..bytes.end]
+        );
+        if text.trim().is_empty() {
+            text.clear();
+        }
+
+        self.rewrites.push(Rewrite {
+            lines: self.line(bytes.start)..self.line(bytes.end - 1) + 1,
+            text,
+            bytes,
+        });
+    }
+
+    /// `attr` followed by a new line with the same indentation, if `position` starts its line.
+    /// Otherwise, `attr` followed by a space
+    fn attribute_before(&self, attr: &str, position: usize) -> String {
+        let indent = &self.source[self.line_start(position)..position];
+        if indent.trim().is_empty() {
+            format!("{attr}\n{indent}")
+        } else {
+            format!("{attr} ")
+        }
+    }
+
+    /// Start of the line that contains `position`
+    fn line_start(&self, position: usize) -> usize {
+        self.source[..position]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1)
+    }
+
+    /// End of the line that contains `position`, including the `\n`
+    fn line_end(&self, position: usize) -> usize {
+        self.source[position..]
+            .find('\n')
+            .map_or(self.source.len(), |newline| position + newline + 1)
+    }
+
+    /// Line that contains `position`, counting from 0
+    fn line(&self, position: usize) -> usize {
+        self.source[..position].matches('\n').count()
+    }
+}
+
+/// Where the token with `span` starts in the source
+fn start(span: Span) -> usize {
+    span.byte_range().start
+}
+
+/// Parses the named fields inside of `group`
+fn parse_fields(group: &Group) -> Vec<Field> {
+    let tokens = group.stream().into_iter().collect::<Vec<_>>();
+    let mut fields = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let start = start(tokens[i].span());
+
+        // #[attr] pub field:
+        // ^^^^^^^^^^^^^^^^^^
+        while i < tokens.len() && !is_punct(&tokens[i], ':') {
+            i += 1;
+        }
+        i += 1;
+
+        // field: HashMap<K, V> = value,
+        //        ^^^^^^^^^^^^^
+        let ty_start = i;
+        let mut angle_depth = 0_usize;
+        while let Some(tt) = tokens.get(i) {
+            match tt {
+                TokenTree::Punct(punct)
+                    if angle_depth == 0 && matches!(punct.as_char(), ',' | '=') =>
+                {
+                    break;
+                }
+                TokenTree::Punct(punct) if punct.as_char() == '<' => angle_depth += 1,
+                // `->` is not a closing angle bracket
+                TokenTree::Punct(punct)
+                    if punct.as_char() == '>'
+                        && !matches!(tokens.get(i.wrapping_sub(1)), Some(TokenTree::Punct(arrow)) if arrow.as_char() == '-' && arrow.spacing() == Spacing::Joint) =>
+                {
+                    angle_depth = angle_depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        let Some(last) = tokens.get(ty_start..i).and_then(<[_]>::last) else {
+            break;
+        };
+        let ty_end = last.span().byte_range().end;
+        let ty = without_whitespace(&tokens[ty_start..i]);
+
+        // field: Type = value,
+        //               ^^^^^
+        let value = if tokens.get(i).is_some_and(|tt| is_punct(tt, '=')) {
+            i += 1;
+            let value_start = i;
+            while tokens.get(i).is_some_and(|tt| !is_punct(tt, ',')) {
+                i += 1;
+            }
+            tokens[value_start..i].last().map(|last| {
+                (
+                    without_whitespace(&tokens[value_start..i]),
+                    last.span().byte_range().end,
+                )
+            })
+        } else {
+            None
+        };
+
+        fields.push(Field {
+            start,
+            ty_end,
+            ty,
+            value,
+        });
+
+        // field: Type = value,
+        //                    ^
+        i += 1;
+    }
+
+    fields
+}
+
+/// Whether `value` is a call to `Default::default()` for a field of type `ty`
+///
+/// Both of them have no whitespace
+fn is_default_call(value: &str, ty: &str) -> bool {
+    let ty_name = ty.split('<').next().unwrap_or(ty);
+    matches!(
+        value,
+        "Default::default()"
+            | "core::default::Default::default()"
+            | "::core::default::Default::default()"
+            | "std::default::Default::default()"
+            | "::std::default::Default::default()"
+    ) || value
+        .strip_suffix("::default()")
+        .is_some_and(|path| path == ty || path == ty_name || path == format!("<{ty}>"))
+}
+
+/// Attributes at the start of `prefix`
+///
+/// ```text
+/// #[derive(Default)] pub struct Foo
+///   ^^^^^^^^^^^^^^^
+/// ```
+fn attrs(prefix: &[TokenTree]) -> Vec<&Group> {
+    prefix
+        .chunks(2)
+        .map_while(|attr| match attr {
+            [TokenTree::Punct(pound), TokenTree::Group(attr)] if pound.as_char() == '#' => {
+                Some(attr)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the attribute is `#[auto_default]`, or `#[auto_default::auto_default(...)]`
+fn is_auto_default(attr: &Group) -> bool {
+    attr.stream()
+        .into_iter()
+        .take_while(|tt| matches!(tt, TokenTree::Ident(_) | TokenTree::Punct(_)))
+        .any(|tt| matches!(tt, TokenTree::Ident(ident) if ident == "auto_default"))
+}
+
+/// Whether the attribute is documentation, such as a `///` comment
+fn is_doc(attr: &Group) -> bool {
+    matches!(attr.stream().into_iter().next(), Some(TokenTree::Ident(doc)) if doc == "doc")
+}
+
+fn is_punct(tt: &TokenTree, c: char) -> bool {
+    matches!(tt, TokenTree::Punct(punct) if punct.as_char() == c)
+}
+
+/// `Vec < u8 >` => `Vec<u8>`
+fn without_whitespace(tokens: &[TokenTree]) -> String {
+    tokens
+        .iter()
+        .cloned()
+        .collect::<TokenStream>()
+        .to_string()
+        .split_whitespace()
+        .collect()
+}
@@ -0,0 +1,182 @@
+//! `cargo auto-default` rewrites the structs of a crate to use `#[auto_default]`,
+//! or replaces `#[auto_default]` with what it expands to
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fs, io};
+
+const USAGE: &str = "\
+Rewrites structs that spell out `= Default::default()` on their fields to use `#[auto_default]`
+
+Usage: cargo auto-default [OPTIONS] [PATH]...
+
+Arguments:
+  [PATH]...  Files or directories to rewrite [default: src]
+
+Options:
+  --dry-run           Print a diff of the changes, without writing them
+  --eject             Replace the items marked `#[auto_default]` with what they
+                      expand to, so the crate no longer depends on `auto-default`
+  --min-fields <N>    Only rewrite structs with at least N fields that are
+                      `= Default::default()` [default: 2]
+  -h, --help          Print help";
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).peekable();
+
+    // `cargo auto-default` runs `cargo-auto-default auto-default`
+    args.next_if(|arg| arg == "auto-default");
+
+    let mut dry_run = false;
+    let mut eject = false;
+    let mut min_fields = 2;
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--eject" => eject = true,
+            "--min-fields" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => min_fields = n,
+                None => {
+                    eprintln!("error: `--min-fields` expects a number\n\n{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("error: unknown option `{arg}`\n\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if paths.is_empty() {
+        paths.push(PathBuf::from("src"));
+    }
+
+    let mut files = Vec::new();
+    for path in &paths {
+        if let Err(error) = collect_files(path, &mut files) {
+            eprintln!("error: failed to read `{}`: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut items = 0;
+    let mut changed_files = 0;
+
+    for file in files {
+        let source = match fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!("error: failed to read `{}`: {error}", file.display());
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let rewrites = if eject {
+            let policy = manifest_dir(&file).and_then(|dir| auto_default_core::policy_path(&dir));
+            cargo_auto_default::eject(&source, policy.as_deref())
+        } else {
+            cargo_auto_default::migrate(&source, min_fields)
+        };
+        let rewrites = match rewrites {
+            Ok(rewrites) => rewrites,
+            Err(cargo_auto_default::Error::Expand(error)) => {
+                eprintln!(
+                    "error: failed to expand an item in `{}`: {error}",
+                    file.display()
+                );
+                return ExitCode::FAILURE;
+            }
+            Err(error) => {
+                eprintln!(
+                    "warning: skipping `{}`, because it can't be parsed: {error}",
+                    file.display()
+                );
+                continue;
+            }
+        };
+        if rewrites.is_empty() {
+            continue;
+        }
+
+        // Removed imports are not items
+        items += rewrites
+            .iter()
+            .filter(|rewrite| !rewrite.text.is_empty())
+            .count();
+        changed_files += 1;
+
+        if dry_run {
+            print!(
+                "{}",
+                cargo_auto_default::diff(&file.display().to_string(), &source, &rewrites)
+            );
+        } else if let Err(error) = fs::write(&file, cargo_auto_default::apply(&source, &rewrites)) {
+            eprintln!("error: failed to write `{}`: {error}", file.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    match (eject, dry_run) {
+        (false, true) => eprintln!("Would rewrite {items} structs in {changed_files} files"),
+        (false, false) => {
+            eprintln!("Rewrote {items} structs in {changed_files} files");
+            if items > 0 {
+                eprintln!("The crate needs `auto-default` in its `[dependencies]`");
+            }
+        }
+        (true, true) => eprintln!("Would eject {items} items in {changed_files} files"),
+        (true, false) => {
+            eprintln!("Ejected {items} items in {changed_files} files");
+            eprintln!("The crate no longer needs `auto-default` in its `[dependencies]`");
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Adds `path` to `files` if it is a file, or every `.rs` file inside of it if it is a directory
+///
+/// The `target` directory and hidden directories are skipped
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for entry in entries {
+        let skipped = entry
+            .file_name()
+            .is_some_and(|name| name == "target" || name.to_string_lossy().starts_with('.'));
+        if skipped {
+            continue;
+        }
+        if entry.is_dir() || entry.extension().is_some_and(|ext| ext == "rs") {
+            collect_files(&entry, files)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Directory of the `Cargo.toml` of the crate that `file` belongs to
+///
+/// The macro reads the policy file of the crate from this directory
+fn manifest_dir(file: &Path) -> Option<PathBuf> {
+    let file = fs::canonicalize(file).ok()?;
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+        .map(Path::to_path_buf)
+}
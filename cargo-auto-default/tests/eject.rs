@@ -0,0 +1,151 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Ejects `source`, returning the new source
+#[track_caller]
+fn eject(source: &str) -> String {
+    let rewrites = cargo_auto_default::eject(source, None).unwrap();
+    cargo_auto_default::apply(source, &rewrites)
+}
+
+#[test]
+fn expands_marked_items() {
+    assert_eq!(
+        eject(
+            "\
+#![feature(default_field_values)]
+use auto_default::auto_default;
+use std::path::PathBuf;
+
+mod config {
+    /// Configuration
+    #[auto_default::auto_default]
+    pub struct Config {
+        retries: u8,
+        #[auto_default(skip)]
+        path: PathBuf,
+    }
+}
+
+// Not marked
+struct Other {
+    a: u8,
+}
+"
+        ),
+        "\
+#![feature(default_field_values)]
+use std::path::PathBuf;
+
+mod config {
+    /// Configuration
+    pub struct Config {
+        retries: u8 = ::core::default::Default::default(),
+        path: PathBuf,
+    }
+}
+
+// Not marked
+struct Other {
+    a: u8,
+}
+"
+    );
+}
+
+#[test]
+fn reports_invalid_items() {
+    let error = cargo_auto_default::eject(
+        "\
+#[auto_default(unknown)]
+struct Foo {
+    a: u8,
+}
+",
+        None,
+    )
+    .err()
+    .unwrap();
+
+    assert!(matches!(error, cargo_auto_default::Error::Expand(_)));
+}
+
+#[test]
+fn uses_policy() {
+    let source = "\
+#[auto_default]
+struct Foo {
+    timeout: Duration,
+}
+";
+    let rewrites =
+        cargo_auto_default::eject(source, Some(Path::new("tests/eject/auto_default.toml")))
+            .unwrap();
+
+    // The policy file is not included, since the crate no longer uses the macro
+    assert_eq!(
+        cargo_auto_default::apply(source, &rewrites),
+        "\
+struct Foo {
+    timeout: Duration = Duration::ZERO,
+}
+"
+    );
+}
+
+#[test]
+fn ejected_code_compiles() {
+    let ejected = eject(
+        "\
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(impl_default)]
+pub struct Skipped {
+    a: u8,
+    #[auto_default(skip)]
+    b: String,
+    #[auto_default(runtime = vec![1])]
+    c: Vec<u8>,
+}
+
+#[auto_default(assert_default)]
+pub struct Asserted<T> {
+    value: Option<T>,
+}
+
+#[auto_default(is_default, changed_fields)]
+pub struct Generic<T> {
+    value: Option<T>,
+}
+",
+    );
+
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ejected_code_compiles");
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("lib.rs");
+    std::fs::write(&file, &ejected).unwrap();
+
+    let output = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+        .args([
+            "--edition",
+            "2024",
+            "--crate-type",
+            "lib",
+            "--emit",
+            "metadata",
+        ])
+        .arg("--out-dir")
+        .arg(&dir)
+        .arg(&file)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{ejected}\n\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,144 @@
+/// Rewrites `source`, returning the new source
+#[track_caller]
+fn migrate(source: &str) -> String {
+    let rewrites = cargo_auto_default::migrate(source, 2).unwrap();
+    cargo_auto_default::apply(source, &rewrites)
+}
+
+#[test]
+fn rewrites_default_fields() {
+    assert_eq!(
+        migrate(
+            "\
+#![feature(default_field_values)]
+
+/// Configuration
+#[derive(Default)]
+pub(crate) struct Config {
+    /// How many times to retry
+    pub retries: u8 = Default::default(),
+    verbose: bool = bool::default(),
+    items: Vec<u8> = <Vec<u8>>::default(),
+    theme: &'static str = \"dark\",
+    path: String,
+}
+"
+        ),
+        "\
+#![feature(default_field_values)]
+
+/// Configuration
+#[auto_default::auto_default]
+#[derive(Default)]
+pub(crate) struct Config {
+    /// How many times to retry
+    pub retries: u8,
+    verbose: bool,
+    items: Vec<u8>,
+    theme: &'static str = \"dark\",
+    #[auto_default(skip)]
+    path: String,
+}
+"
+    );
+}
+
+#[test]
+fn rewrites_nested_structs() {
+    assert_eq!(
+        migrate(
+            "\
+mod config {
+    fn build() {
+        struct Local<F: Fn() -> u8> { a: u8 = Default::default(), b: F, c: u8 = Default::default() }
+    }
+}
+"
+        ),
+        "\
+mod config {
+    fn build() {
+        #[auto_default::auto_default]
+        struct Local<F: Fn() -> u8> { a: u8, #[auto_default(skip)] b: F, c: u8 }
+    }
+}
+"
+    );
+}
+
+#[test]
+fn keeps_other_structs() {
+    let source = "\
+// Only one field is `Default::default()`
+struct Few {
+    a: u8 = Default::default(),
+    b: u8,
+}
+
+// Already uses `#[auto_default]`
+#[auto_default]
+struct Migrated {
+    a: u8 = Default::default(),
+    b: u8 = Default::default(),
+}
+
+// Default values of other types
+struct Other {
+    a: u8 = Other::default(),
+    b: u8 = Default::default(),
+}
+
+struct Tuple(u8, u8);
+";
+    assert_eq!(migrate(source), source);
+}
+
+#[test]
+fn diffs_rewrites() {
+    let source = "\
+use std::path::PathBuf;
+
+struct Config {
+    retries: u8 = Default::default(),
+    verbose: bool = Default::default(),
+    path: PathBuf,
+}
+
+struct Theme {
+    dark: bool = Default::default(),
+    name: String = Default::default(),
+}
+";
+    let rewrites = cargo_auto_default::migrate(source, 2).unwrap();
+
+    assert_eq!(
+        cargo_auto_default::diff("src/config.rs", source, &rewrites),
+        "\
+--- a/src/config.rs
++++ b/src/config.rs
+@@ -3,5 +3,7 @@
+-struct Config {
+-    retries: u8 = Default::default(),
+-    verbose: bool = Default::default(),
+-    path: PathBuf,
+-}
++#[auto_default::auto_default]
++struct Config {
++    retries: u8,
++    verbose: bool,
++    #[auto_default(skip)]
++    path: PathBuf,
++}
+@@ -9,4 +11,5 @@
+-struct Theme {
+-    dark: bool = Default::default(),
+-    name: String = Default::default(),
+-}
++#[auto_default::auto_default]
++struct Theme {
++    dark: bool,
++    name: String,
++}
+"
+    );
+}
@@ -0,0 +1,1718 @@
+//! Arguments passed to the `#[auto_default(...)]` attribute on the container,
+//! and to `#[auto_default(...)]` attributes on fields and variants
+//!
+//! ```text
+//! #[auto_default(infer_literals)]
+//!                ^^^^^^^^^^^^^^
+//! struct Foo {
+//!     #[auto_default(skip)]
+//!                    ^^^^
+//!     foo: u32,
+//! }
+//! ```
+use proc_macro::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+
+use crate::diagnostic::{CompileError, ErrorCode};
+use crate::expand::{IdentExt, Position, TokenTreeExt};
+use crate::policy::Policy;
+
+/// Options that apply to the entire item
+#[derive(Default)]
+pub struct Args {
+    /// `#[auto_default(infer_literals)]`
+    ///
+    /// Fields with a primitive type get a literal, such as `0` or `false`,
+    /// instead of `Default::default()`
+    pub infer_literals: bool,
+    /// `#[auto_default(known_types)]`
+    ///
+    /// Fields of well-known types from the standard library, such as `Option<T>` or `Vec<T>`,
+    /// get a `const` constructor, such as `None` or `Vec::new()`, instead of `Default::default()`
+    pub known_types: bool,
+    /// `#[auto_default(struct_default)]`
+    ///
+    /// Fields get `Type { .. }` instead of `Default::default()`
+    pub struct_default: bool,
+    /// `#[auto_default(assoc_const)]`
+    ///
+    /// Fields get `<Type>::DEFAULT` instead of `Default::default()`
+    pub assoc_const: bool,
+    /// `#[auto_default(const_default_trait)]`
+    ///
+    /// Fields get `<Type as ::const_default::ConstDefault>::DEFAULT` instead of `Default::default()`
+    pub const_default_trait: bool,
+    /// `#[auto_default(prefer_new)]`
+    ///
+    /// Fields get `<Type>::new()` instead of `Default::default()`
+    pub prefer_new: bool,
+    /// `#[auto_default(with = path)]`
+    ///
+    /// Fields get `path()` instead of `Default::default()`
+    pub with: Option<TokenStream>,
+    /// `#[auto_default(consts = path)]` or `#[auto_default(strict_consts = path)]`
+    ///
+    /// Fields get the constant named after them in the module `path`,
+    /// such as `path::PORT` for `port`, instead of `Default::default()`
+    pub consts: Option<Consts>,
+    /// `#[auto_default(doc_defaults)]`
+    ///
+    /// Fields with a default value get documentation saying what the default is
+    pub doc_defaults: bool,
+    /// `#[auto_default(normalize)]`
+    ///
+    /// Hand-written default values such as `Default::default()` or `Type::default()`
+    /// are replaced with `::core::default::Default::default()`
+    pub normalize: bool,
+    /// `#[auto_default(deny_redundant)]`
+    ///
+    /// Hand-written default values such as `Default::default()` or `Type::default()`,
+    /// which the macro would add anyway, are errors
+    pub deny_redundant: bool,
+    /// `#[auto_default(warn_redundant)]`
+    ///
+    /// Like `deny_redundant`, but reports warnings instead of errors
+    pub warn_redundant: bool,
+    /// `#[auto_default(allow_unused_skip)]`
+    ///
+    /// Don't warn about `#[auto_default(skip)]` on fields that have a default value
+    pub allow_unused_skip: bool,
+    /// `#[auto_default(short_default)]`
+    ///
+    /// Fields get `Default::default()` instead of `::core::default::Default::default()`
+    pub short_default: bool,
+    /// `#[auto_default(skip_variants(A, B))]`
+    ///
+    /// Names of enum variants that are skipped, as if they were marked `#[auto_default(skip)]`
+    pub skip_variants: Vec<Ident>,
+    /// `#[auto_default(skip_fields(prefix = "raw_", names(handle, fd)))]`
+    ///
+    /// Fields that are skipped, as if they were marked `#[auto_default(skip)]`
+    pub skip_fields: SkipFields,
+    /// `#[auto_default(skip_underscored)]`
+    ///
+    /// Fields whose name starts with `_`, such as `_marker`, are skipped
+    pub skip_underscored: bool,
+    /// `#[auto_default(skip_phantom_data)]`
+    ///
+    /// `PhantomData` fields are skipped by their variant and by `skip_fields` or `skip_underscored`,
+    /// instead of getting `PhantomData`
+    pub skip_phantom_data: bool,
+    /// `#[auto_default(alias = adskip)]`
+    ///
+    /// `#[adskip]` on a field or a variant is the same as `#[auto_default(skip)]`
+    pub alias: Option<Ident>,
+    /// `#[auto_default(helper = adf)]`
+    ///
+    /// Options of fields and variants are read from `#[adf(...)]` instead of
+    /// `#[auto_default(...)]`, which is left to other macros
+    pub helper: Option<Ident>,
+    /// `#[auto_default(debug)]`
+    ///
+    /// Print the expanded item to stderr during compilation
+    pub debug: bool,
+    /// `#[auto_default(reset)]`
+    ///
+    /// Generate a `reset_to_default` method, and a `reset_{field}` method for every field
+    /// that has a default value
+    pub reset: bool,
+    /// `#[auto_default(is_default)]`
+    ///
+    /// Generate an `is_default` method, which checks if every field is equal to its default value
+    pub is_default: bool,
+    /// `#[auto_default(changed_fields)]`
+    ///
+    /// Generate a `changed_fields` method, which returns the names of the fields
+    /// that are not equal to their default value
+    pub changed_fields: bool,
+    /// `#[auto_default(with_fn)]`
+    ///
+    /// Generate a `with` function, which creates the default value and changes it with a closure
+    pub with_fn: bool,
+    /// `#[auto_default(clap)]`
+    ///
+    /// Fields with a default value get `#[arg(default_value_t = value)]`
+    pub clap: bool,
+    /// `#[auto_default(annotate(serde(default), other))]`
+    ///
+    /// Attributes added to every field with a default value
+    pub annotate: Vec<TokenStream>,
+    /// `#[auto_default(field_defaults)]`
+    ///
+    /// Generate a `FIELD_DEFAULTS` constant, which contains the default value of every field
+    /// as a string
+    pub field_defaults: bool,
+    /// `#[auto_default(self_test)]`
+    ///
+    /// Generate a test which checks that `Foo { .. }` is equal to `Foo::default()`
+    pub self_test: bool,
+    /// `#[auto_default(validate_test)]`
+    ///
+    /// Generate a test which checks that `Foo { .. }` passes the `validate()`
+    /// of `validator` or `garde`
+    pub validate_test: bool,
+    /// `#[auto_default(extern_c)]` or `#[auto_default(extern_c = "config_default")]`
+    ///
+    /// Generate an `extern "C"` function which returns `Foo { .. }`, for callers in C
+    pub extern_c: Option<ExternC>,
+    /// `#[auto_default(wasm)]` or `#[auto_default(wasm(setters))]`
+    ///
+    /// Generate a `#[wasm_bindgen(constructor)]` which returns `Foo { .. }`, for callers
+    /// in JavaScript
+    pub wasm: Option<Wasm>,
+    /// `#[auto_default(assert_default)]`
+    ///
+    /// Check that the type of every field with the default value `Default::default()`
+    /// implements `Default`, next to the item
+    pub assert_default: bool,
+    /// `#[auto_default(impl_default)]`
+    ///
+    /// Implement `Default` with the default field values
+    pub impl_default: bool,
+    /// `#[auto_default(from_serde)]`
+    ///
+    /// Fields with `#[serde(default = "path")]` get `path()`, and fields with `#[serde(default)]`
+    /// get `Default::default()`
+    pub from_serde: bool,
+    /// `#[auto_default(serde_defaults)]`
+    ///
+    /// Fields with a default value other than `Default::default()` get
+    /// `#[serde(default = "...")]`, which calls a function that returns the default value
+    pub serde_defaults: bool,
+    /// `#[auto_default(bound = "T: Default")]`
+    ///
+    /// Where predicates of the implementation of `Default`, instead of the inferred ones
+    pub bound: Option<TokenStream>,
+    /// `#[auto_default(finalize = Self::validate)]`
+    ///
+    /// Function that the implementation of `Default` calls with `&mut Self`,
+    /// before returning the value
+    pub finalize: Option<TokenStream>,
+    /// `#[auto_default(values(timeout = Duration::ZERO, retries = 3))]`
+    ///
+    /// Default values of the fields of a struct, by their names
+    pub values: Vec<(Ident, TokenStream)>,
+    /// `#[auto_default(first_variant_default)]`
+    ///
+    /// The first variant of an enum is its default value, as if it was marked `#[default]`
+    pub first_variant_default: bool,
+    /// `#[auto_default(impl_const_default)]`
+    ///
+    /// Implement `ConstDefault` of the `const-default` crate next to the item
+    pub impl_const_default: bool,
+    /// `#[auto_default(const_if = "feature = \"const\"")]`
+    ///
+    /// The item is emitted twice: with default field values if the cfg is enabled, and
+    /// without them otherwise, where only the implementation of `Default` uses them
+    pub const_if: Option<TokenStream>,
+    /// Set by `#[derive(AutoDefault)]`, which only implements `Default`,
+    /// and doesn't change the item
+    pub derive: bool,
+    /// `#[auto_default(check)]`
+    ///
+    /// Report fields without a default value, instead of adding one
+    pub check: bool,
+    /// `#[auto_default(from_derives)]`
+    ///
+    /// The default values of `SmartDefault`, `Derivative` and `Educe` become default field values,
+    /// and their implementation of `Default` is replaced by `#[derive(Default)]`, unless
+    /// `impl_default` is set
+    pub from_derives: bool,
+    /// Policy of the crate from `auto_default.toml`, which applies to every item
+    pub policy: Option<Policy>,
+}
+
+/// Module of the constants of `#[auto_default(consts = defaults)]`
+pub struct Consts {
+    /// `consts` or `strict_consts`
+    pub arg: Ident,
+    /// `defaults`
+    pub module: TokenStream,
+    /// With `strict_consts`, fields always get the constant, so a missing constant is an error.
+    /// Otherwise, fields without a constant get `Default::default()`
+    pub strict: bool,
+}
+
+/// Function of `#[auto_default(extern_c)]`
+pub struct ExternC {
+    /// `extern_c`
+    pub arg: Ident,
+    /// Name of the function from `extern_c = "config_default"`. Without it,
+    /// the function of `Config` is named `config_default`
+    pub name: Option<Ident>,
+}
+
+/// Constructor of `#[auto_default(wasm)]`
+pub struct Wasm {
+    /// `wasm`
+    pub arg: Ident,
+    /// `wasm(setters)` generates a `#[wasm_bindgen(setter)]` for every field
+    /// without a default value
+    pub setters: bool,
+}
+
+/// Fields skipped by `#[auto_default(skip_fields(...))]`
+#[derive(Default)]
+pub struct SkipFields {
+    /// `prefix = "raw_"`
+    ///
+    /// Fields whose name starts with any of these prefixes are skipped
+    pub prefixes: Vec<String>,
+    /// `names(handle, fd)`
+    ///
+    /// Names of the fields that are skipped
+    pub names: Vec<Ident>,
+}
+
+impl SkipFields {
+    /// Parse the options inside of `skip_fields(...)`
+    fn parse(&mut self, options: Vec<Arg>, errors: &mut Vec<CompileError>) {
+        for option in options {
+            match option.ident.to_string().as_str() {
+                "prefix" => {
+                    let Some(value) = option.expect_eq(errors) else {
+                        continue;
+                    };
+                    let mut value = value.into_iter();
+                    let prefix = match (value.next(), value.next()) {
+                        (Some(TokenTree::Literal(prefix)), None) => string_value(&prefix)
+                            .filter(|prefix| is_ident(prefix))
+                            .ok_or(prefix.span()),
+                        (tt, _) => Err(tt.map_or_else(|| option.ident.span(), |tt| tt.span())),
+                    };
+                    match prefix {
+                        Ok(prefix) => self.prefixes.push(prefix),
+                        Err(span) => errors.push(CompileError::new(
+                            ErrorCode::InvalidArgument,
+                            span,
+                            "expected a string literal, such as `prefix = \"raw_\"`",
+                        )),
+                    }
+                }
+                "names" => {
+                    for name in option.expect_list(errors).into_iter().flatten() {
+                        name.expect_flag(errors);
+                        if self
+                            .names
+                            .iter()
+                            .any(|skipped| name.ident.is(&skipped.to_string()))
+                        {
+                            errors.push(CompileError::new(
+                                ErrorCode::Duplicate,
+                                name.ident.span(),
+                                format!("duplicate field `{}`", name.ident),
+                            ));
+                        } else {
+                            self.names.push(name.ident);
+                        }
+                    }
+                }
+                _ => errors.push(
+                    CompileError::new(
+                        ErrorCode::InvalidArgument,
+                        option.ident.span(),
+                        format!("unknown argument `{}`", option.ident),
+                    )
+                    .help("expected `prefix = \"...\"` or `names(...)`"),
+                ),
+            }
+        }
+    }
+}
+
+impl Args {
+    /// Parse the arguments of `#[auto_default(...)]` on the container
+    pub fn parse(args: TokenStream, errors: &mut Vec<CompileError>) -> Self {
+        Self::parse_into(Self::default(), args, errors)
+    }
+
+    /// Parse the arguments of `#[auto_default(...)]` on an item with `#[derive(AutoDefault)]`,
+    /// reporting the ones that would change the item
+    #[allow(
+        dead_code,
+        reason = "`auto-default-core` has no `#[derive(AutoDefault)]`"
+    )]
+    pub fn parse_derive(args: TokenStream, errors: &mut Vec<CompileError>) -> Self {
+        let mut this = Self::parse_into(
+            Self {
+                derive: true,
+                ..Self::default()
+            },
+            args,
+            errors,
+        );
+
+        let unsupported = [
+            (this.self_test, "self_test"),
+            (this.validate_test, "validate_test"),
+            (this.extern_c.is_some(), "extern_c"),
+            (this.wasm.is_some(), "wasm"),
+            (this.clap, "clap"),
+            (this.doc_defaults, "doc_defaults"),
+            (this.normalize, "normalize"),
+            (this.check, "check"),
+            (this.from_derives, "from_derives"),
+            (this.impl_const_default, "impl_const_default"),
+            (this.const_if.is_some(), "const_if = ..."),
+            (!this.annotate.is_empty(), "annotate(...)"),
+        ];
+        for name in this.methods().chain(
+            unsupported
+                .into_iter()
+                .filter_map(|(enabled, name)| enabled.then_some(name)),
+        ) {
+            errors.push(
+                CompileError::new(
+                    ErrorCode::Misplaced,
+                    Span::call_site(),
+                    format!(
+                        "`#[auto_default({name})]` is not supported by `#[derive(AutoDefault)]`"
+                    ),
+                )
+                .help(
+                    "`#[derive(AutoDefault)]` can't change the item, use `#[auto_default]` instead",
+                ),
+            );
+        }
+
+        this.impl_default = true;
+        this
+    }
+
+    /// Parse the arguments of `#[auto_default(...)]` on the container into `this`
+    fn parse_into(mut this: Self, args: TokenStream, errors: &mut Vec<CompileError>) -> Self {
+        // Span of `check`, which conflicts with the options that change the added default values
+        let mut check = None;
+
+        for arg in parse_list(args, errors) {
+            match arg.ident.to_string().as_str() {
+                "infer_literals" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.infer_literals, &arg.ident, errors);
+                }
+                "known_types" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.known_types, &arg.ident, errors);
+                }
+                "struct_default" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.struct_default, &arg.ident, errors);
+                    if this.prefer_new {
+                        errors.push(container_conflict(&arg.ident, "prefer_new"));
+                    }
+                    if this.assoc_const {
+                        errors.push(container_conflict(&arg.ident, "assoc_const"));
+                    }
+                    if this.const_default_trait {
+                        errors.push(container_conflict(&arg.ident, "const_default_trait"));
+                    }
+                }
+                "assoc_const" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.assoc_const, &arg.ident, errors);
+                    if this.prefer_new {
+                        errors.push(container_conflict(&arg.ident, "prefer_new"));
+                    }
+                    if this.struct_default {
+                        errors.push(container_conflict(&arg.ident, "struct_default"));
+                    }
+                    if this.const_default_trait {
+                        errors.push(container_conflict(&arg.ident, "const_default_trait"));
+                    }
+                }
+                "const_default_trait" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.const_default_trait, &arg.ident, errors);
+                    if this.prefer_new {
+                        errors.push(container_conflict(&arg.ident, "prefer_new"));
+                    }
+                    if this.struct_default {
+                        errors.push(container_conflict(&arg.ident, "struct_default"));
+                    }
+                    if this.assoc_const {
+                        errors.push(container_conflict(&arg.ident, "assoc_const"));
+                    }
+                }
+                "prefer_new" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.prefer_new, &arg.ident, errors);
+                    for (enabled, other) in [
+                        (this.struct_default, "struct_default"),
+                        (this.assoc_const, "assoc_const"),
+                        (this.const_default_trait, "const_default_trait"),
+                    ] {
+                        if enabled {
+                            errors.push(container_conflict(&arg.ident, other));
+                        }
+                    }
+                }
+                "debug" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.debug, &arg.ident, errors);
+                }
+                "reset" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.reset, &arg.ident, errors);
+                }
+                "is_default" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.is_default, &arg.ident, errors);
+                }
+                "changed_fields" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.changed_fields, &arg.ident, errors);
+                }
+                "with_fn" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.with_fn, &arg.ident, errors);
+                }
+                "annotate" => {
+                    let ArgValue::List(attrs) = &arg.value else {
+                        arg.expect_list(errors);
+                        continue;
+                    };
+
+                    // serde(default), other
+                    // ^^^^^^^^^^^^^^  ^^^^^
+                    let mut source = attrs.stream().into_iter().peekable();
+                    if source.peek().is_none() {
+                        errors.push(CompileError::new(
+                            ErrorCode::InvalidArgument,
+                            attrs.span(),
+                            "expected an attribute, such as `annotate(serde(default))`",
+                        ));
+                    }
+                    while source.peek().is_some() {
+                        let attr = source
+                            .by_ref()
+                            .take_while(|tt| !is_comma(tt))
+                            .collect::<TokenStream>();
+                        if attr.is_empty() {
+                            errors.push(CompileError::new(
+                                ErrorCode::InvalidArgument,
+                                attrs.span(),
+                                "expected an attribute, such as `annotate(serde(default))`",
+                            ));
+                        } else {
+                            this.annotate.push(attr);
+                        }
+                    }
+                }
+                "clap" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.clap, &arg.ident, errors);
+                }
+                "field_defaults" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.field_defaults, &arg.ident, errors);
+                }
+                "self_test" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.self_test, &arg.ident, errors);
+                }
+                "validate_test" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.validate_test, &arg.ident, errors);
+                }
+                "extern_c" => {
+                    // extern_c = "config_default"
+                    //            ^^^^^^^^^^^^^^^^
+                    let name = match &arg.value {
+                        ArgValue::Flag => None,
+                        ArgValue::Eq(value) => {
+                            let mut value = value.clone().into_iter();
+                            let name = match (value.next(), value.next()) {
+                                (Some(TokenTree::Literal(name)), None) => string_value(&name)
+                                    .filter(|function| is_ident(function))
+                                    .map(|function| Ident::new(&function, name.span()))
+                                    .ok_or(name.span()),
+                                (tt, _) => Err(tt.map_or_else(|| arg.ident.span(), |tt| tt.span())),
+                            };
+                            match name {
+                                Ok(name) => Some(name),
+                                Err(span) => {
+                                    errors.push(CompileError::new(
+                                        ErrorCode::InvalidArgument,
+                                        span,
+                                        "expected a string literal with the name of the function, \
+                                         such as `extern_c = \"config_default\"`",
+                                    ));
+                                    continue;
+                                }
+                            }
+                        }
+                        ArgValue::List(group) => {
+                            errors.push(CompileError::new(
+                                ErrorCode::InvalidArgument,
+                                group.span(),
+                                "expected `extern_c` or `extern_c = \"...\"`",
+                            ));
+                            continue;
+                        }
+                    };
+                    if this.extern_c.is_some() {
+                        errors.push(CompileError::new(
+                            ErrorCode::Duplicate,
+                            arg.ident.span(),
+                            "duplicate argument `extern_c`",
+                        ));
+                    }
+                    this.extern_c = Some(ExternC {
+                        arg: arg.ident,
+                        name,
+                    });
+                }
+                "wasm" => {
+                    // wasm(setters)
+                    //      ^^^^^^^
+                    let mut setters = false;
+                    match &arg.value {
+                        ArgValue::Flag => {}
+                        ArgValue::List(_) => {
+                            for option in arg.expect_list(errors).unwrap_or_default() {
+                                if option.ident.is("setters") {
+                                    option.expect_flag(errors);
+                                    set_flag(&mut setters, &option.ident, errors);
+                                } else {
+                                    errors.push(CompileError::new(
+                                        ErrorCode::InvalidArgument,
+                                        option.ident.span(),
+                                        format!(
+                                            "unknown option `{}`, expected `setters`",
+                                            option.ident
+                                        ),
+                                    ));
+                                }
+                            }
+                        }
+                        ArgValue::Eq(_) => {
+                            arg.expect_flag(errors);
+                            continue;
+                        }
+                    }
+                    if this.wasm.is_some() {
+                        errors.push(CompileError::new(
+                            ErrorCode::Duplicate,
+                            arg.ident.span(),
+                            "duplicate argument `wasm`",
+                        ));
+                    }
+                    this.wasm = Some(Wasm {
+                        arg: arg.ident,
+                        setters,
+                    });
+                }
+                "impl_default" => {
+                    arg.expect_flag(errors);
+                    if this.derive {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Unused,
+                                arg.ident.span(),
+                                "`#[derive(AutoDefault)]` already implements `Default`",
+                            )
+                            .help("remove `impl_default`"),
+                        );
+                    }
+                    set_flag(&mut this.impl_default, &arg.ident, errors);
+                }
+                "impl_const_default" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.impl_const_default, &arg.ident, errors);
+                }
+                "bound" => {
+                    let Some(value) = arg.expect_eq(errors) else {
+                        continue;
+                    };
+                    let mut value = value.into_iter();
+                    let bound = match (value.next(), value.next()) {
+                        (Some(TokenTree::Literal(bound)), None) => string_value(&bound)
+                            .and_then(|predicates| predicates.parse::<TokenStream>().ok())
+                            .map(|predicates| respan(predicates, bound.span()))
+                            .ok_or(bound.span()),
+                        (tt, _) => Err(tt.map_or_else(|| arg.ident.span(), |tt| tt.span())),
+                    };
+                    let bound = match bound {
+                        Ok(bound) => bound,
+                        Err(span) => {
+                            errors.push(CompileError::new(
+                                ErrorCode::InvalidArgument,
+                                span,
+                                "expected a string literal, such as `bound = \"T: Default\"`",
+                            ));
+                            continue;
+                        }
+                    };
+
+                    if this.bound.is_some() {
+                        errors.push(CompileError::new(
+                            ErrorCode::Duplicate,
+                            arg.ident.span(),
+                            "duplicate argument `bound`",
+                        ));
+                    }
+                    this.bound = Some(bound);
+                }
+                "const_if" => {
+                    let Some(value) = arg.expect_eq(errors) else {
+                        continue;
+                    };
+                    let mut value = value.into_iter();
+                    let cfg = match (value.next(), value.next()) {
+                        (Some(TokenTree::Literal(cfg)), None) => {
+                            parse_string(&cfg).ok_or(cfg.span())
+                        }
+                        (tt, _) => Err(tt.map_or_else(|| arg.ident.span(), |tt| tt.span())),
+                    };
+                    let cfg = match cfg {
+                        Ok(cfg) => cfg,
+                        Err(span) => {
+                            errors.push(CompileError::new(
+                                ErrorCode::InvalidArgument,
+                                span,
+                                "expected a string literal, such as `const_if = \"feature = \\\"const\\\"\"`",
+                            ));
+                            continue;
+                        }
+                    };
+
+                    if this.const_if.is_some() {
+                        errors.push(CompileError::new(
+                            ErrorCode::Duplicate,
+                            arg.ident.span(),
+                            "duplicate argument `const_if`",
+                        ));
+                    }
+                    this.const_if = Some(cfg);
+                }
+                "finalize" => {
+                    if let Some(path) = arg.expect_eq(errors) {
+                        if this.finalize.is_some() {
+                            errors.push(CompileError::new(
+                                ErrorCode::Duplicate,
+                                arg.ident.span(),
+                                "duplicate argument `finalize`",
+                            ));
+                        }
+                        this.finalize = Some(path);
+                    }
+                }
+                "from_serde" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.from_serde, &arg.ident, errors);
+                }
+                "from_derives" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.from_derives, &arg.ident, errors);
+                }
+                "serde_defaults" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.serde_defaults, &arg.ident, errors);
+                }
+                "assert_default" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.assert_default, &arg.ident, errors);
+                }
+                "doc_defaults" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.doc_defaults, &arg.ident, errors);
+                }
+                "short_default" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.short_default, &arg.ident, errors);
+                }
+                "check" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.check, &arg.ident, errors);
+                    check = Some(arg.ident.clone());
+                }
+                "normalize" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.normalize, &arg.ident, errors);
+                }
+                "deny_redundant" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.deny_redundant, &arg.ident, errors);
+                    if this.warn_redundant {
+                        errors.push(container_conflict(&arg.ident, "warn_redundant"));
+                    }
+                }
+                "allow_unused_skip" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.allow_unused_skip, &arg.ident, errors);
+                }
+                "warn_redundant" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.warn_redundant, &arg.ident, errors);
+                    if this.deny_redundant {
+                        errors.push(container_conflict(&arg.ident, "deny_redundant"));
+                    }
+                }
+                "values" => parse_values(&arg, &mut this.values, errors),
+                "first_variant_default" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.first_variant_default, &arg.ident, errors);
+                }
+                "skip_variants" => {
+                    for variant in arg.expect_list(errors).into_iter().flatten() {
+                        variant.expect_flag(errors);
+                        if this
+                            .skip_variants
+                            .iter()
+                            .any(|skipped| variant.ident.is(&skipped.to_string()))
+                        {
+                            errors.push(CompileError::new(
+                                ErrorCode::Duplicate,
+                                variant.ident.span(),
+                                format!("duplicate variant `{}`", variant.ident),
+                            ));
+                        } else {
+                            this.skip_variants.push(variant.ident);
+                        }
+                    }
+                }
+                "skip_underscored" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.skip_underscored, &arg.ident, errors);
+                }
+                "skip_phantom_data" => {
+                    arg.expect_flag(errors);
+                    set_flag(&mut this.skip_phantom_data, &arg.ident, errors);
+                }
+                "skip_fields" => {
+                    if let Some(options) = arg.expect_list(errors) {
+                        this.skip_fields.parse(options, errors);
+                    }
+                }
+                "alias" | "helper" => {
+                    let Some(value) = arg.expect_eq(errors) else {
+                        continue;
+                    };
+                    let is_alias = arg.ident.is("alias");
+                    let mut value = value.into_iter();
+                    let name = match (value.next(), value.next()) {
+                        (Some(TokenTree::Ident(name)), None) => name,
+                        (tt, _) => {
+                            errors.push(CompileError::new(
+                                ErrorCode::InvalidArgument,
+                                tt.map_or_else(|| arg.ident.span(), |tt| tt.span()),
+                                format!(
+                                    "expected an identifier, such as `{} = {}`",
+                                    arg.ident,
+                                    if is_alias { "adskip" } else { "adf" }
+                                ),
+                            ));
+                            continue;
+                        }
+                    };
+
+                    let slot = if is_alias {
+                        &mut this.alias
+                    } else {
+                        &mut this.helper
+                    };
+                    if slot.is_some() {
+                        errors.push(CompileError::new(
+                            ErrorCode::Duplicate,
+                            arg.ident.span(),
+                            format!("duplicate argument `{}`", arg.ident),
+                        ));
+                    }
+                    *slot = Some(name);
+                }
+                "consts" | "strict_consts" => {
+                    let Some(module) = arg.expect_eq(errors) else {
+                        continue;
+                    };
+                    if let Some(consts) = &this.consts {
+                        errors.push(if consts.arg.is(&arg.ident.to_string()) {
+                            CompileError::new(
+                                ErrorCode::Duplicate,
+                                arg.ident.span(),
+                                format!("duplicate argument `{}`", arg.ident),
+                            )
+                        } else {
+                            container_conflict(&arg.ident, &consts.arg.to_string())
+                        });
+                    }
+                    this.consts = Some(Consts {
+                        strict: arg.ident.is("strict_consts"),
+                        arg: arg.ident,
+                        module,
+                    });
+                }
+                "with" => {
+                    if let Some(path) = arg.expect_eq(errors) {
+                        if this.with.is_some() {
+                            errors.push(CompileError::new(
+                                ErrorCode::Duplicate,
+                                arg.ident.span(),
+                                "duplicate argument `with`",
+                            ));
+                        }
+                        this.with = Some(path);
+                    }
+                }
+                _ => errors.push(CompileError::new(
+                    ErrorCode::InvalidArgument,
+                    arg.ident.span(),
+                    format!("unknown argument `{}`", arg.ident),
+                )),
+            }
+        }
+
+        if let Some(bound) = &this.bound
+            && !this.impl_default
+            && !this.derive
+        {
+            errors.push(
+                CompileError::new(
+                    ErrorCode::Unused,
+                    bound
+                        .clone()
+                        .into_iter()
+                        .next()
+                        .map_or_else(Span::call_site, |tt| tt.span()),
+                    "`bound` does nothing without `impl_default`",
+                )
+                .help("add `impl_default`, or remove `bound`"),
+            );
+        }
+
+        // Nothing is added with `check`, so these options would do nothing
+        if let Some(check) = check {
+            for (enabled, other) in [
+                (this.infer_literals, "infer_literals"),
+                (this.known_types, "known_types"),
+                (this.struct_default, "struct_default"),
+                (this.assoc_const, "assoc_const"),
+                (this.const_default_trait, "const_default_trait"),
+                (this.prefer_new, "prefer_new"),
+                (this.with.is_some(), "with = ..."),
+                (this.consts.is_some(), "consts = ..."),
+            ] {
+                if enabled {
+                    errors.push(container_conflict(&check, other));
+                }
+            }
+        }
+
+        // Fields get a constant or `Default::default()`, so these options would do nothing
+        if let Some(consts) = &this.consts {
+            for (enabled, other) in [
+                (this.infer_literals, "infer_literals"),
+                (this.known_types, "known_types"),
+                (this.struct_default, "struct_default"),
+                (this.assoc_const, "assoc_const"),
+                (this.const_default_trait, "const_default_trait"),
+                (this.prefer_new, "prefer_new"),
+                (this.with.is_some(), "with = ..."),
+            ] {
+                if enabled {
+                    errors.push(container_conflict(&consts.arg, other));
+                }
+            }
+        }
+
+        if let Some(finalize) = &this.finalize
+            && !this.impl_default
+            && !this.derive
+        {
+            errors.push(
+                CompileError::new(
+                    ErrorCode::Unused,
+                    finalize
+                        .clone()
+                        .into_iter()
+                        .next()
+                        .map_or_else(Span::call_site, |tt| tt.span()),
+                    "`finalize` does nothing without `impl_default`",
+                )
+                .help("add `impl_default`, or remove `finalize`"),
+            );
+        }
+
+        // Without the cfg, the item has no default field values, so there is no `Self { .. }`
+        if let Some(cfg) = &this.const_if {
+            let span = cfg
+                .clone()
+                .into_iter()
+                .next()
+                .map_or_else(Span::call_site, |tt| tt.span());
+            for other in this.methods().chain(
+                [
+                    (this.self_test, "self_test"),
+                    (this.validate_test, "validate_test"),
+                    (this.extern_c.is_some(), "extern_c"),
+                    (this.wasm.is_some(), "wasm"),
+                    (this.impl_const_default, "impl_const_default"),
+                ]
+                .into_iter()
+                .filter_map(|(enabled, name)| enabled.then_some(name)),
+            ) {
+                errors.push(
+                    CompileError::new(
+                        ErrorCode::Conflict,
+                        span,
+                        format!("`#[auto_default(const_if = ...)]` cannot be used together with `#[auto_default({other})]`"),
+                    )
+                    .help("it needs `Self { .. }`, which can't be used when the cfg is disabled"),
+                );
+            }
+        }
+
+        this
+    }
+
+    /// Applies the policy of the crate, which is not passed in the arguments
+    pub fn set_policy(&mut self, policy: Option<Policy>) {
+        // strict = true
+        if policy.as_ref().is_some_and(|policy| policy.strict) && !self.warn_redundant {
+            self.deny_redundant = true;
+        }
+        self.policy = policy;
+    }
+
+    /// Names of the arguments that generate methods for the item
+    pub fn methods(&self) -> impl Iterator<Item = &'static str> {
+        [
+            (self.reset, "reset"),
+            (self.is_default, "is_default"),
+            (self.changed_fields, "changed_fields"),
+            (self.with_fn, "with_fn"),
+            (self.field_defaults, "field_defaults"),
+            (self.serde_defaults, "serde_defaults"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+    }
+}
+
+/// Options from `#[auto_default(...)]` attributes on a field or a variant
+#[derive(Default)]
+pub struct FieldArgs {
+    /// `#[auto_default(skip)]`
+    ///
+    /// Do not add a default value to the field, or to any field of the variant
+    pub skip: Option<Span>,
+    /// `#[auto_default(include)]`
+    ///
+    /// Add a default value to the field, even if its variant or the container skips it
+    pub include: Option<Span>,
+    /// `#[auto_default(skip, reason = "...")]`
+    ///
+    /// Why the field or the variant is skipped. It is added to their documentation
+    pub reason: Option<Literal>,
+    /// Option that changes the default value that is added to the field
+    pub default: Option<FieldDefault>,
+    /// `#[cfg(...)]` attributes, which are not options but are kept with the options,
+    /// so that they can be applied to code generated for the field
+    pub cfg: Vec<TokenTree>,
+    /// The field has `#[arg(default_value_t = ...)]`, `#[command(flatten)]` or `#[command(subcommand)]`,
+    /// so `#[auto_default(clap)]` does not add a default value for `clap` to it
+    pub clap_skip: bool,
+    /// The item has an attribute macro below `#[auto_default]` that can't parse
+    /// default field values, such as `#[pin_project]`
+    pub before_auto_default: bool,
+    /// The variant is marked `#[default]`
+    pub default_variant: bool,
+    /// The struct is marked `#[non_exhaustive]`, so other crates can't write `Foo { .. }`.
+    /// The span points at the attribute
+    pub non_exhaustive: Option<Span>,
+    /// The item has `#[derive(Default)]`
+    pub derive_default: bool,
+    /// The struct is marked `#[repr(C)]`, so `#[auto_default(extern_c)]` can return it
+    pub repr_c: bool,
+    /// Default value from `#[serde(default)]` or `#[serde(default = "path")]` on the field,
+    /// used by `#[auto_default(from_serde)]`. `#[auto_default(serde_defaults)]` doesn't add
+    /// another `#[serde(default = "...")]` to the field if it is set
+    pub serde_default: Option<SerdeDefault>,
+    /// `#[auto_default(group = "network")]`
+    ///
+    /// Name of the group of the field, whose fields are reset together by `reset_network`
+    pub group: Option<Ident>,
+    /// `#[auto_default(values(timeout = Duration::ZERO, retries = 3))]`
+    ///
+    /// Default values of the fields of a variant, by their names
+    pub values: Vec<(Ident, TokenStream)>,
+    /// Default value of the field from `values(...)` on the struct or on its variant.
+    /// The span points at the name of the field in `values(...)`
+    pub listed_default: Option<(Span, TokenStream)>,
+    /// Default value from the attribute of another crate's `Default`, such as `#[default(4)]`
+    /// of `SmartDefault`, used by `#[auto_default(from_derives)]`. The span points at the attribute
+    pub foreign_default: Option<(Span, TokenStream)>,
+}
+
+/// Default value that `serde` uses for a missing field
+pub enum SerdeDefault {
+    /// `#[serde(default)]`
+    ///
+    /// The field gets `Default::default()`
+    Default,
+    /// `#[serde(default = "path")]`
+    ///
+    /// The field gets `path()`
+    Path(TokenStream),
+}
+
+/// Option on a field that changes the default value that is added to it
+pub struct FieldDefault {
+    /// Span of the option's name
+    pub span: Span,
+    /// What the default value of the field is
+    pub kind: DefaultKind,
+}
+
+/// The default value that is added to a field, instead of `Default::default()`
+pub enum DefaultKind {
+    /// `#[auto_default(struct_default)]`
+    ///
+    /// The field gets `Type { .. }`
+    StructDefault,
+    /// `#[auto_default(assoc_const)]`
+    ///
+    /// The field gets `<Type>::DEFAULT`
+    AssocConst,
+    /// `#[auto_default(const_default_trait)]`
+    ///
+    /// The field gets `<Type as ::const_default::ConstDefault>::DEFAULT`
+    ConstDefaultTrait,
+    /// `#[auto_default(prefer_new)]`
+    ///
+    /// The field gets `<Type>::new()`
+    PreferNew,
+    /// `#[auto_default(with = path)]`
+    ///
+    /// The field gets `path()`
+    With(TokenStream),
+    /// `#[auto_default(runtime = expr)]`
+    ///
+    /// The field gets no default field value, and the implementation of `Default`
+    /// generated by `impl_default` uses `expr`, which doesn't need to be `const`
+    Runtime(TokenStream),
+    /// `#[auto_default(value = expr)]`
+    ///
+    /// Like `runtime`, but `expr` can use the default values of the fields declared before this
+    /// one by their names, which the implementation of `Default` binds with `let`
+    Value(TokenStream),
+    /// `#[auto_default(boxed = Type)]`
+    ///
+    /// Like `runtime`, for fields such as `Box<dyn Trait>`. The implementation of `Default`
+    /// uses `Box::new(<Type as Default>::default())`
+    Boxed(TokenStream),
+    /// `#[auto_default(lazy = expr)]`
+    ///
+    /// Like `runtime`, but `expr` is only evaluated once, by the first call to `default()`.
+    /// The value is kept in a `static` and cloned
+    Lazy(TokenStream),
+}
+
+impl DefaultKind {
+    /// How this option is written inside of `#[auto_default(...)]`
+    pub fn syntax(&self) -> &'static str {
+        match self {
+            Self::StructDefault => "struct_default",
+            Self::AssocConst => "assoc_const",
+            Self::ConstDefaultTrait => "const_default_trait",
+            Self::PreferNew => "prefer_new",
+            Self::With(_) => "with = ...",
+            Self::Runtime(_) => "runtime = ...",
+            Self::Value(_) => "value = ...",
+            Self::Boxed(_) => "boxed = ...",
+            Self::Lazy(_) => "lazy = ...",
+        }
+    }
+}
+
+impl FieldArgs {
+    /// Parse the arguments of a single `#[auto_default(...)]` attribute
+    /// into `self`, which may already contain arguments from previous attributes
+    pub fn parse(&mut self, args: TokenStream, position: Position, errors: &mut Vec<CompileError>) {
+        for arg in parse_list(args, errors) {
+            match arg.ident.to_string().as_str() {
+                "skip" => {
+                    arg.expect_flag(errors);
+                    if position == Position::Container {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Misplaced,
+                                arg.ident.span(),
+                                "`#[auto_default(skip)]` is not allowed on container",
+                            )
+                            .help(concat!(
+                                "remove `skip`, or remove `#[auto_default]` to not add",
+                                " default values to any of the fields"
+                            )),
+                        );
+                    } else if let Some(first) = self.skip {
+                        // Disallow 2 attributes on a single field:
+                        //
+                        // #[auto_default(skip)]
+                        // #[auto_default(skip)]
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Duplicate,
+                                arg.ident.span(),
+                                "duplicate `#[auto_default(skip)]`",
+                            )
+                            .note(first, "first `#[auto_default(skip)]` is here"),
+                        );
+                    } else {
+                        self.skip = Some(arg.ident.span());
+                    }
+                }
+                "include" => {
+                    arg.expect_flag(errors);
+                    if position != Position::Field {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Misplaced,
+                                arg.ident.span(),
+                                "`#[auto_default(include)]` is only allowed on fields",
+                            )
+                            .help(
+                                "`include` adds a default value to a field that is skipped \
+                                 by its variant or by the container",
+                            ),
+                        );
+                    } else if let Some(first) = self.include {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Duplicate,
+                                arg.ident.span(),
+                                "duplicate `#[auto_default(include)]`",
+                            )
+                            .note(first, "first `#[auto_default(include)]` is here"),
+                        );
+                    } else {
+                        self.include = Some(arg.ident.span());
+                    }
+                }
+                "reason" => {
+                    let Some(value) = arg.expect_eq(errors) else {
+                        continue;
+                    };
+                    let mut value = value.into_iter();
+                    let reason = match (value.next(), value.next()) {
+                        (Some(TokenTree::Literal(reason)), None) if is_string(&reason) => reason,
+                        (tt, _) => {
+                            errors.push(CompileError::new(
+                                ErrorCode::InvalidArgument,
+                                tt.map_or_else(|| arg.ident.span(), |tt| tt.span()),
+                                "expected a string literal, such as `reason = \"...\"`",
+                            ));
+                            continue;
+                        }
+                    };
+
+                    if position == Position::Container {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Misplaced,
+                                arg.ident.span(),
+                                "`#[auto_default(reason = ...)]` is not allowed on container",
+                            )
+                            .help("remove `reason`"),
+                        );
+                    } else if let Some(first) = &self.reason {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Duplicate,
+                                arg.ident.span(),
+                                "duplicate `#[auto_default(reason = ...)]`",
+                            )
+                            .note(first.span(), "first reason is here"),
+                        );
+                    } else {
+                        self.reason = Some(reason);
+                    }
+                }
+                "group" => {
+                    let Some(value) = arg.expect_eq(errors) else {
+                        continue;
+                    };
+                    let mut value = value.into_iter();
+                    let group = match (value.next(), value.next()) {
+                        (Some(TokenTree::Literal(group)), None) => string_value(&group)
+                            .filter(|name| is_ident(name))
+                            .map(|name| Ident::new(&name, group.span()))
+                            .ok_or(group.span()),
+                        (tt, _) => Err(tt.map_or_else(|| arg.ident.span(), |tt| tt.span())),
+                    };
+                    let group = match group {
+                        Ok(group) => group,
+                        Err(span) => {
+                            errors.push(CompileError::new(
+                                ErrorCode::InvalidArgument,
+                                span,
+                                "expected a string literal with the name of the group, \
+                                 such as `group = \"network\"`",
+                            ));
+                            continue;
+                        }
+                    };
+
+                    if position != Position::Field {
+                        errors.push(CompileError::new(
+                            ErrorCode::Misplaced,
+                            arg.ident.span(),
+                            "`#[auto_default(group = ...)]` is only allowed on fields",
+                        ));
+                    } else if let Some(first) = &self.group {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Duplicate,
+                                arg.ident.span(),
+                                "duplicate `#[auto_default(group = ...)]`",
+                            )
+                            .note(first.span(), "first group is here"),
+                        );
+                    } else {
+                        self.group = Some(group);
+                    }
+                }
+                "values" => {
+                    if position == Position::Variant {
+                        parse_values(&arg, &mut self.values, errors);
+                    } else {
+                        errors.push(
+                            CompileError::new(
+                                ErrorCode::Misplaced,
+                                arg.ident.span(),
+                                "`#[auto_default(values(...))]` is only allowed on variants, \
+                                 and in the arguments of `#[auto_default]` on a struct",
+                            )
+                            .help("write the default value after the type of the field: `= ...`"),
+                        );
+                    }
+                }
+                "struct_default" => {
+                    arg.expect_flag(errors);
+                    self.set_default(&arg, DefaultKind::StructDefault, position, errors);
+                }
+                "assoc_const" => {
+                    arg.expect_flag(errors);
+                    self.set_default(&arg, DefaultKind::AssocConst, position, errors);
+                }
+                "const_default_trait" => {
+                    arg.expect_flag(errors);
+                    self.set_default(&arg, DefaultKind::ConstDefaultTrait, position, errors);
+                }
+                "prefer_new" => {
+                    arg.expect_flag(errors);
+                    self.set_default(&arg, DefaultKind::PreferNew, position, errors);
+                }
+                "with" => {
+                    if let Some(path) = arg.expect_eq(errors) {
+                        self.set_default(&arg, DefaultKind::With(path), position, errors);
+                    }
+                }
+                "runtime" => {
+                    if let Some(value) = arg.expect_eq(errors) {
+                        self.set_default(&arg, DefaultKind::Runtime(value), position, errors);
+                    }
+                }
+                "value" => {
+                    if let Some(value) = arg.expect_eq(errors) {
+                        self.set_default(&arg, DefaultKind::Value(value), position, errors);
+                    }
+                }
+                "boxed" => {
+                    if let Some(ty) = arg.expect_eq(errors) {
+                        self.set_default(&arg, DefaultKind::Boxed(ty), position, errors);
+                    }
+                }
+                "lazy" => {
+                    if let Some(value) = arg.expect_eq(errors) {
+                        self.set_default(&arg, DefaultKind::Lazy(value), position, errors);
+                    }
+                }
+                _ => errors.push(CompileError::new(
+                    ErrorCode::InvalidArgument,
+                    arg.ident.span(),
+                    format!("unknown argument `{}`", arg.ident),
+                )),
+            }
+        }
+    }
+
+    /// Default value of the field from an attribute, which becomes its default field value
+    pub fn attr_value(&self) -> Option<&(Span, TokenStream)> {
+        self.foreign_default
+            .as_ref()
+            .or(self.listed_default.as_ref())
+    }
+
+    /// Read the default value from the arguments of a `#[serde(...)]` attribute on the field
+    ///
+    /// ```text
+    /// #[serde(rename = "port", default = "default_port")]
+    ///                          ^^^^^^^^^^^^^^^^^^^^^^^^
+    /// ```
+    ///
+    /// Errors in the attribute are reported by `serde`, so they are ignored here
+    pub fn parse_serde(&mut self, args: TokenStream) {
+        for arg in parse_list(args, &mut Vec::new()) {
+            if !arg.ident.is("default") {
+                continue;
+            }
+            match &arg.value {
+                ArgValue::Flag => self.serde_default = Some(SerdeDefault::Default),
+                ArgValue::Eq(value) => {
+                    let mut value = value.clone().into_iter();
+                    if let (Some(TokenTree::Literal(path)), None) = (value.next(), value.next())
+                        && let Some(path) = parse_string(&path)
+                    {
+                        self.serde_default = Some(SerdeDefault::Path(path));
+                    }
+                }
+                ArgValue::List(_) => {}
+            }
+        }
+    }
+
+    /// Set the default value of the field, if no other option has set it yet
+    fn set_default(
+        &mut self,
+        arg: &Arg,
+        kind: DefaultKind,
+        position: Position,
+        errors: &mut Vec<CompileError>,
+    ) {
+        let span = arg.ident.span();
+
+        if position != Position::Field {
+            errors.push(CompileError::new(
+                ErrorCode::Misplaced,
+                span,
+                format!(
+                    "`#[auto_default({})]` is only allowed on fields",
+                    kind.syntax()
+                ),
+            ));
+        } else if let Some(existing) = &self.default {
+            let error = if existing.kind.syntax() == kind.syntax() {
+                CompileError::new(
+                    ErrorCode::Duplicate,
+                    span,
+                    format!("duplicate `#[auto_default({})]`", kind.syntax()),
+                )
+                .note(existing.span, "first one is here")
+            } else {
+                CompileError::new(
+                    ErrorCode::Conflict,
+                    span,
+                    format!(
+                        "`#[auto_default({})]` cannot be used together with `#[auto_default({})]`",
+                        kind.syntax(),
+                        existing.kind.syntax()
+                    ),
+                )
+                .note(
+                    existing.span,
+                    format!("`#[auto_default({})]` is here", existing.kind.syntax()),
+                )
+            };
+            errors.push(error);
+        } else {
+            self.default = Some(FieldDefault { span, kind });
+        }
+    }
+}
+
+/// `arg` on the container conflicts with the option `other`, which was passed before it
+fn container_conflict(arg: &Ident, other: &str) -> CompileError {
+    CompileError::new(
+        ErrorCode::Conflict,
+        arg.span(),
+        format!("`#[auto_default({arg})]` cannot be used together with `#[auto_default({other})]`"),
+    )
+}
+
+/// Contents of a string literal, such as `raw_` for `"raw_"`
+///
+/// Only the escape sequences of quotes, backslashes and whitespace are supported,
+/// such as `\"` in `const_if = "feature = \"const\""`
+fn string_value(literal: &Literal) -> Option<String> {
+    let literal = literal.to_string();
+
+    // r#"raw_"#
+    if let Some(raw) = literal.strip_prefix('r') {
+        let value = raw.trim_matches('#').strip_prefix('"')?.strip_suffix('"')?;
+        return Some(value.to_string());
+    }
+
+    // "raw_"
+    let mut value = String::new();
+    let mut chars = literal.strip_prefix('"')?.strip_suffix('"')?.chars();
+    while let Some(c) = chars.next() {
+        value.push(match c {
+            '\\' => match chars.next()? {
+                '"' => '"',
+                '\'' => '\'',
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(value)
+}
+
+/// Whether `name` can be used in an identifier, such as `raw_` in `raw_handle`
+fn is_ident(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Rust code inside of a string literal, such as `default_port` for `"default_port"`
+///
+/// The tokens point at the literal
+pub fn parse_string(literal: &Literal) -> Option<TokenStream> {
+    let parsed = string_value(literal)?.parse::<TokenStream>().ok()?;
+    Some(respan(parsed, literal.span()))
+}
+
+/// Sets the span of every token in `tokens` to `span`
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Group(group) => {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                TokenTree::Group(respanned)
+            }
+            tt => tt.with_span(span),
+        })
+        .collect()
+}
+
+/// Whether `literal` is a string literal, such as `"..."` or `r#"..."#`
+fn is_string(literal: &Literal) -> bool {
+    let literal = literal.to_string();
+    literal.starts_with('"') || literal.starts_with("r\"") || literal.starts_with("r#")
+}
+
+/// Sets `flag` to `true`, reporting an error if it was already set
+fn set_flag(flag: &mut bool, ident: &Ident, errors: &mut Vec<CompileError>) {
+    if *flag {
+        errors.push(CompileError::new(
+            ErrorCode::Duplicate,
+            ident.span(),
+            format!("duplicate argument `{ident}`"),
+        ));
+    }
+    *flag = true;
+}
+
+/// A single argument in a comma-separated list of arguments
+///
+/// ```text
+/// #[auto_default(infer_literals, with = path, nested(a, b))]
+///                ^^^^^^^^^^^^^^  ^^^^^^^^^^^  ^^^^^^^^^^^^
+/// ```
+pub struct Arg {
+    /// Name of the argument
+    ///
+    /// ```text
+    /// with = path
+    /// ^^^^
+    /// ```
+    pub ident: Ident,
+    /// What comes after the name of the argument
+    pub value: ArgValue,
+}
+
+/// Value of an [`Arg`]
+pub enum ArgValue {
+    /// Argument has no value
+    ///
+    /// ```text
+    /// infer_literals
+    /// ```
+    Flag,
+    /// Argument is followed by `=` and tokens until the next top-level `,`
+    ///
+    /// ```text
+    /// with = path
+    ///        ^^^^
+    /// ```
+    Eq(TokenStream),
+    /// Argument is followed by a parenthesized list
+    ///
+    /// ```text
+    /// nested(a, b)
+    ///       ^^^^^^
+    /// ```
+    List(Group),
+}
+
+impl Arg {
+    /// Returns the value after `=`, reporting an error if this argument is not `name = value`
+    pub fn expect_eq(&self, errors: &mut Vec<CompileError>) -> Option<TokenStream> {
+        match &self.value {
+            // error is reported when parsing the argument
+            ArgValue::Eq(value) if value.is_empty() => None,
+            ArgValue::Eq(value) => Some(value.clone()),
+            ArgValue::Flag | ArgValue::List(_) => {
+                errors.push(CompileError::new(
+                    ErrorCode::InvalidArgument,
+                    self.ident.span(),
+                    format!("expected `{} = ...`", self.ident),
+                ));
+                None
+            }
+        }
+    }
+
+    /// Returns the arguments inside of the parentheses, reporting an error if this argument
+    /// is not `name(...)`
+    pub fn expect_list(&self, errors: &mut Vec<CompileError>) -> Option<Vec<Arg>> {
+        match &self.value {
+            ArgValue::List(group) => Some(parse_list(group.stream(), errors)),
+            ArgValue::Flag | ArgValue::Eq(_) => {
+                errors.push(CompileError::new(
+                    ErrorCode::InvalidArgument,
+                    self.ident.span(),
+                    format!("expected `{}(...)`", self.ident),
+                ));
+                None
+            }
+        }
+    }
+
+    /// Reports an error if this argument has a value
+    pub fn expect_flag(&self, errors: &mut Vec<CompileError>) {
+        let span = match &self.value {
+            ArgValue::Flag => return,
+            ArgValue::Eq(value) => value
+                .clone()
+                .into_iter()
+                .next()
+                .map_or_else(|| self.ident.span(), |tt| tt.span()),
+            ArgValue::List(group) => group.span(),
+        };
+
+        errors.push(CompileError::new(
+            ErrorCode::InvalidArgument,
+            span,
+            format!("`{}` does not take a value", self.ident),
+        ));
+    }
+}
+
+/// Parses the default values of fields in `values(...)` into `values`
+///
+/// ```text
+/// values(timeout = Duration::ZERO, retries = 3)
+///        ^^^^^^^^^^^^^^^^^^^^^^^^  ^^^^^^^^^^^
+/// ```
+fn parse_values(arg: &Arg, values: &mut Vec<(Ident, TokenStream)>, errors: &mut Vec<CompileError>) {
+    for field in arg.expect_list(errors).into_iter().flatten() {
+        let Some(value) = field.expect_eq(errors) else {
+            continue;
+        };
+        if let Some((first, _)) = values
+            .iter()
+            .find(|(name, _)| field.ident.is(&name.to_string()))
+        {
+            errors.push(
+                CompileError::new(
+                    ErrorCode::Duplicate,
+                    field.ident.span(),
+                    format!("duplicate default value for `{}`", field.ident),
+                )
+                .note(first.span(), "first one is here"),
+            );
+        } else {
+            values.push((field.ident, value));
+        }
+    }
+}
+
+/// Parses a comma-separated list of arguments
+///
+/// ```text
+/// infer_literals, with = path, nested(a, b)
+/// ```
+pub fn parse_list(stream: TokenStream, errors: &mut Vec<CompileError>) -> Vec<Arg> {
+    let mut source = stream.into_iter().peekable();
+    let mut args = Vec::new();
+
+    loop {
+        // infer_literals, with = path
+        // ^^^^^^^^^^^^^^
+        let ident = match source.next() {
+            Some(TokenTree::Ident(ident)) => ident,
+            Some(tt) => {
+                errors.push(CompileError::new(
+                    ErrorCode::InvalidArgument,
+                    tt.span(),
+                    "expected identifier",
+                ));
+                return args;
+            }
+            None => return args,
+        };
+
+        let value = match source.peek() {
+            // with = path
+            //      ^
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                let eq_span = punct.span();
+                source.next();
+
+                // with = path
+                //        ^^^^
+                let mut value = TokenStream::new();
+                while let Some(tt) = source.next_if(|tt| !is_comma(tt)) {
+                    value.extend([tt]);
+                }
+
+                if value.is_empty() {
+                    errors.push(CompileError::new(
+                        ErrorCode::InvalidArgument,
+                        eq_span,
+                        "expected a value after `=`",
+                    ));
+                }
+
+                ArgValue::Eq(value)
+            }
+            // nested(a, b)
+            //       ^^^^^^
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                let group = group.clone();
+                source.next();
+                ArgValue::List(group)
+            }
+            _ => ArgValue::Flag,
+        };
+
+        args.push(Arg { ident, value });
+
+        // infer_literals, with = path
+        //               ^
+        match source.next() {
+            Some(tt) if is_comma(&tt) => {}
+            Some(tt) => {
+                errors.push(CompileError::new(
+                    ErrorCode::InvalidArgument,
+                    tt.span(),
+                    "expected `,`",
+                ));
+                return args;
+            }
+            None => return args,
+        }
+    }
+}
+
+fn is_comma(tt: &TokenTree) -> bool {
+    matches!(tt, TokenTree::Punct(punct) if punct.as_char() == ',')
+}
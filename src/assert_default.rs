@@ -0,0 +1,54 @@
+//! Assertions that are generated next to the item by `#[auto_default(assert_default)]`
+//!
+//! ```rust,ignore
+//! #[auto_default(assert_default)]
+//! struct Foo<T> {
+//!     a: Vec<T>,
+//! }
+//!
+//! const _: () = {
+//!     fn assert_default<T: Default>() {}
+//!     fn assert_fields<T>() {
+//!         assert_default::<Vec<T>>();
+//!     }
+//! };
+//! ```
+//!
+//! If the type of a field doesn't implement `Default`, the error points at the type of the field,
+//! instead of wherever the struct is created with `Foo { .. }`
+use proc_macro::TokenStream;
+
+use crate::expand::{Field, is_default_call};
+use crate::generics::Generics;
+use crate::tokens::{braced, tokens};
+
+/// `const _: () = { ... };` which checks that the type of every field with the default value
+/// `Default::default()` implements `Default`
+pub fn assert_default(generics: &Generics, fields: &[Field]) -> TokenStream {
+    let mut assertions = TokenStream::new();
+
+    for field in fields {
+        let Some(default) = &field.default else {
+            continue;
+        };
+        if !is_default_call(default, &field.ty) {
+            continue;
+        }
+
+        // Fields that are disabled by `#[cfg(...)]` are not checked
+        assertions.extend(tokens![&field.cfg, "assert_default::<", &field.ty, "> ();"]);
+    }
+
+    tokens![
+        "const _: () =",
+        braced(tokens![
+            "fn assert_default<T: ::core::default::Default>() {}",
+            "fn assert_fields <",
+            &generics.params,
+            "> ()",
+            &generics.where_clause,
+            braced(assertions),
+        ]),
+        ";"
+    ]
+}
@@ -0,0 +1,57 @@
+//! Implementation of the [`construct!`](crate::construct!) macro
+//!
+//! ```rust,ignore
+//! construct!(Foo { a: 1, .. })
+//! ```
+//!
+//! Expands to:
+//!
+//! ```rust,ignore
+//! Foo { a: 1, ..::core::default::Default::default() }
+//! ```
+use proc_macro::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+use crate::diagnostic::{CompileError, ErrorCode};
+use crate::expand::core_default;
+
+/// Replaces the `..` at the end of the struct expression with `..Default::default()`
+pub fn construct(input: TokenStream) -> TokenStream {
+    let mut path = input.into_iter().collect::<Vec<_>>();
+
+    // Foo { a: 1, .. }
+    //     ^^^^^^^^^^^^
+    let fields = match path.pop() {
+        Some(TokenTree::Group(group))
+            if group.delimiter() == Delimiter::Brace && !path.is_empty() =>
+        {
+            group
+        }
+        tt => {
+            return CompileError::new(
+                ErrorCode::InvalidConstruct,
+                tt.map_or_else(Span::call_site, |tt| tt.span()),
+                "expected a struct expression, such as `Foo { a: 1, .. }`",
+            )
+            .into_iter()
+            .collect();
+        }
+    };
+
+    let mut tokens = fields.stream().into_iter().collect::<Vec<_>>();
+
+    // Foo { a: 1, .. }
+    //             ^^
+    if let [.., TokenTree::Punct(first), TokenTree::Punct(second)] = tokens.as_slice()
+        && first.as_char() == '.'
+        && second.as_char() == '.'
+    {
+        tokens.extend(core_default(second.span()));
+    }
+
+    let mut fields_with_default = Group::new(Delimiter::Brace, tokens.into_iter().collect());
+    fields_with_default.set_span(fields.span());
+
+    path.into_iter()
+        .chain([TokenTree::Group(fields_with_default)])
+        .collect()
+}
@@ -0,0 +1,272 @@
+//! Implementations of `Default` by other crates, which don't use the default field values
+//!
+//! ```text
+//! #[derive(SmartDefault)]   #[derive(Derivative)]                   #[derive(Educe)]
+//!                           #[derivative(Default)]                  #[educe(Default)]
+//! struct Foo {              struct Foo {                            struct Foo {
+//!     #[default(4)]             #[derivative(Default(value = "4"))]     #[educe(Default = 4)]
+//!     a: u8,                    a: u8,                                  a: u8,
+//! }                         }                                       }
+//! ```
+//!
+//! Their `Default` would silently disagree with the default field values, so they are errors.
+//! With `#[auto_default(from_derives)]`, their default values become default field values,
+//! and their implementation of `Default` is replaced by `#[derive(Default)]`, or by the one
+//! of `#[auto_default(impl_default)]`
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use crate::args::{Args, FieldArgs, parse_string};
+use crate::diagnostic::{CompileError, ErrorCode};
+use crate::expand::{IdentExt, Position, TokenTreeExt};
+use crate::tokens::tokens;
+
+/// Path of `#[derive(Default)]`, which doesn't need the prelude
+const DERIVE_DEFAULT: &str = "::core::default::Default";
+
+/// If `attr` belongs to the `Default` of another crate, returns the attributes that replace it
+///
+/// Returns `None` if `attr` is kept as it is. Without `from_derives`, the attribute on the item
+/// is reported as an error, and attributes on fields and variants are kept for the other crate
+pub fn foreign_attr(
+    attr: &Group,
+    position: Position,
+    args: &Args,
+    field_args: &mut FieldArgs,
+    errors: &mut Vec<CompileError>,
+) -> Option<TokenStream> {
+    let tokens = attr.stream().into_iter().collect::<Vec<_>>();
+    let (name, list) = match tokens.as_slice() {
+        [TokenTree::Ident(name), TokenTree::Group(list)]
+            if list.delimiter() == Delimiter::Parenthesis =>
+        {
+            (name, list)
+        }
+        // #[default = expr]
+        //   ^^^^^^^ of `SmartDefault`
+        [TokenTree::Ident(default), TokenTree::Punct(eq), value @ ..]
+            if default.is("default") && eq.as_char() == '=' && position == Position::Field =>
+        {
+            if args.from_derives {
+                field_args.foreign_default =
+                    Some((default.span(), value.iter().cloned().collect()));
+                return Some(TokenStream::new());
+            }
+            return None;
+        }
+        _ => return None,
+    };
+
+    // #[default(expr)]
+    //   ^^^^^^^ of `SmartDefault`
+    if name.is("default") {
+        if position != Position::Field || !args.from_derives {
+            return None;
+        }
+        let value = match list.stream().into_iter().collect::<Vec<_>>().as_slice() {
+            // #[default(_code = "expr")]
+            [
+                TokenTree::Ident(code),
+                TokenTree::Punct(eq),
+                TokenTree::Literal(expr),
+            ] if code.is("_code") && eq.as_char() == '=' => parse_string(expr)?,
+            _ => list.stream(),
+        };
+        field_args.foreign_default = Some((name.span(), value));
+        return Some(TokenStream::new());
+    }
+
+    // #[derive(Debug, SmartDefault)]
+    //                 ^^^^^^^^^^^^
+    if name.is("derive") {
+        let mut entries = entries(list.stream());
+        let smart_default = entries.iter().position(|entry| {
+            matches!(entry.last(), Some(TokenTree::Ident(ident)) if ident.is("SmartDefault"))
+        })?;
+        let span = entries[smart_default].last()?.span();
+
+        if position != Position::Container {
+            return None;
+        }
+        if !args.from_derives {
+            errors.push(conflict("SmartDefault", span));
+            return None;
+        }
+
+        // With `impl_default`, the macro implements `Default` itself
+        if args.impl_default {
+            entries.remove(smart_default);
+            if entries.is_empty() {
+                return Some(TokenStream::new());
+            }
+        } else {
+            entries[smart_default] = respan(tokens![DERIVE_DEFAULT], span).into_iter().collect();
+        }
+        return Some(attribute(name, list, entries));
+    }
+
+    // #[derivative(Debug, Default)]
+    //                     ^^^^^^^
+    if !name.is("derivative") && !name.is("educe") {
+        return None;
+    }
+    let mut entries = entries(list.stream());
+    let default = entries.iter().position(
+        |entry| matches!(entry.first(), Some(TokenTree::Ident(ident)) if ident.is("Default")),
+    )?;
+    let span = entries[default][0].span();
+
+    if !args.from_derives {
+        if position == Position::Container {
+            errors.push(conflict(&format!("#[{name}(Default)]"), span));
+        }
+        return None;
+    }
+
+    let entry = entries.remove(default);
+    let mut replacement = if entries.is_empty() {
+        TokenStream::new()
+    } else {
+        attribute(name, list, entries)
+    };
+
+    match position {
+        // #[derive(Default)]
+        Position::Container if !args.impl_default => {
+            replacement.extend(outer_attr(span, "derive", Some(DERIVE_DEFAULT)));
+        }
+        Position::Container => {}
+        // #[default]
+        Position::Variant => replacement.extend(outer_attr(span, "default", None)),
+        Position::Field => {
+            if let Some(value) = entry_value(&entry[1..], name.is("derivative")) {
+                field_args.foreign_default = Some((span, value));
+            }
+        }
+    }
+
+    Some(replacement)
+}
+
+/// The other crate implements `Default` without the default field values
+fn conflict(name: &str, span: Span) -> CompileError {
+    CompileError::new(
+        ErrorCode::Conflict,
+        span,
+        format!(
+            "`{name}` implements `Default` without the default field values \
+             of `#[auto_default]`, so the two can disagree"
+        ),
+    )
+    .help(
+        "add `from_derives` to `#[auto_default(...)]` to turn its default values \
+         into default field values, and to use `#[derive(Default)]` instead",
+    )
+}
+
+/// Default value of a field in `#[derivative(...)]` or `#[educe(...)]`, after `Default`
+///
+/// ```text
+/// #[derivative(Default(value = "expr"))]
+/// #[educe(Default = expr)]
+/// #[educe(Default(expression = expr))]
+/// ```
+///
+/// String literals of `derivative` contain the expression, while those of `educe` are the value
+fn entry_value(rest: &[TokenTree], code_in_strings: bool) -> Option<TokenStream> {
+    let value = match rest {
+        [TokenTree::Punct(eq), value @ ..] if eq.as_char() == '=' => value.to_vec(),
+        [TokenTree::Group(options)] if options.delimiter() == Delimiter::Parenthesis => {
+            entries(options.stream())
+                .into_iter()
+                .find_map(|option| match option.as_slice() {
+                    [TokenTree::Ident(key), TokenTree::Punct(eq), value @ ..]
+                        if (key.is("value") || key.is("expression")) && eq.as_char() == '=' =>
+                    {
+                        Some(value.to_vec())
+                    }
+                    _ => None,
+                })?
+        }
+        _ => return None,
+    };
+
+    match value.as_slice() {
+        [TokenTree::Literal(literal)]
+            if code_in_strings && literal.to_string().starts_with('"') =>
+        {
+            parse_string(literal)
+        }
+        _ => Some(value.into_iter().collect()),
+    }
+}
+
+/// Items of a list separated by commas
+///
+/// ```text
+/// Debug, Default(value = "4") => [Debug], [Default(value = "4")]
+/// ```
+fn entries(list: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut entries = Vec::new();
+    let mut entry = Vec::new();
+    for tt in list {
+        match tt {
+            TokenTree::Punct(comma) if comma.as_char() == ',' => {
+                entries.push(std::mem::take(&mut entry));
+            }
+            tt => entry.push(tt),
+        }
+    }
+    entries.push(entry);
+    entries.retain(|entry| !entry.is_empty());
+    entries
+}
+
+/// `#[name(entries)]`, keeping the spans of the original attribute
+fn attribute(name: &Ident, list: &Group, entries: Vec<Vec<TokenTree>>) -> TokenStream {
+    let mut stream = Vec::new();
+    for (i, entry) in entries.into_iter().enumerate() {
+        if i > 0 {
+            stream.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)).with_span(list.span()));
+        }
+        stream.extend(entry);
+    }
+    let mut group = Group::new(Delimiter::Parenthesis, stream.into_iter().collect());
+    group.set_span(list.span());
+
+    outer_attr_tokens(
+        name.span(),
+        [TokenTree::Ident(name.clone()), TokenTree::Group(group)]
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// `#[name(arg)]`, or `#[name]` without an `arg`
+fn outer_attr(span: Span, name: &str, arg: Option<&str>) -> TokenStream {
+    let mut attr = vec![TokenTree::Ident(Ident::new(name, span))];
+    if let Some(arg) = arg {
+        attr.push(
+            TokenTree::Group(Group::new(
+                Delimiter::Parenthesis,
+                respan(tokens![arg], span),
+            ))
+            .with_span(span),
+        );
+    }
+    outer_attr_tokens(span, attr.into_iter().collect())
+}
+
+/// `#[attr]`
+fn outer_attr_tokens(span: Span, attr: TokenStream) -> TokenStream {
+    [
+        TokenTree::Punct(Punct::new('#', Spacing::Alone)).with_span(span),
+        TokenTree::Group(Group::new(Delimiter::Bracket, attr)).with_span(span),
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// `tokens` with every token at `span`
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens.into_iter().map(|tt| tt.with_span(span)).collect()
+}
@@ -0,0 +1,182 @@
+//! Errors reported by the macros
+//!
+//! ```text
+//! error: [AD002] duplicate `#[auto_default(skip)]`
+//!  --> src/main.rs:5:20
+//!   |
+//! 5 |     #[auto_default(skip)]
+//!   |                    ^^^^
+//!   |
+//! note: first `#[auto_default(skip)]` is here
+//!  --> src/main.rs:4:20
+//!   |
+//! 4 |     #[auto_default(skip)]
+//!   |                    ^^^^
+//! ```
+use std::fmt;
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use crate::expand::TokenTreeExt;
+
+/// Kind of an error. Every error message starts with its code, such as `[AD001]`,
+/// and the codes are listed in the documentation of `#[auto_default]`
+#[derive(Clone, Copy)]
+pub enum ErrorCode {
+    /// The argument is not known, or it is written incorrectly
+    InvalidArgument,
+    /// The same argument is passed more than once
+    Duplicate,
+    /// Arguments that can't be used together
+    Conflict,
+    /// The argument or the attribute is not allowed where it is written
+    Misplaced,
+    /// The argument does nothing
+    Unused,
+    /// The item can't be expanded, because it is not a `struct` or an `enum`,
+    /// or it has tokens that can't be parsed
+    InvalidItem,
+    /// The input of `construct!` is not a struct expression
+    #[allow(dead_code, reason = "`auto-default-core` has no `construct!`")]
+    InvalidConstruct,
+    /// A default value is written by hand, but the macro would add the same one
+    Redundant,
+    /// The crate doesn't enable the nightly features that the default field values need
+    #[allow(
+        dead_code,
+        reason = "`auto-default-core` can't see the attributes of the crate"
+    )]
+    MissingFeature,
+    /// A field has no default value, and `#[auto_default(check)]` doesn't add one
+    MissingDefault,
+    /// Other crates can't use the default field values, because the struct is `#[non_exhaustive]`
+    NonExhaustive,
+    /// A default field value calls something that can't be called in a `const` context
+    NotConst,
+}
+
+impl ErrorCode {
+    /// The code, such as `AD001`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidArgument => "AD001",
+            Self::Duplicate => "AD002",
+            Self::Conflict => "AD003",
+            Self::Misplaced => "AD004",
+            Self::Unused => "AD005",
+            Self::InvalidItem => "AD006",
+            Self::InvalidConstruct => "AD007",
+            Self::Redundant => "AD008",
+            Self::MissingFeature => "AD009",
+            Self::MissingDefault => "AD010",
+            Self::NonExhaustive => "AD011",
+            Self::NotConst => "AD012",
+        }
+    }
+}
+
+/// A secondary message that points at another part of the code
+#[derive(Clone)]
+pub struct Note {
+    /// What the note points at
+    #[allow(
+        dead_code,
+        reason = "only read by `Diagnostic`, which is only used by `auto-default` on nightly"
+    )]
+    pub span: Span,
+    /// Message of the note
+    pub message: String,
+}
+
+/// An error, with the spans that it points at
+///
+/// `.into_iter()` generates `compile_error!($message)` at `$span`. Notes and help are
+/// added to the message, because `compile_error!` can only point at a single span
+#[derive(Clone)]
+pub struct CompileError {
+    /// Kind of the error
+    pub code: ErrorCode,
+    /// Where the compile error is generated
+    pub span: Span,
+    /// Message of the compile error
+    pub message: String,
+    /// Other parts of the code that are relevant to the error
+    pub notes: Vec<Note>,
+    /// How the error can be fixed
+    pub help: Option<String>,
+}
+
+impl CompileError {
+    /// Create a new compile error
+    pub fn new(code: ErrorCode, span: Span, message: impl AsRef<str>) -> Self {
+        Self {
+            code,
+            span,
+            message: message.as_ref().to_string(),
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    /// Point at another part of the code that is relevant to the error
+    pub fn note(mut self, span: Span, message: impl AsRef<str>) -> Self {
+        self.notes.push(Note {
+            span,
+            message: message.as_ref().to_string(),
+        });
+        self
+    }
+
+    /// Explain how the error can be fixed
+    pub fn help(mut self, help: impl AsRef<str>) -> Self {
+        self.help = Some(help.as_ref().to_string());
+        self
+    }
+
+    /// The message, starting with the code of the error
+    ///
+    /// ```text
+    /// [AD002] duplicate `#[auto_default(skip)]`
+    /// ```
+    pub fn title(&self) -> String {
+        format!("[{}] {}", self.code.as_str(), self.message)
+    }
+}
+
+/// The title, followed by notes and help on separate lines
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.title())?;
+        for note in &self.notes {
+            write!(f, "\nnote: {}", note.message)?;
+        }
+        if let Some(help) = &self.help {
+            write!(f, "\nhelp: {help}")?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for CompileError {
+    type Item = TokenTree;
+    type IntoIter = std::array::IntoIter<Self::Item, 8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        [
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(self.span),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(self.span),
+            TokenTree::Ident(Ident::new("core", self.span)),
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(self.span),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(self.span),
+            TokenTree::Ident(Ident::new("compile_error", self.span)),
+            TokenTree::Punct(Punct::new('!', Spacing::Alone)).with_span(self.span),
+            TokenTree::Group(Group::new(Delimiter::Brace, {
+                TokenStream::from(
+                    TokenTree::Literal(Literal::string(&self.to_string())).with_span(self.span),
+                )
+            }))
+            .with_span(self.span),
+        ]
+        .into_iter()
+    }
+}
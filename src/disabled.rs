@@ -0,0 +1,56 @@
+//! Turning off the macros for a whole build, without changing the source code
+//!
+//! ```sh
+//! RUSTFLAGS="--cfg auto_default_disabled" cargo build
+//! AUTO_DEFAULT_DISABLED=1 cargo build
+//! ```
+//!
+//! `#[auto_default]` then emits the item as it was written, only removing its own attributes
+//! from the fields and variants, and `#[derive(AutoDefault)]` emits nothing. This shows whether
+//! an error or a slow build is caused by the expansion, and lets toolchains that can't parse
+//! the generated code build the crate.
+//!
+//! Proc macros can't check a `cfg`, so it is found in the arguments of `rustc` like the features
+//! of the crate are
+use std::env;
+use std::sync::OnceLock;
+
+use proc_macro::TokenStream;
+
+use crate::tracked;
+
+/// Name of the `cfg` that turns off the macros
+const CFG: &str = "auto_default_disabled";
+
+/// Environment variable that turns off the macros, unless it is empty or `0`
+const ENV_VAR: &str = "AUTO_DEFAULT_DISABLED";
+
+/// Whether the macros are turned off for the crate that is being compiled
+///
+/// Items that track the environment variable are added to `tracked`
+pub fn disabled(tracked: &mut TokenStream) -> bool {
+    static CFG_SET: OnceLock<bool> = OnceLock::new();
+
+    // `cargo` only compiles the crate again if it knows that the variable was read
+    let env_var =
+        tracked::env_var(ENV_VAR, tracked).is_some_and(|value| !value.is_empty() && value != "0");
+
+    env_var || *CFG_SET.get_or_init(cfg_set)
+}
+
+/// Whether `rustc` was passed `--cfg auto_default_disabled`
+fn cfg_set() -> bool {
+    let mut args = env::args().peekable();
+    while let Some(arg) = args.next() {
+        // --cfg auto_default_disabled
+        // --cfg=auto_default_disabled
+        let cfg = match arg.as_str() {
+            "--cfg" => args.next_if(|cfg| cfg == CFG).is_some(),
+            _ => arg.strip_prefix("--cfg=") == Some(CFG),
+        };
+        if cfg {
+            return true;
+        }
+    }
+    false
+}
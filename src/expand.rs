@@ -0,0 +1,3430 @@
+//! Adds default field values to the item that `#[auto_default]` is applied to
+use std::fmt::{self, Write as _};
+use std::iter::Peekable;
+use std::ops::Range;
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use crate::args::{Args, Consts, DefaultKind, FieldArgs, FieldDefault, SerdeDefault};
+use crate::diagnostic::{CompileError, ErrorCode};
+use crate::generics::Generics;
+use crate::methods::GroupConflict;
+use crate::tokens::{braced, parenthesized, tokens};
+use crate::{assert_default, derives, extern_c, impl_default, methods, pretty, self_test, wasm};
+
+/// Item that `#[auto_default]` expands to
+pub struct Expansion {
+    /// The item with default field values, followed by any generated items
+    ///
+    /// If there were errors, this is the item unchanged
+    pub item: TokenStream,
+    /// If `#[auto_default]` did nothing, the reason why
+    #[allow(dead_code, reason = "`auto-default-core` doesn't report warnings")]
+    pub noop: Option<&'static str>,
+    /// Problems that don't prevent the item from being expanded
+    #[allow(dead_code, reason = "`auto-default-core` doesn't report warnings")]
+    pub warnings: Vec<CompileError>,
+}
+
+/// Adds default field values to `input`, which is the item that `#[auto_default]` is applied to
+pub fn expand(
+    input: TokenStream,
+    args: &Args,
+    compile_errors: &mut Vec<CompileError>,
+) -> Expansion {
+    let Some(cfg) = args.const_if.as_ref().filter(|_| !args.derive) else {
+        return expand_item(input, args, None, compile_errors);
+    };
+
+    // #[auto_default(const_if = "feature = \"const\"")]
+    //
+    // #[cfg(feature = "const")]
+    // struct Foo { a: u8 = 0 }
+    // #[cfg(feature = "const")]
+    // impl Default for Foo { ... }
+    //
+    // #[cfg(not(feature = "const"))]
+    // struct Foo { a: u8 }
+    // #[cfg(not(feature = "const"))]
+    // impl Default for Foo { ... }
+    let with_values = Version {
+        cfg: cfg_attr(cfg.clone()),
+        strip_values: false,
+    };
+    let without_values = Version {
+        cfg: cfg_attr(tokens!["not", parenthesized(cfg.clone())]),
+        strip_values: true,
+    };
+
+    let mut expansion = expand_item(input.clone(), args, Some(&with_values), compile_errors);
+    // Both versions report the same errors, so they are only reported once
+    let stripped = expand_item(input, args, Some(&without_values), &mut Vec::new());
+    expansion.item = tokens![
+        with_values.cfg,
+        expansion.item,
+        without_values.cfg,
+        stripped.item
+    ];
+    expansion
+}
+
+/// One of the two versions of the item emitted by `#[auto_default(const_if = "...")]`
+struct Version {
+    /// `#[cfg(...)]` of the item, and of the items generated next to it
+    cfg: TokenStream,
+    /// The default field values are removed from the item, and only used by `impl Default`
+    strip_values: bool,
+}
+
+/// `#[cfg(predicate)]`
+fn cfg_attr(predicate: TokenStream) -> TokenStream {
+    tokens![
+        "#",
+        Group::new(Delimiter::Bracket, tokens!["cfg", parenthesized(predicate)])
+    ]
+}
+
+/// Adds `item`, which is generated next to the item, to `sink` with the cfg of its `version`
+fn push_generated(sink: &mut Sink, version: Option<&Version>, item: TokenStream) {
+    if item.is_empty() {
+        return;
+    }
+    if let Some(version) = version {
+        sink.extend(version.cfg.clone());
+    }
+    sink.extend(item);
+}
+
+/// Adds default field values to `input`, or removes them for one of the versions of `const_if`
+fn expand_item(
+    input: TokenStream,
+    args: &Args,
+    version: Option<&Version>,
+    compile_errors: &mut Vec<CompileError>,
+) -> Expansion {
+    // Emitted instead of the expansion if the tokens of the item can't be parsed
+    let original = input.clone();
+    let errors_before = compile_errors.len();
+
+    // Input supplied by the user. All tokens from here will
+    // get sent back to `output`
+    let mut source = input.into_iter().peekable();
+
+    // We collect all tokens into here and then return this
+    //
+    // Tokens are collected into a `Vec` and only turned into a `TokenStream` at the end,
+    // since each `TokenStream::extend` is a call into the compiler
+    let mut sink = Sink::new();
+
+    let container_args = stream_attrs(
+        &mut source,
+        &mut sink,
+        compile_errors,
+        // no skip allowed on the container, would make no sense
+        // (just don't use the `#[auto_default]` at all at that point!)
+        Position::Container,
+        args,
+    );
+
+    // The attribute macro would fail to parse the default field values, so the item is
+    // emitted unchanged. Only the error about the order of the attributes is reported
+    if container_args.before_auto_default {
+        sink.extend(source);
+        return unchanged(sink, args, None);
+    }
+    if let Some(expansion) = unparsed(&original, args, errors_before, compile_errors) {
+        return expansion;
+    }
+
+    // Also the visibility of the function of `#[auto_default(extern_c)]`
+    let vis_start = sink.len();
+    stream_vis(&mut source, &mut sink);
+    let vis = sink[vis_start..].iter().cloned().collect::<TokenStream>();
+
+    // pub(in crate) struct Foo
+    //               ^^^^^^
+    let item_kind = match source.next() {
+        Some(TokenTree::Ident(kw)) if kw.is("struct") => {
+            sink.push(TokenTree::Ident(kw));
+            ItemKind::Struct
+        }
+        Some(TokenTree::Ident(kw)) if kw.is("enum") => {
+            sink.push(TokenTree::Ident(kw));
+            ItemKind::Enum
+        }
+        tt => {
+            let span = tt.as_ref().map_or_else(Span::call_site, TokenTree::span);
+            let item = tt.into_iter().chain(source).collect::<Vec<_>>();
+            compile_errors.push(match item.first() {
+                // Fields of a union can't have default values, since only one of them is set
+                Some(TokenTree::Ident(kw)) if kw.is("union") => CompileError::new(
+                    ErrorCode::InvalidItem,
+                    span,
+                    "`#[auto_default]` does not support unions, because their fields can't have default values",
+                )
+                .help(concat!(
+                    "remove `#[auto_default]`. If every field is an FFI type for which all bytes",
+                    " being zero is a valid value, `Default` can be implemented with",
+                    " `unsafe { ::core::mem::zeroed() }`"
+                )),
+                _ => match unsupported_item(&item) {
+                    Some((span, kind)) => CompileError::new(
+                        ErrorCode::InvalidItem,
+                        span,
+                        format!("`#[auto_default]` does not support {kind}"),
+                    )
+                    .help(
+                        "`#[auto_default]` supports structs with named fields, \
+                         and enums whose variants have named fields",
+                    ),
+                    None => CompileError::new(
+                        ErrorCode::InvalidItem,
+                        span,
+                        "expected a `struct` or an `enum`",
+                    ),
+                },
+            });
+
+            // Emit the item unchanged, so the error does not cause
+            // more errors wherever the item is used
+            sink.extend(item);
+            return unchanged(sink, args, None);
+        }
+    };
+
+    // struct Foo
+    //        ^^^
+    let Some(item_ident) = stream_ident(&mut source, &mut sink) else {
+        let kw = sink.last().map_or_else(Span::call_site, TokenTree::span);
+        compile_errors.push(unexpected(source.next(), kw, "the name of the item"));
+        return unparsed(&original, args, errors_before, compile_errors)
+            .unwrap_or_else(|| unchanged(sink, args, None));
+    };
+
+    // Tokens between the name of the item and its fields
+    let mut generics = Sink::new();
+
+    // Generics
+    //
+    // struct Foo<Bar, Baz: Trait> where Baz: Quux { ... }
+    //           ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    let source_item_fields = loop {
+        match source.next() {
+            // Fields of the struct
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => break group,
+            // This token is part of the generics of the struct
+            Some(tt) => {
+                generics.extend([tt.clone()]);
+                sink.extend([tt]);
+            }
+            // reached end of input
+            None => {
+                // Unit structs have no fields to add default values to, so they are kept as-is.
+                // These often come from generated code, so they are not an error, and not
+                // a warning either
+                //
+                // struct Foo;
+                if is_unit_struct(&generics) {
+                    return unchanged(sink, args, None);
+                }
+
+                // note: if enum, this is unreachable because `enum Foo` is invalid (requires `{}`),
+                // whilst `struct Foo;` is completely valid
+                compile_errors.push(CompileError::new(
+                    ErrorCode::InvalidItem,
+                    item_ident.span(),
+                    "expected struct with named fields",
+                ));
+
+                // All of the item's tokens are already in `sink`
+                return unchanged(sink, args, None);
+            }
+        }
+    };
+
+    // struct Foo<T> { ... }
+    //           ^
+    let generics_span = generics
+        .first()
+        .filter(|tt| matches!(tt, TokenTree::Punct(punct) if punct.as_char() == '<'))
+        .map(TokenTree::span);
+    let generics = Generics::parse(generics.into_iter().collect());
+
+    // `rustc` doesn't allow default field values on a `#[non_exhaustive]` struct, since other
+    // crates couldn't write `Foo { .. }` anyway. The values are removed from the item,
+    // and only used by the implementation of `Default`
+    let non_exhaustive = container_args
+        .non_exhaustive
+        .filter(|_| item_kind == ItemKind::Struct && !args.derive);
+
+    // #[auto_default(first_variant_default)]
+    //
+    // With `#[derive(Default)]`, the first variant is marked `#[default]`,
+    // otherwise `Default` is implemented next to the item
+    let first_variant_default = args.first_variant_default && item_kind == ItemKind::Enum;
+    // #[auto_default(const_if = "...")]
+    let strip_values = version.is_some_and(|version| version.strip_values);
+    let impl_default = args.impl_default
+        || args.const_if.is_some()
+        || non_exhaustive.is_some()
+        || (first_variant_default && (args.derive || !container_args.derive_default));
+
+    let mut fields = Fields {
+        collect: args.methods().next().is_some()
+            || args.self_test
+            || args.validate_test
+            || args.extern_c.is_some()
+            || args.wasm.is_some()
+            || args.assert_default
+            || impl_default
+            || args.impl_const_default,
+        strip_values: non_exhaustive.is_some() || strip_values,
+        list: Vec::new(),
+        skip_fields_found: vec![false; args.skip_fields.names.len()],
+        // Enums report that `serde_defaults` is only supported on structs
+        serde_defaults: (args.serde_defaults && item_kind == ItemKind::Struct).then(|| {
+            if generics.args.is_empty() {
+                item_ident.to_string()
+            } else {
+                // Generic arguments can't be inferred from the return type of the function
+                format!(
+                    "{item_ident}::<{}>",
+                    pretty::tokens_to_string(generics.args.clone())
+                )
+            }
+        }),
+        total: 0,
+        changed: 0,
+        warnings: Vec::new(),
+        type_params: impl_default::type_params(&generics),
+    };
+
+    // #[derive(Default)] would ignore the default values
+    if let Some(span) = non_exhaustive
+        && container_args.derive_default
+    {
+        fields.warnings.push(
+            CompileError::new(
+                ErrorCode::NonExhaustive,
+                span,
+                "`#[derive(Default)]` doesn't use the default values of this struct, \
+                 because a `#[non_exhaustive]` struct can't have default field values",
+            )
+            .help(
+                "remove `#[derive(Default)]`, and `#[auto_default]` will implement `Default` \
+                 with the default values instead",
+            ),
+        );
+    }
+
+    // Without the cfg, `#[derive(Default)]` would ignore the default values
+    if args.const_if.is_some() && container_args.derive_default && !args.derive {
+        compile_errors.push(
+            CompileError::new(
+                ErrorCode::Conflict,
+                Span::call_site(),
+                "`#[auto_default(const_if = ...)]` cannot be used together with `#[derive(Default)]`",
+            )
+            .help(
+                "remove `#[derive(Default)]`, and `#[auto_default]` will implement `Default` \
+                 with the default values instead",
+            ),
+        );
+    }
+
+    // Created once, then cloned for every field that needs it
+    let default_template = if args.short_default {
+        short_default(Span::call_site())
+    } else {
+        core_default(Span::call_site())
+    };
+
+    // What `Default::default()` returns, for `#[auto_default(self_test)]`, `impl_default`
+    // and `impl_const_default`
+    let mut default_value = None;
+
+    match item_kind {
+        ItemKind::Struct => {
+            if let Some(variant) = args.skip_variants.first() {
+                compile_errors.push(CompileError::new(
+                    ErrorCode::Misplaced,
+                    variant.span(),
+                    "`#[auto_default(skip_variants(...))]` is only supported on enums",
+                ));
+            }
+            if args.first_variant_default {
+                compile_errors.push(CompileError::new(
+                    ErrorCode::Misplaced,
+                    Span::call_site(),
+                    "`#[auto_default(first_variant_default)]` is only supported on enums",
+                ));
+            }
+
+            sink.push(TokenTree::Group(add_default_field_values(
+                source_item_fields,
+                args,
+                &default_template,
+                compile_errors,
+                &mut fields,
+                // none of the fields are considered to be skipped initially
+                IsSkip(false),
+                &args.values,
+            )));
+
+            default_value = Some(DefaultValue {
+                path: tokens![&item_ident],
+                fields: Some(0..fields.list.len()),
+            });
+        }
+        ItemKind::Enum => {
+            if let Some((name, _)) = args.values.first() {
+                compile_errors.push(
+                    CompileError::new(
+                        ErrorCode::Misplaced,
+                        name.span(),
+                        "`#[auto_default(values(...))]` on an enum must be written on its variants",
+                    )
+                    .help("move `values(...)` to `#[auto_default(...)]` on a variant"),
+                );
+            }
+
+            let mut source_variants = source_item_fields.stream().into_iter().peekable();
+            let mut sink_variants = Sink::new();
+
+            // Whether each of `args.skip_variants` is a variant of this enum
+            let mut skip_variants_found = vec![false; args.skip_variants.len()];
+
+            // Whether any variant is marked `#[default]`
+            let mut has_default_variant = false;
+
+            // Whether the next variant is the first one
+            let mut is_first = true;
+
+            loop {
+                let mut variant_args = stream_attrs(
+                    &mut source_variants,
+                    &mut sink_variants,
+                    compile_errors,
+                    // can skip the variant, which removes auto-default for all
+                    // fields
+                    Position::Variant,
+                    args,
+                );
+
+                // #[auto_default(first_variant_default)]
+                //
+                // #[default]
+                // ^^^^^^^^^^ added to the first variant, for `#[derive(Default)]`
+                let marked_default = variant_args.default_variant;
+                if first_variant_default && is_first && source_variants.peek().is_some() {
+                    variant_args.default_variant = true;
+                    if !impl_default {
+                        sink_variants.extend(tokens!["#[default]"]);
+                    }
+                }
+                is_first = false;
+
+                has_default_variant |= variant_args.default_variant;
+
+                // #[doc = "Fields of this variant have no default values:"]
+                // #[doc = "reason"]
+                if let Some(reason) = variant_args.reason.filter(|_| variant_args.skip.is_some()) {
+                    sink_variants.extend(doc_reason(
+                        "Fields of this variant have no default values:",
+                        reason,
+                    ));
+                }
+
+                // variants technically can have visibility, at least on a syntactic level
+                //
+                // pub Variant {  }
+                // ^^^
+                stream_vis(&mut source_variants, &mut sink_variants);
+
+                // Variant {  }
+                // ^^^^^^^
+                let Some(variant_ident) = stream_ident(&mut source_variants, &mut sink_variants)
+                else {
+                    // that means we have an enum with no variants, e.g.:
+                    //
+                    // enum Never {}
+                    //
+                    // When we parse the variants, there won't be an identifier
+                    break;
+                };
+
+                if first_variant_default && marked_default {
+                    compile_errors.push(
+                        CompileError::new(
+                            ErrorCode::Conflict,
+                            variant_ident.span(),
+                            "`#[auto_default(first_variant_default)]` cannot be used together \
+                             with a variant marked `#[default]`",
+                        )
+                        .help("remove `first_variant_default`, or remove `#[default]`"),
+                    );
+                }
+
+                // #[auto_default(skip_variants(Variant))]
+                //                              ^^^^^^^
+                let skipped_by_container = args
+                    .skip_variants
+                    .iter()
+                    .position(|skipped| variant_ident.is(&skipped.to_string()));
+                if let Some(i) = skipped_by_container {
+                    skip_variants_found[i] = true;
+                }
+
+                // if this variant is marked #[auto_default(skip)], or is listed in `skip_variants`
+                let is_skip = IsSkip(variant_args.skip.is_some() || skipped_by_container.is_some());
+
+                // only variants with named fields can be marked `#[auto_default(skip)]`,
+                // or have `#[auto_default(values(...))]`
+                let mut disallow_skip = || {
+                    if let Some((name, _)) = variant_args.values.first() {
+                        compile_errors.push(CompileError::new(
+                            ErrorCode::Misplaced,
+                            name.span(),
+                            "`#[auto_default(values(...))]` is only allowed on variants with named fields",
+                        ));
+                    }
+                    if variant_args.skip.is_some() {
+                        compile_errors.push(CompileError::new(
+                            ErrorCode::Misplaced,
+                            variant_ident.span(),
+                            concat!(
+                                "`#[auto_default(skip)]` is",
+                                " only allowed on variants with named fields"
+                            ),
+                        ));
+                    }
+                    if let Some(i) = skipped_by_container {
+                        compile_errors.push(CompileError::new(
+                            ErrorCode::Misplaced,
+                            args.skip_variants[i].span(),
+                            "`skip_variants` can only contain variants with named fields",
+                        ));
+                    }
+                };
+
+                match source_variants.peek() {
+                    // Enum variant with named fields. Add default field values.
+                    Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                        let named_variant_fields = group.clone();
+                        source_variants.next();
+                        let first_field = fields.list.len();
+                        sink_variants.push(TokenTree::Group(add_default_field_values(
+                            named_variant_fields,
+                            args,
+                            &default_template,
+                            compile_errors,
+                            &mut fields,
+                            is_skip,
+                            &variant_args.values,
+                        )));
+
+                        if variant_args.default_variant {
+                            default_value = Some(DefaultValue {
+                                path: tokens![&item_ident, "::", &variant_ident],
+                                fields: Some(first_field..fields.list.len()),
+                            });
+                        }
+
+                        stream_enum_variant_discriminant_and_comma(
+                            &mut source_variants,
+                            &mut sink_variants,
+                            compile_errors,
+                        );
+                    }
+                    // Enum variant with unnamed fields.
+                    Some(TokenTree::Group(group))
+                        if group.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        disallow_skip();
+                        if variant_args.default_variant && !marked_default {
+                            compile_errors.push(CompileError::new(
+                                ErrorCode::Misplaced,
+                                variant_ident.span(),
+                                "`#[auto_default(first_variant_default)]` requires the first \
+                                 variant to have named fields or no fields",
+                            ));
+                        }
+                        let unnamed_variant_fields = group.clone();
+                        source_variants.next();
+                        sink_variants.push(TokenTree::Group(unnamed_variant_fields));
+
+                        stream_enum_variant_discriminant_and_comma(
+                            &mut source_variants,
+                            &mut sink_variants,
+                            compile_errors,
+                        );
+                    }
+                    // This was a unit variant. Next variant may exist,
+                    // if it does it is parsed on next iteration
+                    Some(TokenTree::Punct(punct))
+                        if punct.as_char() == ',' || punct.as_char() == '=' =>
+                    {
+                        disallow_skip();
+                        if variant_args.default_variant {
+                            default_value = Some(DefaultValue {
+                                path: tokens![&item_ident, "::", &variant_ident],
+                                fields: None,
+                            });
+                        }
+                        stream_enum_variant_discriminant_and_comma(
+                            &mut source_variants,
+                            &mut sink_variants,
+                            compile_errors,
+                        );
+                    }
+                    // Unit variant, with no comma at the end. This is the last variant
+                    None => {
+                        disallow_skip();
+                        if variant_args.default_variant {
+                            default_value = Some(DefaultValue {
+                                path: tokens![&item_ident, "::", &variant_ident],
+                                fields: None,
+                            });
+                        }
+                        break;
+                    }
+                    Some(_) => {
+                        compile_errors.push(unexpected(
+                            source_variants.next(),
+                            variant_ident.span(),
+                            "`{ ... }`, `( ... )`, `=` or `,` after the variant",
+                        ));
+                        break;
+                    }
+                }
+            }
+
+            for (enabled, arg) in [
+                (args.self_test, "self_test"),
+                (args.validate_test, "validate_test"),
+                (args.impl_default, "impl_default"),
+                (args.impl_const_default, "impl_const_default"),
+                (args.first_variant_default, "first_variant_default"),
+            ] {
+                if enabled && !has_default_variant {
+                    compile_errors.push(
+                        CompileError::new(
+                            ErrorCode::Misplaced,
+                            item_ident.span(),
+                            format!(
+                                "`#[auto_default({arg})]` requires a variant marked `#[default]`"
+                            ),
+                        )
+                        .help(
+                            "mark the variant that `Default::default()` returns with `#[default]`",
+                        ),
+                    );
+                }
+            }
+
+            for (variant, found) in args.skip_variants.iter().zip(skip_variants_found) {
+                if !found {
+                    compile_errors.push(CompileError::new(
+                        ErrorCode::InvalidArgument,
+                        variant.span(),
+                        format!("this enum has no variant named `{variant}`"),
+                    ));
+                }
+            }
+
+            let mut sink_variants = Group::new(
+                source_item_fields.delimiter(),
+                sink_variants.into_iter().collect(),
+            );
+            sink_variants.set_span(source_item_fields.span());
+            sink.push(TokenTree::Group(sink_variants));
+        }
+    }
+
+    for (field, found) in args.skip_fields.names.iter().zip(&fields.skip_fields_found) {
+        if !found {
+            compile_errors.push(CompileError::new(
+                ErrorCode::InvalidArgument,
+                field.span(),
+                format!("this item has no field named `{field}`"),
+            ));
+        }
+    }
+
+    if let Some(expansion) = unparsed(&original, args, errors_before, compile_errors) {
+        return expansion;
+    }
+
+    // Tokens of the item, before the generated items
+    let item_end = sink.len();
+
+    // These need `Foo { .. }`
+    if let Some(span) = non_exhaustive {
+        for arg in args
+            .methods()
+            .chain(args.self_test.then_some("self_test"))
+            .chain(args.validate_test.then_some("validate_test"))
+            .chain(args.extern_c.is_some().then_some("extern_c"))
+            .chain(args.wasm.is_some().then_some("wasm"))
+            .chain(args.impl_const_default.then_some("impl_const_default"))
+        {
+            compile_errors.push(
+                CompileError::new(
+                    ErrorCode::NonExhaustive,
+                    span,
+                    format!(
+                        "`#[auto_default({arg})]` is not supported on `#[non_exhaustive]` structs"
+                    ),
+                )
+                .help("it needs `Self { .. }`, which can't be used without default field values"),
+            );
+        }
+    }
+
+    // impl Foo { ... }
+    let methods = match item_kind {
+        // The methods need the default field values, which a derive can't add
+        _ if args.derive || non_exhaustive.is_some() || strip_values => TokenStream::new(),
+        ItemKind::Struct => {
+            if args.reset {
+                check_groups(&fields.list, compile_errors);
+            }
+            // `reset_to_default` and `with` create the default value with `Self { .. }`
+            let mut has_defaults = true;
+            for arg in [(args.reset, "reset"), (args.with_fn, "with_fn")]
+                .into_iter()
+                .filter_map(|(enabled, arg)| enabled.then_some(arg))
+            {
+                has_defaults &= has_default_values(&fields.list, arg, compile_errors);
+            }
+            if has_defaults {
+                methods::methods(&item_ident, &generics, &fields.list, args)
+            } else {
+                TokenStream::new()
+            }
+        }
+        ItemKind::Enum => {
+            for method in args.methods() {
+                compile_errors.push(CompileError::new(
+                    ErrorCode::Misplaced,
+                    Span::call_site(),
+                    format!("`#[auto_default({method})]` is only supported on structs"),
+                ));
+            }
+            TokenStream::new()
+        }
+    };
+    push_generated(&mut sink, version, methods.clone());
+
+    // const _: () = { ... };
+    if args.assert_default {
+        push_generated(
+            &mut sink,
+            version,
+            assert_default::assert_default(&generics, &fields.list),
+        );
+    }
+
+    // #[cfg(test)] #[test] fn auto_default_self_test_foo() { ... }
+    // #[cfg(test)] #[test] fn auto_default_validate_test_foo() { ... }
+    let self_test: fn(&Ident, &DefaultValue) -> TokenStream = self_test::self_test;
+    let tests = [
+        (args.self_test, "self_test", self_test),
+        (
+            args.validate_test,
+            "validate_test",
+            self_test::validate_test,
+        ),
+    ];
+    for (enabled, arg, test) in tests {
+        let Some(value) = default_value
+            .as_ref()
+            .filter(|_| enabled && non_exhaustive.is_none() && !strip_values)
+        else {
+            continue;
+        };
+        if let Some(span) = generics_span {
+            compile_errors.push(CompileError::new(
+                ErrorCode::Misplaced,
+                span,
+                format!("`#[auto_default({arg})]` is not supported on generic items"),
+            ));
+        } else if has_default_values(value.fields(&fields.list), arg, compile_errors) {
+            push_generated(&mut sink, version, test(&item_ident, value));
+        }
+    }
+
+    // #[unsafe(no_mangle)] pub extern "C" fn foo_default() -> Foo { ... }
+    if let Some(extern_c) = &args.extern_c
+        && non_exhaustive.is_none()
+        && !strip_values
+    {
+        if item_kind == ItemKind::Enum {
+            compile_errors.push(CompileError::new(
+                ErrorCode::Misplaced,
+                extern_c.arg.span(),
+                "`#[auto_default(extern_c)]` is only supported on structs",
+            ));
+        } else if !container_args.repr_c {
+            compile_errors.push(
+                CompileError::new(
+                    ErrorCode::Misplaced,
+                    extern_c.arg.span(),
+                    "`#[auto_default(extern_c)]` requires the struct to be `#[repr(C)]`",
+                )
+                .help("add `#[repr(C)]`, so that C code can use the struct that is returned"),
+            );
+        } else if let Some(span) = generics_span {
+            compile_errors.push(CompileError::new(
+                ErrorCode::Misplaced,
+                span,
+                "`#[auto_default(extern_c)]` is not supported on generic items",
+            ));
+        } else if let Some(value) = &default_value
+            && has_default_values(value.fields(&fields.list), "extern_c", compile_errors)
+        {
+            push_generated(
+                &mut sink,
+                version,
+                extern_c::extern_c(&item_ident, vis, value, extern_c.name.as_ref()),
+            );
+        }
+    }
+
+    // #[wasm_bindgen] impl Foo { #[wasm_bindgen(constructor)] pub fn new() -> Foo { ... } }
+    if let Some(wasm) = &args.wasm
+        && non_exhaustive.is_none()
+        && !strip_values
+    {
+        if item_kind == ItemKind::Enum {
+            compile_errors.push(CompileError::new(
+                ErrorCode::Misplaced,
+                wasm.arg.span(),
+                "`#[auto_default(wasm)]` is only supported on structs",
+            ));
+        } else if let Some(span) = generics_span {
+            compile_errors.push(
+                CompileError::new(
+                    ErrorCode::Misplaced,
+                    span,
+                    "`#[auto_default(wasm)]` is not supported on generic items",
+                )
+                .help("`#[wasm_bindgen]` doesn't support generic structs"),
+            );
+        } else if let Some(value) = &default_value {
+            push_generated(
+                &mut sink,
+                version,
+                wasm::wasm(&item_ident, value, &fields.list, wasm.setters),
+            );
+        }
+    }
+
+    // impl Default for Foo { ... }
+    if impl_default
+        && !((non_exhaustive.is_some() || args.const_if.is_some()) && container_args.derive_default)
+        && let Some(value) = &default_value
+    {
+        push_generated(
+            &mut sink,
+            version,
+            impl_default::impl_default(
+                &item_ident,
+                &generics,
+                value,
+                &fields.list,
+                args,
+                non_exhaustive.is_some() || strip_values,
+            ),
+        );
+    }
+
+    // impl ConstDefault for Foo { ... }
+    if args.impl_const_default
+        && non_exhaustive.is_none()
+        && !strip_values
+        && let Some(value) = &default_value
+    {
+        push_generated(
+            &mut sink,
+            version,
+            impl_default::impl_const_default(&item_ident, &generics, value, &fields.list),
+        );
+    }
+
+    // Only warn once the item is otherwise correct, so the warning doesn't add noise to errors
+    let noop = if !compile_errors.is_empty()
+        || fields.changed > 0
+        || !methods.is_empty()
+        || args.self_test
+        || args.validate_test
+        || args.extern_c.is_some()
+        || args.wasm.is_some()
+        || args.assert_default
+        || impl_default
+        || first_variant_default
+        || args.impl_const_default
+    {
+        None
+    } else if fields.total == 0 {
+        Some("`#[auto_default]` does nothing, because this item has no named fields")
+    } else if args.check {
+        None
+    } else {
+        Some(
+            "`#[auto_default]` does nothing, because every field already has a default value or is skipped",
+        )
+    };
+
+    // A derive can't change the item, so only the generated items are emitted
+    if args.derive {
+        sink.drain(..item_end);
+    }
+
+    Expansion {
+        item: sink.into_iter().collect(),
+        noop,
+        warnings: fields.warnings,
+    }
+}
+
+/// Reports groups whose `reset_{group}` method would have the same name as another method
+/// generated by `#[auto_default(reset)]`
+fn check_groups(fields: &[Field], compile_errors: &mut Vec<CompileError>) {
+    for (group, _) in methods::groups(fields) {
+        match methods::group_conflict(fields, group) {
+            Some(GroupConflict::Method) => compile_errors.push(CompileError::new(
+                ErrorCode::Conflict,
+                group.span(),
+                format!(
+                    "a group can't be named `{group}`, because `reset_{group}` is already a method"
+                ),
+            )),
+            Some(GroupConflict::Field(field)) => compile_errors.push(
+                CompileError::new(
+                    ErrorCode::Conflict,
+                    group.span(),
+                    format!(
+                        "the group `{group}` has the same name as a field, \
+                         so both would get a `reset_{group}` method"
+                    ),
+                )
+                .note(field.ident.span(), "the field is here"),
+            ),
+            None => {}
+        }
+    }
+}
+
+/// Expansion that emits the item unchanged
+///
+/// Nothing is emitted for `#[derive(AutoDefault)]`, since the item is already emitted
+fn unchanged(sink: Sink, args: &Args, noop: Option<&'static str>) -> Expansion {
+    Expansion {
+        item: if args.derive {
+            TokenStream::new()
+        } else {
+            sink.into_iter().collect()
+        },
+        noop,
+        warnings: Vec::new(),
+    }
+}
+
+/// Error for a token that can't be in the item at this position, or for the end of the item
+/// if `tt` is `None`, in which case the error points at `before`
+///
+/// These don't come from code that `rustc` parses as an item, but other macros can generate
+/// them, such as with groups without delimiters around fragments of `macro_rules!`
+fn unexpected(tt: Option<TokenTree>, before: Span, expected: &str) -> CompileError {
+    match tt {
+        Some(tt) => CompileError::new(
+            ErrorCode::InvalidItem,
+            tt.span(),
+            format!("expected {expected}, found `{tt}`"),
+        ),
+        None => CompileError::new(
+            ErrorCode::InvalidItem,
+            before,
+            format!("expected {expected}, found the end of the item"),
+        ),
+    }
+}
+
+/// If an [`unexpected`] token was found since `errors_before` errors were reported,
+/// expansion that emits the `original` item without the attributes of `#[auto_default]`
+///
+/// Only the first of these errors is kept, since the errors after it are caused
+/// by parsing the rest of the item from the wrong position
+fn unparsed(
+    original: &TokenStream,
+    args: &Args,
+    errors_before: usize,
+    errors: &mut Vec<CompileError>,
+) -> Option<Expansion> {
+    let i = errors_before
+        + errors[errors_before..]
+            .iter()
+            .position(|error| matches!(error.code, ErrorCode::InvalidItem))?;
+    let error = errors.remove(i);
+    errors.truncate(i);
+    errors.push(error);
+
+    let item = strip_helper_attrs(original.clone(), args);
+    Some(unchanged(item.into_iter().collect(), args, None))
+}
+
+struct IsSkip(bool);
+
+/// Named fields of the item
+struct Fields {
+    /// If `list` should be filled. It is only needed for generating methods,
+    /// so large items don't pay for copying every field otherwise
+    collect: bool,
+    /// If the default field values are removed from the item, because it is `#[non_exhaustive]`
+    strip_values: bool,
+    /// All named fields, including the fields of enum variants
+    list: Vec<Field>,
+    /// How many named fields there are
+    total: usize,
+    /// How many fields got a default value or documentation
+    changed: usize,
+    /// Whether each of `args.skip_fields.names` is a field of the item
+    skip_fields_found: Vec<bool>,
+    /// Path of the struct, such as `Foo::<T>`, whose functions `#[serde(default = "...")]`
+    /// refers to, if `#[auto_default(serde_defaults)]` is used
+    serde_defaults: Option<String>,
+    /// Warnings about the fields, such as from `#[auto_default(warn_redundant)]`
+    warnings: Vec<CompileError>,
+    /// Names of the type and const parameters of the item, which the `static`
+    /// of `#[auto_default(lazy = ...)]` can't use
+    type_params: Vec<String>,
+}
+
+/// A named field
+///
+/// ```text
+/// field: Type = default
+/// ```
+pub struct Field {
+    /// Name of the field
+    pub ident: Ident,
+    /// Type of the field
+    pub ty: Vec<TokenTree>,
+    /// Default value of the field, if it has one
+    pub default: Option<Vec<TokenTree>>,
+    /// Value from `#[auto_default(runtime = expr)]`, which is only used by `impl_default`
+    pub runtime: Option<TokenStream>,
+    /// The value is from `#[auto_default(value = expr)]`, so it can use the default values
+    /// of the fields before it
+    pub refers_to_fields: bool,
+    /// If the default value is written by hand, rather than added by the macro
+    pub custom: bool,
+    /// `#[cfg(...)]` attributes of the field, which also apply to code generated for the field
+    pub cfg: Vec<TokenTree>,
+    /// If the field gets `#[serde(default = "...")]` from `#[auto_default(serde_defaults)]`
+    pub serde_default: bool,
+    /// `#[auto_default(group = "...")]`
+    pub group: Option<Ident>,
+}
+
+/// What `Default::default()` returns: the struct, or the variant of the enum marked `#[default]`
+pub struct DefaultValue {
+    /// `Foo` or `Foo::Variant`
+    pub path: TokenStream,
+    /// Where the fields of the struct or the variant are in [`Fields::list`],
+    /// unless it is a unit variant
+    pub fields: Option<Range<usize>>,
+}
+
+impl DefaultValue {
+    /// Named fields of the struct or the variant
+    pub fn fields<'a>(&self, list: &'a [Field]) -> &'a [Field] {
+        self.fields.clone().map_or(&[], |fields| &list[fields])
+    }
+}
+
+/// Where an `#[auto_default(...)]` attribute is placed
+#[derive(Clone, Copy, PartialEq)]
+pub enum Position {
+    /// On the `struct` or `enum` itself
+    Container,
+    /// On a variant of an `enum`
+    Variant,
+    /// On a named field
+    Field,
+}
+
+/// Streams enum variant discriminant + comma at the end from `source` into `sink`
+///
+/// enum Example {
+///     Three,
+///          ^
+///     Two(u32) = 2,
+///             ^^^^^
+///     Four { hello: u32 } = 4,
+///                        ^^^^^
+/// }
+fn stream_enum_variant_discriminant_and_comma(
+    source: &mut Source,
+    sink: &mut Sink,
+    errors: &mut Vec<CompileError>,
+) {
+    match source.next() {
+        // No discriminant, there may be another variant after this
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {
+            sink.push(TokenTree::Punct(punct));
+        }
+        // No discriminant, this is the final enum variant
+        None => {}
+        // Enum variant has a discriminant
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+            sink.push(TokenTree::Punct(punct));
+
+            // Stream discriminant expression from `source` into `sink`
+            loop {
+                match source.next() {
+                    // End of discriminant, there may be a variant after this
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {
+                        sink.push(TokenTree::Punct(punct));
+                        break;
+                    }
+                    // This token is part of the variant's expression
+                    Some(tt) => {
+                        sink.extend([tt]);
+                    }
+                    // End of discriminant, this is the last variant
+                    None => break,
+                }
+            }
+        }
+        tt => {
+            let before = sink.last().map_or_else(Span::call_site, TokenTree::span);
+            errors.push(unexpected(tt, before, "`=` or `,` after the variant"));
+        }
+    }
+}
+
+pub type Source = Peekable<proc_macro::token_stream::IntoIter>;
+type Sink = Vec<TokenTree>;
+
+/// Streams the identifier from `input` into `output`, returning it, if the identifier exists
+fn stream_ident(source: &mut Source, sink: &mut Sink) -> Option<Ident> {
+    let Some(TokenTree::Ident(ident)) = source.next_if(|tt| matches!(tt, TokenTree::Ident(_)))
+    else {
+        return None;
+    };
+    sink.push(TokenTree::Ident(ident.clone()));
+    Some(ident)
+}
+
+// Parses attributes
+//
+// #[attr] #[attr] pub field: Type
+// #[attr] #[attr] struct Foo
+// #[attr] #[attr] enum Foo
+//
+// Returns the options of all `#[auto_default(...)]` attributes that were encountered,
+// including `#[auto_default::skip]` and the `alias` of `#[auto_default(skip)]`
+fn stream_attrs(
+    source: &mut Source,
+    sink: &mut Sink,
+    errors: &mut Vec<CompileError>,
+    position: Position,
+    args: &Args,
+) -> FieldArgs {
+    let mut field_args = FieldArgs::default();
+
+    loop {
+        if !matches!(source.peek(), Some(TokenTree::Punct(hash)) if hash.as_char() == '#') {
+            // #[auto_default(reason = "...")]
+            //                ^^^^^^ without `skip`, there is nothing to explain
+            if let Some(reason) = &field_args.reason
+                && field_args.skip.is_none()
+            {
+                errors.push(CompileError::new(
+                    ErrorCode::Unused,
+                    reason.span(),
+                    "`reason` can only be used together with `#[auto_default(skip)]`",
+                ));
+            }
+            break field_args;
+        };
+
+        // #[some_attr]
+        // ^
+        let pound = source.next();
+
+        // #[some_attr]
+        //  ^^^^^^^^^^^
+        let attr = match source.next() {
+            Some(TokenTree::Group(attr)) => attr,
+            tt => {
+                let pound_span = pound.as_ref().map_or_else(Span::call_site, TokenTree::span);
+                errors.push(unexpected(tt, pound_span, "`[...]` after `#`"));
+                break field_args;
+            }
+        };
+        #[cfg(feature = "full-parse")]
+        let attr = crate::full_parse::attr(attr);
+
+        // #[some_attr = hello]
+        //   ^^^^^^^^^^^^^^^^^
+        let mut attr_tokens = attr.stream().into_iter().peekable();
+
+        // Check if this attribute is `#[auto_default(...)]`
+        if let Some(helper_args) = helper_attribute(&mut attr_tokens, args, errors) {
+            field_args.parse(helper_args, position, errors);
+            continue;
+        }
+
+        // Code generated for a field only exists if the field does
+        //
+        // #[cfg(feature = "serde")]
+        // ^^^^^^^^^^^^^^^^^^^^^^^^^
+        if matches!(attr_tokens.peek(), Some(TokenTree::Ident(cfg)) if cfg.is("cfg")) {
+            field_args.cfg.extend(pound.clone());
+            field_args.cfg.push(TokenTree::Group(attr.clone()));
+        }
+
+        // `#[auto_default(clap)]` does not add `#[arg(default_value_t = ...)]` to these fields
+        //
+        // #[arg(default_value_t = 4)]
+        // #[command(flatten)]
+        if let Some(TokenTree::Ident(path)) = attr_tokens.peek()
+            && (path.is("arg") || path.is("command") || path.is("clap"))
+            && let Some(TokenTree::Group(clap_args)) = attr.stream().into_iter().nth(1)
+        {
+            field_args.clap_skip |= clap_args.stream().into_iter().any(|tt| {
+                matches!(tt, TokenTree::Ident(arg) if {
+                    let arg = arg.to_string();
+                    arg.starts_with("default_value") || arg == "flatten" || arg == "subcommand"
+                })
+            });
+        }
+
+        // `#[auto_default(from_serde)]` uses the default value of `serde` for the field,
+        // and `#[auto_default(serde_defaults)]` doesn't add another one
+        //
+        // #[serde(default = "default_port")]
+        //         ^^^^^^^^^^^^^^^^^^^^^^^^
+        if (args.from_serde || args.serde_defaults)
+            && position == Position::Field
+            && matches!(attr_tokens.peek(), Some(TokenTree::Ident(serde)) if serde.is("serde"))
+            && let Some(TokenTree::Group(serde_args)) = attr.stream().into_iter().nth(1)
+            && serde_args.delimiter() == Delimiter::Parenthesis
+        {
+            field_args.parse_serde(serde_args.stream());
+        }
+
+        // Attribute macros below `#[auto_default]` are applied to the item with
+        // default field values, which they may not be able to parse
+        //
+        // #[auto_default]
+        // #[pin_project]
+        //   ^^^^^^^^^^^
+        if position == Position::Container
+            && !args.derive
+            && let Some(name) = attr_name(&attr)
+            && BEFORE_AUTO_DEFAULT.iter().any(|before| name.is(before))
+        {
+            errors.push(
+                CompileError::new(
+                    ErrorCode::Misplaced,
+                    name.span(),
+                    format!("`#[{name}]` must be placed above `#[auto_default]`"),
+                )
+                .help(format!(
+                    "`#[{name}]` can't parse default field values, \
+                     so it has to be applied before `#[auto_default]` adds them"
+                )),
+            );
+            field_args.before_auto_default = true;
+        }
+
+        // Other crates can't write `Foo { .. }`
+        //
+        // #[non_exhaustive]
+        //   ^^^^^^^^^^^^^^
+        if position == Position::Container
+            && let Some(TokenTree::Ident(non_exhaustive)) = attr_tokens.peek()
+            && non_exhaustive.is("non_exhaustive")
+        {
+            field_args.non_exhaustive = Some(non_exhaustive.span());
+        }
+
+        // #[repr(C, align(8))]
+        //        ^
+        if position == Position::Container
+            && matches!(attr_tokens.peek(), Some(TokenTree::Ident(repr)) if repr.is("repr"))
+            && let Some(TokenTree::Group(reprs)) = attr.stream().into_iter().nth(1)
+        {
+            field_args.repr_c |= reprs
+                .stream()
+                .into_iter()
+                .any(|tt| matches!(tt, TokenTree::Ident(c) if c.is("C")));
+        }
+
+        // #[derive(Debug, Default)]
+        //                 ^^^^^^^
+        if position == Position::Container
+            && matches!(attr_tokens.peek(), Some(TokenTree::Ident(derive)) if derive.is("derive"))
+            && let Some(TokenTree::Group(derives)) = attr.stream().into_iter().nth(1)
+        {
+            field_args.derive_default |= derives
+                .stream()
+                .into_iter()
+                .any(|tt| matches!(tt, TokenTree::Ident(default) if default.is("Default")));
+        }
+
+        // The variant that `Default::default()` returns
+        //
+        // #[default]
+        //   ^^^^^^^
+        if position == Position::Variant
+            && matches!(attr_tokens.peek(), Some(TokenTree::Ident(default)) if default.is("default"))
+            && attr.stream().into_iter().nth(1).is_none()
+        {
+            field_args.default_variant = true;
+
+            // `#[default]` belongs to `#[derive(Default)]`, which is replaced by `impl_default`
+            if args.impl_default || args.const_if.is_some() {
+                continue;
+            }
+        }
+
+        // #[derive(SmartDefault)]
+        //          ^^^^^^^^^^^^ disagrees with the default field values
+        if let Some(replacement) =
+            derives::foreign_attr(&attr, position, args, &mut field_args, errors)
+        {
+            sink.extend(replacement);
+            continue;
+        }
+
+        // Not our attribute, so none of its tokens were consumed and it is kept as-is
+        //
+        // #[attr]
+        // ^^^^^^^
+        sink.extend(pound);
+        sink.extend([TokenTree::Group(attr)]);
+    }
+}
+
+/// Attribute macros that can't parse default field values, because they parse the item with `syn`
+///
+/// They must be placed above `#[auto_default]`, so that they are applied before the default
+/// field values are added
+const BEFORE_AUTO_DEFAULT: &[&str] = &["pin_project", "serde_as", "wasm_bindgen"];
+
+/// Last segment of the path of the attribute
+///
+/// ```text
+/// #[pin_project::pin_project(project = Projection)]
+///                ^^^^^^^^^^^
+/// ```
+fn attr_name(attr: &Group) -> Option<Ident> {
+    let mut name = None;
+    for tt in attr.stream() {
+        match tt {
+            TokenTree::Ident(ident) => name = Some(ident),
+            TokenTree::Punct(colon) if colon.as_char() == ':' => {}
+            _ => break,
+        }
+    }
+    name
+}
+
+/// Removes `#[auto_default(...)]` attributes from the start of `item`, returning their arguments
+/// and the rest of the item
+///
+/// The arguments are `None` if the item has no `#[auto_default]` attribute
+#[allow(
+    dead_code,
+    reason = "`auto-default-core` only uses it with the `testing` feature"
+)]
+pub fn take_container_args(item: TokenStream) -> (Option<TokenStream>, TokenStream) {
+    let mut source = item.into_iter().peekable();
+    let mut args: Option<TokenStream> = None;
+    let mut rest = TokenStream::new();
+
+    // #[auto_default(infer_literals)] #[derive(Debug)] pub struct User { ... }
+    // ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ ^^^^^^^^^^^^^^^^
+    while let Some(pound) =
+        source.next_if(|tt| matches!(tt, TokenTree::Punct(hash) if hash.as_char() == '#'))
+    {
+        // Reported when the rest of the item is expanded
+        let Some(TokenTree::Group(attr)) = source.next_if(|tt| matches!(tt, TokenTree::Group(_)))
+        else {
+            rest.extend([pound]);
+            break;
+        };
+
+        // #[auto_default]
+        // #[auto_default(infer_literals)]
+        // #[auto_default::auto_default(infer_literals)]
+        let tokens = attr.stream().into_iter().collect::<Vec<_>>();
+        let path = match tokens.as_slice() {
+            [
+                TokenTree::Ident(krate),
+                TokenTree::Punct(colon),
+                TokenTree::Punct(_),
+                path @ ..,
+            ] if krate.is("auto_default") && colon.as_char() == ':' => path,
+            path => path,
+        };
+        let attr_args = match path {
+            [TokenTree::Ident(ident)] if ident.is("auto_default") => Some(TokenStream::new()),
+            [TokenTree::Ident(ident), TokenTree::Group(group)]
+                if ident.is("auto_default") && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                Some(group.stream())
+            }
+            _ => None,
+        };
+
+        match attr_args {
+            Some(attr_args) => {
+                let args = args.get_or_insert_default();
+                if !args.is_empty() && !attr_args.is_empty() {
+                    args.extend([TokenTree::Punct(Punct::new(',', Spacing::Alone))]);
+                }
+                args.extend(attr_args);
+            }
+            None => rest.extend([pound, TokenTree::Group(attr)]),
+        }
+    }
+
+    rest.extend(source);
+    (args, rest)
+}
+
+/// If `source` is `auto_default(...)`, returns the tokens inside of the parentheses
+///
+/// With `#[auto_default(helper = adf)]` on the item, `adf(...)` is recognized instead.
+/// The short spellings of `auto_default(skip)` are also recognized:
+///
+/// ```text
+/// #[auto_default::skip]
+/// #[auto_default::skip(reason = "...")]
+/// #[alias]
+/// #[alias(reason = "...")]
+/// ```
+///
+/// The attribute is recognized even if it is malformed, in which case an error
+/// is reported and the returned stream is empty
+fn helper_attribute(
+    source: &mut Source,
+    args: &Args,
+    errors: &mut Vec<CompileError>,
+) -> Option<TokenStream> {
+    let Some(TokenTree::Ident(ident)) = source.peek().cloned() else {
+        return None;
+    };
+
+    // #[adskip]
+    //   ^^^^^^
+    if let Some(alias) = &args.alias
+        && ident.is(&alias.to_string())
+    {
+        source.next();
+        let name = ident.to_string();
+        return Some(skip_args(ident, &name, source, errors));
+    }
+
+    // #[auto_default(helper = adf)]
+    //
+    // #[adf(skip)]
+    //   ^^^
+    let is_helper = args
+        .helper
+        .as_ref()
+        .is_some_and(|helper| ident.is(&helper.to_string()));
+    if !is_helper && !ident.is("auto_default") {
+        return None;
+    };
+
+    // #[auto_default(skip)]
+    //   ^^^^^^^^^^^^
+    source.next();
+
+    // With a `helper`, `#[auto_default(...)]` belongs to another macro
+    if !is_helper
+        && args.helper.is_some()
+        && !matches!(source.peek(), Some(TokenTree::Punct(colon)) if colon.as_char() == ':')
+    {
+        return None;
+    }
+
+    // #[auto_default::skip]
+    //               ^^
+    if !is_helper
+        && matches!(source.peek(), Some(TokenTree::Punct(colon)) if colon.as_char() == ':')
+    {
+        source.next();
+        source.next_if(|tt| matches!(tt, TokenTree::Punct(colon) if colon.as_char() == ':'));
+
+        return match source.next() {
+            Some(TokenTree::Ident(skip)) if skip.is("skip") => {
+                Some(skip_args(skip, "auto_default::skip", source, errors))
+            }
+            tt => {
+                errors.push(CompileError::new(
+                    ErrorCode::InvalidArgument,
+                    tt.as_ref().map_or_else(|| ident.span(), TokenTree::span),
+                    "expected `auto_default::skip`",
+                ));
+                Some(TokenStream::new())
+            }
+        };
+    }
+
+    // We know it is `#[auto_default ???]`, we need to validate that `???`
+    // is `(...)` now
+
+    // #[auto_default(skip)]
+    //   ^^^^^^^^^^^^
+    let auto_default_span = ident.span();
+
+    // #[auto_default(skip)]
+    //               ^^^^^^
+    let group = match source.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => group,
+        Some(tt) => {
+            errors.push(CompileError::new(
+                ErrorCode::InvalidArgument,
+                tt.span(),
+                "expected `(skip)`",
+            ));
+            return Some(TokenStream::new());
+        }
+        None => {
+            errors.push(CompileError::new(
+                ErrorCode::InvalidArgument,
+                auto_default_span,
+                "expected `(skip)` after this",
+            ));
+            return Some(TokenStream::new());
+        }
+    };
+
+    // #[auto_default(skip)]
+    //                ^^^^
+    let inside = group.stream();
+
+    if inside.is_empty() {
+        errors.push(CompileError::new(
+            ErrorCode::InvalidArgument,
+            group.span(),
+            "expected `(skip)`, found `()`",
+        ));
+    }
+
+    Some(inside)
+}
+
+/// The item without the attributes of `#[auto_default]` on its fields and variants,
+/// such as `#[auto_default(skip)]`, which would be unknown attributes without the macro
+///
+/// Used when the macro is turned off, so the item is otherwise emitted as it was written
+pub fn strip_helper_attrs(item: TokenStream, args: &Args) -> TokenStream {
+    let mut output = Vec::new();
+    let mut source = item.into_iter().peekable();
+    while let Some(tt) = source.next() {
+        match tt {
+            // #[auto_default(skip)]
+            // ^^^^^^^^^^^^^^^^^^^^^
+            TokenTree::Punct(pound)
+                if pound.as_char() == '#'
+                    && matches!(source.peek(), Some(TokenTree::Group(attr))
+                        if attr.delimiter() == Delimiter::Bracket
+                            && helper_attribute(
+                                &mut attr.stream().into_iter().peekable(),
+                                args,
+                                &mut Vec::new(),
+                            )
+                            .is_some()) =>
+            {
+                source.next();
+            }
+            // struct Foo { ... }
+            //            ^^^^^^^
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                let mut stripped =
+                    Group::new(Delimiter::Brace, strip_helper_attrs(group.stream(), args));
+                stripped.set_span(group.span());
+                output.push(TokenTree::Group(stripped));
+            }
+            tt => output.push(tt),
+        }
+    }
+    output.into_iter().collect()
+}
+
+/// Arguments of a short spelling of `#[auto_default(skip)]`, such as `#[auto_default::skip]`
+///
+/// ```text
+/// #[auto_default::skip(reason = "...")] => skip, reason = "..."
+///                 ^^^^^^^^^^^^^^^^^^^^
+/// ```
+///
+/// `skip` is the last identifier of the attribute's path, so that errors point at it.
+/// `name` is the whole path of the attribute
+fn skip_args(
+    skip: Ident,
+    name: &str,
+    source: &mut Source,
+    errors: &mut Vec<CompileError>,
+) -> TokenStream {
+    let skip_span = skip.span();
+    let mut args = TokenStream::from(TokenTree::Ident(Ident::new("skip", skip_span)));
+
+    match source.next() {
+        None => {}
+        // #[auto_default::skip(reason = "...")]
+        //                     ^^^^^^^^^^^^^^^^^
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            args.extend([TokenTree::Punct(Punct::new(',', Spacing::Alone)).with_span(skip_span)]);
+            args.extend(group.stream());
+        }
+        Some(tt) => errors.push(CompileError::new(
+            ErrorCode::InvalidArgument,
+            tt.span(),
+            format!("expected `#[{name}]` or `#[{name}(reason = \"...\")]`"),
+        )),
+    }
+
+    args
+}
+
+fn stream_vis(source: &mut Source, sink: &mut Sink) {
+    #[cfg(feature = "full-parse")]
+    if let Some(vis) = crate::full_parse::vis(source) {
+        sink.extend(vis);
+        return;
+    }
+
+    // `$vis:vis` of `macro_rules!` is in a group without delimiters, which is empty
+    // if the visibility is inherited
+    if let Some(TokenTree::Group(group)) = source.peek()
+        && group.delimiter() == Delimiter::None
+        && group
+            .stream()
+            .into_iter()
+            .next()
+            .is_none_or(|tt| matches!(tt, TokenTree::Ident(vis) if vis.is("pub")))
+    {
+        sink.extend(source.next());
+        return;
+    }
+
+    // Remove visibility if it is present
+    //
+    // pub(in crate) struct
+    // ^^^^^^^^^^^^^
+    if let Some(TokenTree::Ident(vis)) = source.peek()
+        && vis.is("pub")
+    {
+        // pub(in crate) struct
+        // ^^^
+        sink.extend(source.next());
+
+        if let Some(TokenTree::Group(group)) = source.peek()
+            && let Delimiter::Parenthesis = group.delimiter()
+        {
+            // pub(in crate) struct
+            //    ^^^^^^^^^^
+            sink.extend(source.next());
+        }
+    };
+}
+
+#[derive(PartialEq)]
+enum ItemKind {
+    Struct,
+    Enum,
+}
+
+/// `fields` is [`StructFields`] in the grammar.
+///
+/// It is the curly braces, and everything within, for a struct with named fields,
+/// or an enum variant with named fields.
+///
+/// These fields are transformed by adding `= Default::default()` to every
+/// field that doesn't already have a default value.
+///
+/// If a field is marked with `#[auto_default(skip)]`, no default value will be
+/// added
+///
+/// [`StructFields`]: https://doc.rust-lang.org/reference/items/structs.html#grammar-StructFields
+fn add_default_field_values(
+    fields: Group,
+    args: &Args,
+    default_template: &[TokenTree],
+    compile_errors: &mut Vec<CompileError>,
+    item_fields: &mut Fields,
+    is_skip_variant: IsSkip,
+    values: &[(Ident, TokenStream)],
+) -> Group {
+    // All the tokens corresponding to the struct's field, passed by the user
+    // These tokens will eventually all be sent to `output_fields`,
+    // plus a few extra for any `Default::default()` that we output
+    let mut input_fields = fields.stream().into_iter().peekable();
+
+    // The tokens corresponding to the fields of the output struct
+    let mut output_fields = Sink::new();
+
+    // Whether each of `values` is a field of the struct or the variant
+    let mut values_found = vec![false; values.len()];
+
+    // Parses all fields.
+    // Each iteration parses a single field
+    loop {
+        let mut field_args = stream_attrs(
+            &mut input_fields,
+            &mut output_fields,
+            compile_errors,
+            Position::Field,
+            args,
+        );
+        // #[auto_default(runtime = Box::new(Backend::new()))]
+        //                          ^^^^^^^^^^^^^^^^^^^^^^^^^ only used by `impl Default`
+        let runtime = match field_args.default.as_ref() {
+            Some(FieldDefault {
+                span,
+                kind:
+                    kind @ (DefaultKind::Runtime(value)
+                    | DefaultKind::Value(value)
+                    | DefaultKind::Boxed(value)
+                    | DefaultKind::Lazy(value)),
+            }) => {
+                if !args.impl_default {
+                    compile_errors.push(
+                        CompileError::new(
+                            ErrorCode::Misplaced,
+                            *span,
+                            format!(
+                                "`#[auto_default({})]` can only be used together with \
+                                 `#[auto_default(impl_default)]` on the item",
+                                kind.syntax()
+                            ),
+                        )
+                        .note(
+                            value
+                                .clone()
+                                .into_iter()
+                                .next()
+                                .map_or(*span, |tt| tt.span()),
+                            match kind {
+                                DefaultKind::Boxed(_) => {
+                                    "default field values must be `const`, and values can't be \
+                                     allocated in `const`, so this value can only be used by the \
+                                     generated implementation of `Default`"
+                                }
+                                DefaultKind::Lazy(_) => {
+                                    "default field values are evaluated every time, so this value \
+                                     can only be kept and cloned by the generated implementation \
+                                     of `Default`"
+                                }
+                                _ => {
+                                    "default field values must be `const`, so this value can only \
+                                     be used by the generated implementation of `Default`"
+                                }
+                            },
+                        )
+                        .help("add `impl_default` to `#[auto_default(...)]` on the item"),
+                    );
+                }
+                Some(kind)
+            }
+            _ => None,
+        };
+        if let Some(field_default) = &field_args.default
+            && let Some(skip) = field_args.skip
+        {
+            compile_errors.push(
+                CompileError::new(
+                    ErrorCode::Conflict,
+                    field_default.span,
+                    format!(
+                        "`#[auto_default({})]` cannot be used together with `#[auto_default(skip)]`",
+                        field_default.kind.syntax()
+                    ),
+                )
+                .note(skip, "`#[auto_default(skip)]` is here"),
+            );
+        }
+
+        // #[doc = "Has no default value:"]
+        // #[doc = "reason"]
+        if let Some(reason) = field_args
+            .reason
+            .clone()
+            .filter(|_| field_args.skip.is_some())
+        {
+            output_fields.extend(doc_reason("Has no default value:", reason));
+        }
+
+        if let Some(include) = field_args.include
+            && let Some(skip) = field_args.skip
+        {
+            compile_errors.push(
+                CompileError::new(
+                    ErrorCode::Conflict,
+                    include,
+                    "`#[auto_default(include)]` cannot be used together with `#[auto_default(skip)]`",
+                )
+                .note(skip, "`#[auto_default(skip)]` is here"),
+            );
+        }
+
+        // Tokens of this field after its attributes. These are only sent to `output_fields`
+        // once we know the default value, because we may add attributes to the field
+        let mut field = Sink::new();
+
+        stream_vis(&mut input_fields, &mut field);
+        let Some(field_ident) = stream_ident(&mut input_fields, &mut field) else {
+            // No fields. e.g.: `struct Struct {}`
+            break;
+        };
+        let field_ident_span = field_ident.span();
+
+        // #[auto_default(values(timeout = Duration::ZERO))]
+        //                       ^^^^^^^^^^^^^^^^^^^^^^^^ default field value of `timeout`
+        if let Some(i) = values
+            .iter()
+            .position(|(name, _)| field_ident.is(&name.to_string()))
+        {
+            values_found[i] = true;
+            let (name, value) = &values[i];
+            let other = match (&field_args.default, &field_args.foreign_default) {
+                (Some(default), _) => Some((
+                    default.span,
+                    format!("#[auto_default({})]", default.kind.syntax()),
+                )),
+                (None, Some((span, _))) => {
+                    Some((*span, "the default value of another crate".into()))
+                }
+                (None, None) => field_args
+                    .skip
+                    .map(|skip| (skip, "#[auto_default(skip)]".into())),
+            };
+            if let Some((span, other)) = other {
+                compile_errors.push(
+                    CompileError::new(
+                        ErrorCode::Conflict,
+                        name.span(),
+                        format!("`{name}` in `values(...)` cannot be used together with `{other}`"),
+                    )
+                    .note(span, format!("`{other}` is here")),
+                );
+            } else {
+                field_args.listed_default = Some((name.span(), value.clone()));
+            }
+        }
+
+        // field: Type
+        //      ^
+        field.extend(input_fields.next());
+
+        // Added to `field` once we know the default value, so it doesn't need to be cloned
+        //
+        // field: Type
+        //        ^^^^
+        let field_ty = parse_type(&mut input_fields);
+
+        // #[auto_default(skip_fields(prefix = "raw_", names(handle)))]
+        // #[auto_default(skip_underscored)]
+        // skip_types = ["RawFd"] in `auto_default.toml`
+        //
+        // Options of the field take precedence, like they do over other options of the container
+        let skipped_by_name = args
+            .skip_fields
+            .names
+            .iter()
+            .position(|skipped| field_ident.is(&skipped.to_string()));
+        if let Some(i) = skipped_by_name {
+            item_fields.skip_fields_found[i] = true;
+        }
+        let skipped_by_field_name = skipped_by_name.is_some()
+            || ((args.skip_underscored || !args.skip_fields.prefixes.is_empty()) && {
+                let name = methods::unraw(&field_ident);
+                (args.skip_underscored && name.starts_with('_'))
+                    || args
+                        .skip_fields
+                        .prefixes
+                        .iter()
+                        .any(|prefix| name.starts_with(prefix))
+            });
+
+        // _marker: PhantomData<T> = ::core::marker::PhantomData
+        //                           ^^^^^^^^^^^^^^^^^^^^^^^^^^^ even if the variant or a name
+        //                                                       based option skips the field
+        //
+        // `PhantomData` always has a default value, so skipping it only breaks `Foo { .. }`.
+        // A type in `skip_types` names the field's type, so that skip is kept
+        let phantom_data = !args.skip_phantom_data
+            && field_args.skip.is_none()
+            && field_args.default.is_none()
+            && type_name(&field_ty).is_some_and(|name| name.is("PhantomData"))
+            && ((is_skip_variant.0 && field_args.include.is_none()) || skipped_by_field_name);
+
+        // #[auto_default(include)] takes precedence over the skip of the variant
+        let is_skip = field_args.skip.is_some()
+            || (is_skip_variant.0 && field_args.include.is_none() && !phantom_data);
+
+        let skipped_by_container = field_args.default.is_none()
+            && field_args.attr_value().is_none()
+            && (!args.from_serde || field_args.serde_default.is_none())
+            && ((skipped_by_field_name && !phantom_data)
+                || args
+                    .policy
+                    .as_ref()
+                    .is_some_and(|policy| policy.skips(&field_ty)));
+        let skip_by_container = skipped_by_container && field_args.include.is_none();
+
+        // #[auto_default(boxed = Backend)]
+        //
+        // field: Box<dyn Trait>
+        //        ^^^ Box::new(<Backend as ::core::default::Default>::default())
+        // #[auto_default(value = buffer_size * 2)]
+        //                       ^^^^^^^^^^^ default value of an earlier field
+        let refers_to_fields = matches!(runtime, Some(DefaultKind::Value(_)));
+        let runtime = runtime.and_then(|kind| match kind {
+            DefaultKind::Boxed(concrete) => Some(boxed(&field_ty, concrete, compile_errors)),
+            DefaultKind::Runtime(value) | DefaultKind::Value(value) => Some(value.clone()),
+            DefaultKind::Lazy(value) => Some(lazy(
+                &field_ty,
+                value,
+                &item_fields.type_params,
+                compile_errors,
+            )),
+            _ => None,
+        });
+
+        // Everything after the type of the field
+        //
+        // field: Type = default
+        //             ^^^^^^^^^
+        // If we add a default value or documentation to this field
+        let mut changed = false;
+
+        // field: Type = default
+        //             ^ the default value is written by hand
+        let custom = matches!(input_fields.peek(), Some(TokenTree::Punct(eq)) if eq.as_char() == '=')
+            || field_args.foreign_default.is_some()
+            // `#[derive(AutoDefault)]` doesn't add it to the item
+            || (field_args.listed_default.is_some() && !args.derive);
+
+        // #[auto_default(include)]
+        //                ^^^^^^^ the field would get a default value anyway
+        if let Some(include) = field_args.include
+            && field_args.skip.is_none()
+            && (custom || !(is_skip_variant.0 || skipped_by_container))
+        {
+            item_fields.warnings.push(
+                CompileError::new(
+                    ErrorCode::Unused,
+                    include,
+                    if custom {
+                        "`#[auto_default(include)]` does nothing, because this field has a default value"
+                    } else {
+                        "`#[auto_default(include)]` does nothing, because this field \
+                         is not skipped by its variant or by the container"
+                    },
+                )
+                .help("remove `#[auto_default(include)]`"),
+            );
+        }
+
+        // #[doc = "Defaults to `defaults::PORT`, or `Default::default()` ..."]
+        let mut doc = None;
+
+        let default_value = match (input_fields.peek(), field_args.attr_value().cloned()) {
+            // This field has a custom default field value
+            //
+            // field: Type = default
+            //             ^
+            (Some(TokenTree::Punct(eq)), _) if eq.as_char() == '=' => {
+                let eq = eq.clone();
+                input_fields.next();
+
+                if let Some(field_default) = &field_args.default {
+                    compile_errors.push(
+                        CompileError::new(
+                            ErrorCode::Unused,
+                            field_default.span,
+                            format!(
+                                concat!(
+                                    "this field is marked `#[auto_default({})]`,",
+                                    " which does nothing since this field has a",
+                                    " default value: `= ...`"
+                                ),
+                                field_default.kind.syntax()
+                            ),
+                        )
+                        .note(eq.span(), "the default value is here"),
+                    );
+                }
+
+                // #[default(4)]
+                //   ^^^^^^^ of another crate, translated by `from_derives`
+                if let Some((span, _)) = &field_args.foreign_default {
+                    compile_errors.push(
+                        CompileError::new(
+                            ErrorCode::Conflict,
+                            *span,
+                            "this field has a default value for another crate's `Default`, \
+                             and a default field value: `= ...`",
+                        )
+                        .note(eq.span(), "the default field value is here"),
+                    );
+                }
+
+                // #[auto_default(values(field = value))]
+                //                       ^^^^^
+                if let Some((span, _)) = &field_args.listed_default {
+                    compile_errors.push(
+                        CompileError::new(
+                            ErrorCode::Conflict,
+                            *span,
+                            "this field has a default value in `values(...)`, \
+                             and a default field value: `= ...`",
+                        )
+                        .note(eq.span(), "the default field value is here"),
+                    );
+                }
+
+                // The field keeps its default value, so the skip is left over from a refactor
+                if let Some(skip) = field_args.skip
+                    && !args.allow_unused_skip
+                {
+                    let mut warning = CompileError::new(
+                        ErrorCode::Unused,
+                        skip,
+                        concat!(
+                            "this field is marked `#[auto_default(skip)]`,",
+                            " which does nothing since this field has a",
+                            " default value: `= ...`"
+                        ),
+                    )
+                    .note(eq.span(), "the default value is here")
+                    .help(
+                        "remove `#[auto_default(skip)]`, or silence this warning \
+                         with `#[auto_default(allow_unused_skip)]`",
+                    );
+                    if let Some(reason) = &field_args.reason {
+                        warning = warning.note(
+                            reason.span(),
+                            format!("it is skipped with the reason {reason}"),
+                        );
+                    }
+                    item_fields.warnings.push(warning);
+                }
+
+                // field: Type = default,
+                //               ^^^^^^^
+                let mut value = Vec::new();
+                while let Some(tt) = input_fields
+                    .next_if(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == ','))
+                {
+                    value.push(tt);
+                }
+
+                // #[auto_default(deny_redundant)]
+                //
+                // field: Type = Default::default(),
+                //               ^^^^^^^^^^^^^^^^^^ the macro adds this anyway
+                if (args.deny_redundant || args.warn_redundant)
+                    && let Some(first) = value.first()
+                    && !is_skip
+                    && !skip_by_container
+                    && adds_default_call(&field_ty, args, &field_args)
+                    && is_default_call(&value, &field_ty)
+                {
+                    let redundant = CompileError::new(
+                        ErrorCode::Redundant,
+                        first.span(),
+                        format!(
+                            "the default value of `{field_ident}` is redundant, because \
+                             `#[auto_default]` adds `Default::default()` to fields without one"
+                        ),
+                    )
+                    .help(format!(
+                        "remove `= {}`",
+                        pretty::tokens_to_string(value.iter().cloned())
+                    ));
+                    if args.deny_redundant {
+                        compile_errors.push(redundant);
+                    } else {
+                        item_fields.warnings.push(redundant);
+                    }
+                }
+
+                // field: Type = Type::default(),
+                //               ^^^^^^^^^^^^^^^ ::core::default::Default::default()
+                if args.normalize
+                    && let Some(first) = value.first()
+                    && is_default_call(&value, &field_ty)
+                    && !tokens_eq(&value, default_template)
+                {
+                    let span = first.span();
+                    value = default_template
+                        .iter()
+                        .map(|tt| tt.clone().with_span(span))
+                        .collect();
+                    changed = true;
+                }
+
+                // The value is removed, so the error is only reported once
+                //
+                // field: String = std::string::String::from("hello"),
+                //                 ^^^^^^^^^^^^^^^^^^^^^^^^^ not `const`
+                if args.derive
+                    || item_fields.strip_values
+                    || check_const(&value, &field_ty, compile_errors)
+                {
+                    Some((eq, value))
+                } else {
+                    changed = true;
+                    None
+                }
+            }
+            // Reached end of field, no custom default value. `parse_type` only stops at `,` or `=`
+            //
+            // field: Type,
+            //            ^
+            //
+            // Or reached end of input, and it has no comma. This is the last field:
+            //
+            // struct Foo {
+            //     field: Type
+            //                ^
+            // }
+            // #[derivative(Default(value = "4"))]
+            //                                ^ becomes the default field value
+            // #[auto_default(values(field = 4))]
+            //                               ^
+            (_, Some((span, value))) => {
+                let value = value.into_iter().collect::<Vec<_>>();
+                changed = true;
+                let mut eq = Punct::new('=', Spacing::Alone);
+                eq.set_span(span);
+                // With `#[derive(AutoDefault)]`, it is only used by `impl Default`
+                (args.derive
+                    || item_fields.strip_values
+                    || check_const(&value, &field_ty, compile_errors))
+                .then_some((eq, value))
+            }
+            _ => {
+                // Insert default value
+                //
+                // field: Type = Default::default(),
+                //             ^^^^^^^^^^^^^^^^^^^^
+                // The default value points at the type of the field, so that errors such as
+                // "the trait `Default` is not implemented" underline the type
+                let insert = !is_skip && !skip_by_container && runtime.is_none();
+
+                // #[auto_default(check)]
+                //
+                // Only options written on the field itself add a default value
+                let explicit = field_args.default.is_some()
+                    || (args.from_serde && field_args.serde_default.is_some());
+                if insert && args.check && !explicit && !phantom_data {
+                    compile_errors.push(
+                        CompileError::new(
+                            ErrorCode::MissingDefault,
+                            field_ident_span,
+                            format!("field `{field_ident}` has no default value"),
+                        )
+                        .help(
+                            "add a default value with `= ...` after the type of the field, \
+                             or skip the field with `#[auto_default(skip)]`",
+                        ),
+                    );
+                }
+
+                (insert && (!args.check || explicit)).then(|| {
+                    changed = true;
+                    let span = type_span(&field_ty).unwrap_or(field_ident_span);
+                    let mut eq = Punct::new('=', Spacing::Alone);
+                    eq.set_span(span);
+                    let value = if phantom_data {
+                        tokens!["::core::marker::PhantomData"]
+                            .into_iter()
+                            .map(|tt| tt.with_span(span))
+                            .collect()
+                    } else if let Some(consts) = args.consts.as_ref().filter(|_| !explicit) {
+                        let (value, path) = named_const(consts, &field_ident, span);
+                        if !consts.strict {
+                            doc = Some(format!(
+                                "Defaults to `{path}`, or to `Default::default()` \
+                                 if there is no such constant"
+                            ));
+                        }
+                        value
+                    } else {
+                        default(&field_ty, span, args, &field_args, default_template)
+                    };
+                    (eq, value)
+                })
+            }
+        };
+
+        // Option<T> and Vec<T> have no default in `clap`, since the argument is optional
+        let clap_arg = args.clap
+            && !field_args.clap_skip
+            && !type_name(&field_ty).is_some_and(|name| name.is("Option") || name.is("Vec"));
+
+        // Fields with `Default::default()` can use `#[serde(default)]` instead
+        //
+        // #[serde(default = "Foo::__port_field_default")]
+        //                    ^^^^^^^^^^^^^^^^^^^^^^^^^^
+        let serde_path = item_fields
+            .serde_defaults
+            .as_ref()
+            .filter(|_| {
+                field_args.serde_default.is_none()
+                    && default_value
+                        .as_ref()
+                        .is_some_and(|(_, value)| !is_default_call(value, &field_ty))
+            })
+            .map(|item| format!("{item}::{}", methods::serde_default_fn(&field_ident)));
+
+        // #[auto_default(group = "network")]
+        if let Some(group) = &field_args.group {
+            if !args.reset {
+                compile_errors.push(
+                    CompileError::new(
+                        ErrorCode::Unused,
+                        group.span(),
+                        "`group` does nothing without `#[auto_default(reset)]`",
+                    )
+                    .help("add `reset` to `#[auto_default]` on the item, or remove `group`"),
+                );
+            } else if default_value.is_none() {
+                compile_errors.push(CompileError::new(
+                    ErrorCode::Conflict,
+                    group.span(),
+                    "a field without a default value can't be in a group, \
+                     because the group can't reset it",
+                ));
+            }
+        }
+
+        item_fields.total += 1;
+        if item_fields.collect {
+            field.extend(field_ty.iter().cloned());
+            item_fields.list.push(Field {
+                ident: field_ident,
+                ty: field_ty,
+                default: default_value.as_ref().map(|(_, value)| value.clone()),
+                runtime,
+                refers_to_fields,
+                custom,
+                cfg: field_args.cfg.clone(),
+                serde_default: serde_path.is_some(),
+                group: field_args.group.clone(),
+            });
+        } else {
+            field.extend(field_ty);
+        }
+
+        if let Some((eq, value)) = default_value {
+            if args.doc_defaults {
+                // #[doc = "Defaults to `Default::default()`"]
+                let doc = doc.unwrap_or_else(|| {
+                    format!(
+                        "Defaults to {}",
+                        inline_code(&pretty::tokens_to_string(value.iter().cloned()))
+                    )
+                });
+                output_fields.extend(doc_default(&doc, field_ident_span));
+                changed = true;
+            }
+
+            // #[serde(default)]
+            for attr in &args.annotate {
+                output_fields.extend(tokens!["#", Group::new(Delimiter::Bracket, attr.clone())]);
+                changed = true;
+            }
+
+            // #[serde(default = "Foo::__port_field_default")]
+            if let Some(path) = serde_path {
+                output_fields.extend(tokens![
+                    "#",
+                    Group::new(
+                        Delimiter::Bracket,
+                        tokens![
+                            "serde",
+                            parenthesized(tokens![
+                                "default =",
+                                TokenTree::Literal(Literal::string(&path))
+                                    .with_span(field_ident_span)
+                            ])
+                        ]
+                    )
+                ]);
+                changed = true;
+            }
+
+            if clap_arg {
+                // #[arg(default_value_t = Default::default())]
+                output_fields.extend(tokens![
+                    "#",
+                    Group::new(
+                        Delimiter::Bracket,
+                        tokens!["arg", parenthesized(tokens!["default_value_t =", &value])]
+                    )
+                ]);
+                changed = true;
+            }
+
+            // field: Type = default
+            //             ^^^^^^^^^
+            if item_fields.strip_values {
+                changed = true;
+            } else {
+                field.extend([TokenTree::Punct(eq)]);
+                field.extend(value);
+            }
+        }
+
+        output_fields.extend(field);
+        item_fields.changed += usize::from(changed);
+
+        // field: Type = default,
+        //                      ^
+        match input_fields.next() {
+            // Comma after field. Next iteration handles the next field
+            Some(comma) => output_fields.extend([comma]),
+            // End of input. This was the last field
+            None => break,
+        }
+    }
+
+    for ((name, _), found) in values.iter().zip(values_found) {
+        if !found {
+            compile_errors.push(CompileError::new(
+                ErrorCode::InvalidArgument,
+                name.span(),
+                format!("there is no field named `{name}` here"),
+            ));
+        }
+    }
+
+    let mut g = Group::new(Delimiter::Brace, output_fields.into_iter().collect());
+    g.set_span(fields.span());
+    g
+}
+
+/// Collects the type of a field from `source`, stopping before the `,` or `=`
+/// that follows it
+///
+/// field: HashMap<K, V> = HashMap::new(),
+///        ^^^^^^^^^^^^^
+///
+/// The `,` inside of the `<...>` does not end the type
+pub fn parse_type(source: &mut Source) -> Vec<TokenTree> {
+    let mut ty = Vec::new();
+
+    // How many `<` we are currently inside of
+    let mut angle_depth = 0_usize;
+
+    loop {
+        match source.peek() {
+            // End of the type
+            //
+            // field: Type,
+            //            ^
+            // field: Type = default,
+            //             ^
+            Some(TokenTree::Punct(p))
+                if angle_depth == 0 && (p.as_char() == ',' || p.as_char() == '=') =>
+            {
+                break;
+            }
+            Some(TokenTree::Punct(p)) if p.as_char() == '<' => angle_depth += 1,
+            Some(TokenTree::Punct(p)) if is_closing_angle_bracket(p, ty.last()) => {
+                angle_depth = angle_depth.saturating_sub(1);
+            }
+            Some(_) => {}
+            None => break,
+        }
+        ty.extend(source.next());
+    }
+
+    #[cfg(feature = "full-parse")]
+    let mut ty = crate::full_parse::ty(ty);
+
+    // field: Vec<T>,
+    //             ^ joined with the `,`, but we may insert ` = default` after it
+    if let Some(TokenTree::Punct(p)) = ty.last_mut()
+        && p.spacing() == Spacing::Joint
+    {
+        let span = p.span();
+        *p = Punct::new(p.as_char(), Spacing::Alone);
+        p.set_span(span);
+    }
+
+    ty
+}
+
+/// Whether every field in `fields` has a default value, which the items generated by `arg` need
+/// to create the default value with `Foo { .. }`
+fn has_default_values(fields: &[Field], arg: &str, errors: &mut Vec<CompileError>) -> bool {
+    let mut has_default_values = true;
+    for field in fields.iter().filter(|field| field.default.is_none()) {
+        errors.push(
+            CompileError::new(
+                ErrorCode::Conflict,
+                field.ident.span(),
+                format!("`#[auto_default({arg})]` requires every field to have a default value"),
+            )
+            .help("remove `#[auto_default(skip)]` from the field, or give it a default value"),
+        );
+        has_default_values = false;
+    }
+    has_default_values
+}
+
+/// `#[doc = "Defaults to `value`"]` attribute for a field, whose text is `doc`
+fn doc_default(doc: &str, span: Span) -> TokenStream {
+    // The empty line starts a new paragraph, if the field is already documented
+    //
+    // #[doc = ""]
+    // #[doc = "Defaults to `value`"]
+    ["", doc]
+        .into_iter()
+        .flat_map(|doc| {
+            [
+                TokenTree::Punct(Punct::new('#', Spacing::Alone)).with_span(span),
+                TokenTree::Group(Group::new(
+                    Delimiter::Bracket,
+                    TokenStream::from_iter([
+                        TokenTree::Ident(Ident::new("doc", span)),
+                        TokenTree::Punct(Punct::new('=', Spacing::Alone)).with_span(span),
+                        TokenTree::Literal(Literal::string(doc)).with_span(span),
+                    ]),
+                ))
+                .with_span(span),
+            ]
+        })
+        .collect()
+}
+
+/// `code` as inline code in Markdown, between more backticks than any run of backticks in it
+///
+/// ```text
+/// 'a'      => `'a'`
+/// "``a`"   => ```"``a`"```
+/// ```
+///
+/// Backticks can only be inside of a literal, so they are never at either end of `code`
+fn inline_code(code: &str) -> String {
+    let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    format!("{fence}{code}{fence}")
+}
+
+/// Documents why a field or a variant is skipped. The `reason` literal is kept as-is,
+/// so it doesn't need to be unescaped
+///
+/// ```text
+/// #[doc = ""]
+/// #[doc = "Has no default value:"]
+/// #[doc = "reason"]
+/// ```
+fn doc_reason(prefix: &str, reason: Literal) -> TokenStream {
+    let span = reason.span();
+    [Literal::string(""), Literal::string(prefix), reason]
+        .into_iter()
+        .flat_map(|doc| {
+            [
+                TokenTree::Punct(Punct::new('#', Spacing::Alone)).with_span(span),
+                TokenTree::Group(Group::new(
+                    Delimiter::Bracket,
+                    TokenStream::from_iter([
+                        TokenTree::Ident(Ident::new("doc", span)),
+                        TokenTree::Punct(Punct::new('=', Spacing::Alone)).with_span(span),
+                        TokenTree::Literal(doc).with_span(span),
+                    ]),
+                ))
+                .with_span(span),
+            ]
+        })
+        .collect()
+}
+
+/// Whether the tokens after the name of a `struct` without named fields are of a unit struct,
+/// rather than a tuple struct
+///
+/// ```text
+/// struct Foo<T> where T: Fn();
+///           ^^^^^^^^^^^^^^^^^^ unit struct
+///
+/// struct Foo<T>(T) where T: Fn();
+///           ^^^^^^^^^^^^^^^^^^^^^ tuple struct
+/// ```
+fn is_unit_struct(tokens: &[TokenTree]) -> bool {
+    let mut angle_depth = 0_usize;
+    for (i, tt) in tokens.iter().enumerate() {
+        match tt {
+            TokenTree::Punct(punct) if punct.as_char() == '<' => angle_depth += 1,
+            TokenTree::Punct(punct)
+                if is_closing_angle_bracket(punct, i.checked_sub(1).map(|i| &tokens[i])) =>
+            {
+                angle_depth = angle_depth.saturating_sub(1);
+            }
+            // Fields of a tuple struct come before the `where` clause
+            TokenTree::Ident(ident) if angle_depth == 0 && ident.is("where") => return true,
+            TokenTree::Group(group)
+                if angle_depth == 0 && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                return false;
+            }
+            _ => {}
+        }
+    }
+    true
+}
+
+/// Name of the type, without its path and generics
+///
+/// ```text
+/// ::std::vec::Vec<u8>
+///             ^^^
+/// ```
+pub fn type_name(ty: &[TokenTree]) -> Option<&Ident> {
+    let mut name = None;
+    for tt in ty {
+        match tt {
+            TokenTree::Ident(ident) => name = Some(ident),
+            TokenTree::Punct(punct) if punct.as_char() == ':' => {}
+            _ => break,
+        }
+    }
+    name
+}
+
+/// Span and name of an item that `#[auto_default]` does not support, from its qualifiers
+/// to its keyword
+///
+/// ```text
+/// pub const unsafe fn foo() {}
+///     ^^^^^^^^^^^^^^^ functions
+/// ```
+fn unsupported_item(item: &[TokenTree]) -> Option<(Span, &'static str)> {
+    let first = item.first()?.span();
+    for tt in item {
+        let kind = match tt {
+            TokenTree::Ident(kw) if kw.is("fn") => "functions",
+            TokenTree::Ident(kw) if kw.is("trait") => "traits",
+            TokenTree::Ident(kw) if kw.is("impl") => "`impl` blocks",
+            TokenTree::Ident(kw) if kw.is("type") => "type aliases",
+            // const unsafe fn foo() {}
+            // ^^^^^ ^^^^^^ qualifiers of the item
+            TokenTree::Ident(kw)
+                if [
+                    "const", "async", "unsafe", "safe", "extern", "auto", "default",
+                ]
+                .into_iter()
+                .any(|qualifier| kw.is(qualifier)) =>
+            {
+                continue;
+            }
+            // extern "C" fn foo() {}
+            //        ^^^
+            TokenTree::Literal(_) => continue,
+            _ => return None,
+        };
+        return Some((join_spans(first, tt.span()), kind));
+    }
+    None
+}
+
+/// Value of a field with `#[auto_default(boxed = concrete)]`, which `impl Default` uses
+///
+/// ```text
+/// std::sync::Arc<dyn Trait>, Backend => std::sync::Arc::new(<Backend as ::core::default::Default>::default())
+/// ```
+fn boxed(ty: &[TokenTree], concrete: &TokenStream, errors: &mut Vec<CompileError>) -> TokenStream {
+    if !type_name(ty).is_some_and(|name| name.is("Box") || name.is("Arc") || name.is("Rc")) {
+        let span = type_span(ty).unwrap_or_else(Span::call_site);
+        errors.push(CompileError::new(
+            ErrorCode::InvalidArgument,
+            span,
+            "`#[auto_default(boxed = ...)]` is only supported on fields of type `Box`, `Arc` or `Rc`",
+        ));
+        // Only the error above is reported
+        return core_default(span).into_iter().collect();
+    }
+
+    // std::sync::Arc<dyn Trait>
+    // ^^^^^^^^^^^^^^
+    let path = ty
+        .iter()
+        .take_while(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == '<'))
+        .cloned()
+        .collect::<TokenStream>();
+
+    tokens![
+        path,
+        ":: new",
+        parenthesized(tokens![
+            "<",
+            concrete,
+            "as ::core::default::Default>::default()"
+        ])
+    ]
+}
+
+/// Value of a field with `#[auto_default(lazy = expr)]`, which `impl Default` uses
+///
+/// ```rust,ignore
+/// {
+///     static LAZY: ::std::sync::OnceLock<Type> = ::std::sync::OnceLock::new();
+///     ::core::clone::Clone::clone(::std::sync::OnceLock::get_or_init(&LAZY, || expr))
+/// }
+/// ```
+fn lazy(
+    ty: &[TokenTree],
+    value: &TokenStream,
+    type_params: &[String],
+    errors: &mut Vec<CompileError>,
+) -> TokenStream {
+    if impl_default::mentions(ty, type_params) {
+        let span = type_span(ty).unwrap_or_else(Span::call_site);
+        errors.push(
+            CompileError::new(
+                ErrorCode::InvalidArgument,
+                span,
+                "`#[auto_default(lazy = ...)]` is not supported on fields whose type uses \
+                 a generic parameter",
+            )
+            .help("use `runtime = ...` instead, which evaluates the value every time"),
+        );
+        // Only the error above is reported
+        return value.clone();
+    }
+
+    // Can't be named by `expr`
+    let lazy = Ident::new("LAZY", Span::mixed_site());
+    tokens![braced(tokens![
+        "static",
+        &lazy,
+        ": ::std::sync::OnceLock<",
+        ty,
+        "> = ::std::sync::OnceLock::new();",
+        "::core::clone::Clone::clone",
+        parenthesized(tokens![
+            "::std::sync::OnceLock::get_or_init",
+            parenthesized(tokens!["&", &lazy, ", ||", value])
+        ])
+    ])]
+}
+
+/// Type of the elements and length of an array type
+///
+/// ```text
+/// [u8; 64] => u8, 64
+/// ```
+fn array_type(ty: &[TokenTree]) -> Option<(Vec<TokenTree>, Vec<TokenTree>)> {
+    let [TokenTree::Group(array)] = ty else {
+        return None;
+    };
+    if array.delimiter() != Delimiter::Bracket {
+        return None;
+    }
+    // `;` of the length can only be inside of a group, such as `{ N; }`
+    let mut elem = array.stream().into_iter().collect::<Vec<_>>();
+    let semi = elem
+        .iter()
+        .position(|tt| matches!(tt, TokenTree::Punct(semi) if semi.as_char() == ';'))?;
+    let len = elem.split_off(semi + 1);
+    elem.pop();
+    (!elem.is_empty() && !len.is_empty()).then_some((elem, len))
+}
+
+/// Repeat expression of `value`
+///
+/// ```text
+/// 0, 64                                    => [0; 64]
+/// ::core::default::Default::default(), 64 => [const { ::core::default::Default::default() }; 64]
+/// ```
+///
+/// Literals are `Copy`, while other values are wrapped in a `const` block,
+/// so the type of the elements doesn't need to implement `Copy`
+fn array_repeat(value: Vec<TokenTree>, len: Vec<TokenTree>, span: Span) -> Vec<TokenTree> {
+    let mut elem = TokenStream::new();
+    if let [TokenTree::Literal(_) | TokenTree::Ident(_)] = value.as_slice() {
+        elem.extend(value);
+    } else {
+        elem.extend([
+            TokenTree::Ident(Ident::new("const", span)),
+            TokenTree::Group(Group::new(Delimiter::Brace, value.into_iter().collect()))
+                .with_span(span),
+        ]);
+    }
+    elem.extend([TokenTree::Punct(Punct::new(';', Spacing::Alone)).with_span(span)]);
+    elem.extend(len);
+
+    vec![TokenTree::Group(Group::new(Delimiter::Bracket, elem)).with_span(span)]
+}
+
+/// Span of the whole type, from its first to its last token
+fn type_span(ty: &[TokenTree]) -> Option<Span> {
+    Some(join_spans(ty.first()?.span(), ty.last()?.span()))
+}
+
+/// Span from `first` to `last`
+#[cfg(auto_default_nightly)]
+fn join_spans(first: Span, last: Span) -> Span {
+    first.join(last).unwrap_or(first)
+}
+
+/// Span from `first` to `last`
+///
+/// Spans can only be joined on nightly, so this is `first`
+#[cfg(not(auto_default_nightly))]
+fn join_spans(first: Span, _last: Span) -> Span {
+    first
+}
+
+/// Whether `punct` is a `>` that closes a `<`, given the token before it
+///
+/// fn() -> Type
+///       ^ this is not a closing angle bracket
+pub fn is_closing_angle_bracket(punct: &Punct, prev: Option<&TokenTree>) -> bool {
+    punct.as_char() == '>'
+        && !matches!(
+            prev,
+            Some(TokenTree::Punct(prev)) if prev.as_char() == '-' && prev.spacing() == Spacing::Joint
+        )
+}
+
+/// Whether [`default`] is `Default::default()` for a field with these arguments,
+/// ignoring `infer_literals`, whose literals are the same as `Default::default()`
+fn adds_default_call(ty: &[TokenTree], args: &Args, field_args: &FieldArgs) -> bool {
+    if field_args.default.is_some() {
+        return false;
+    }
+    match field_args
+        .serde_default
+        .as_ref()
+        .filter(|_| args.from_serde)
+    {
+        Some(SerdeDefault::Path(_)) => false,
+        Some(SerdeDefault::Default) => true,
+        None => {
+            !args.struct_default
+                && !args.assoc_const
+                && !args.const_default_trait
+                && !args.prefer_new
+                && args.with.is_none()
+                && args.consts.is_none()
+                && args
+                    .policy
+                    .as_ref()
+                    .is_none_or(|policy| policy.default(ty).is_none())
+        }
+    }
+}
+
+/// Default value for a field of type `ty`
+///
+/// ::core::default::Default::default()
+///
+/// `template` is the result of [`core_default`] or [`short_default`], which is cheaper to clone
+/// than to create again
+fn default(
+    ty: &[TokenTree],
+    span: Span,
+    args: &Args,
+    field_args: &FieldArgs,
+    template: &[TokenTree],
+) -> Vec<TokenTree> {
+    match field_args.default.as_ref().map(|default| &default.kind) {
+        Some(DefaultKind::StructDefault) => {
+            return struct_default(ty, span);
+        }
+        Some(DefaultKind::AssocConst) => {
+            return assoc_const(ty, span);
+        }
+        Some(DefaultKind::ConstDefaultTrait) => {
+            return const_default_trait(ty, span);
+        }
+        Some(DefaultKind::PreferNew) => {
+            return new_call(ty, span);
+        }
+        Some(DefaultKind::With(path)) => {
+            return call(path, span);
+        }
+        // Fields with `runtime = ...`, `value = ...`, `boxed = ...` or `lazy = ...` don't get
+        // a default field value, so this isn't called for them
+        Some(
+            DefaultKind::Runtime(_)
+            | DefaultKind::Value(_)
+            | DefaultKind::Boxed(_)
+            | DefaultKind::Lazy(_),
+        )
+        | None => {}
+    }
+
+    // `#[auto_default(from_serde)]`
+    match field_args
+        .serde_default
+        .as_ref()
+        .filter(|_| args.from_serde)
+    {
+        Some(SerdeDefault::Path(path)) => return call(path, span),
+        // `serde` calls `Default::default()`, so other options of the container don't apply
+        Some(SerdeDefault::Default) => {
+            return template
+                .iter()
+                .map(|tt| tt.clone().with_span(span))
+                .collect();
+        }
+        None => {}
+    }
+
+    // [u8; 64] => [const { ::core::default::Default::default() }; 64]
+    //
+    // Arrays only implement `Default` up to a length of 32,
+    // so every element gets the default value of its type instead
+    if let Some((elem, len)) = array_type(ty) {
+        let value = default(&elem, span, args, &FieldArgs::default(), template);
+        return array_repeat(value, len, span);
+    }
+
+    if args.infer_literals
+        && let Some(literal) = primitive_literal(ty, span)
+    {
+        return vec![literal];
+    }
+
+    if args.known_types
+        && let Some(value) = known_type(ty, span)
+    {
+        return value;
+    }
+
+    if args.struct_default {
+        return struct_default(ty, span);
+    }
+
+    if args.assoc_const {
+        return assoc_const(ty, span);
+    }
+
+    if args.const_default_trait {
+        return const_default_trait(ty, span);
+    }
+
+    if args.prefer_new {
+        return new_call(ty, span);
+    }
+
+    if let Some(path) = &args.with {
+        return call(path, span);
+    }
+
+    // [types] in `auto_default.toml`, which the options of the item take precedence over
+    if let Some(value) = args.policy.as_ref().and_then(|policy| policy.default(ty)) {
+        return value
+            .clone()
+            .into_iter()
+            .map(|tt| tt.with_span(span))
+            .collect();
+    }
+
+    template
+        .iter()
+        .map(|tt| tt.clone().with_span(span))
+        .collect()
+}
+
+/// Constant named after the field in the module of `#[auto_default(consts = defaults)]`,
+/// and the path to it
+///
+/// ```text
+/// port: u16 = defaults::PORT
+/// ```
+///
+/// Without `strict_consts`, the module may not have the constant. Its items are glob imported
+/// into a block, where they shadow a unit struct of an outer block with the same name. If the
+/// name refers to that struct, it becomes `Default::default()`:
+///
+/// ```text
+/// port: u16 = {
+///     struct PORT;
+///     {
+///         use defaults::*;
+///         const trait Resolve<T> { fn resolve(self) -> T; }
+///         impl<T: [const] Default> const Resolve<T> for PORT { ... Default::default() ... }
+///         impl<T> const Resolve<T> for T { ... self ... }
+///         Resolve::resolve(PORT)
+///     }
+/// }
+/// ```
+fn named_const(consts: &Consts, field: &Ident, span: Span) -> (Vec<TokenTree>, String) {
+    let name = Ident::new(&methods::unraw(field).to_uppercase(), field.span());
+    let path = tokens![&consts.module, "::", &name];
+    let path_string = pretty::tokens_to_string(path.clone());
+    if consts.strict {
+        return (path.into_iter().collect(), path_string);
+    }
+
+    let value = braced(tokens![
+        "#[allow(dead_code, non_camel_case_types)] struct",
+        &name,
+        ";",
+        braced(tokens![
+            "#[allow(unused_imports)] use",
+            &consts.module,
+            ":: *;",
+            "const trait Resolve<T> { fn resolve(self) -> T; }",
+            "impl<T: [const] ::core::default::Default> const Resolve<T> for",
+            &name,
+            "{ fn resolve(self) -> T { ::core::default::Default::default() } }",
+            "impl<T> const Resolve<T> for T { fn resolve(self) -> T { self } }",
+            "Resolve::resolve",
+            parenthesized(tokens![&name])
+        ])
+    ]);
+    (vec![TokenTree::Group(value).with_span(span)], path_string)
+}
+
+/// Default::default()
+///
+/// Used with `#[auto_default(short_default)]`. Unlike [`core_default`], this refers to
+/// whatever `Default` is in scope
+fn short_default(span: Span) -> Vec<TokenTree> {
+    vec![
+        TokenTree::Ident(Ident::new("Default", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(span),
+        TokenTree::Ident(Ident::new("default", span)),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new())).with_span(span),
+    ]
+}
+
+/// ::core::default::Default::default()
+pub fn core_default(span: Span) -> Vec<TokenTree> {
+    vec![
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(span),
+        TokenTree::Ident(Ident::new("core", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(span),
+        TokenTree::Ident(Ident::new("default", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(span),
+        TokenTree::Ident(Ident::new("Default", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(span),
+        TokenTree::Ident(Ident::new("default", span)),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new())).with_span(span),
+    ]
+}
+
+/// Whether `value` is a hand-written default value of the type `ty`, which
+/// `#[auto_default(normalize)]` replaces with the default value that the macro adds
+///
+/// ```text
+/// Default::default()
+/// ::core::default::Default::default()
+/// Type::default()
+/// <Type>::default()
+/// <Type as Default>::default()
+/// ```
+pub fn is_default_call(value: &[TokenTree], ty: &[TokenTree]) -> bool {
+    // Type::default()
+    //     ^^^^^^^^^^^
+    let [
+        path @ ..,
+        TokenTree::Punct(colon),
+        TokenTree::Punct(colon2),
+        TokenTree::Ident(default),
+        TokenTree::Group(call),
+    ] = value
+    else {
+        return false;
+    };
+    if colon.as_char() != ':'
+        || colon2.as_char() != ':'
+        || !default.is("default")
+        || call.delimiter() != Delimiter::Parenthesis
+        || !call.stream().is_empty()
+    {
+        return false;
+    }
+
+    if is_default_trait(path) || tokens_eq(path, ty) {
+        return true;
+    }
+
+    // <Type>::default()
+    // <Type as Default>::default()
+    let [
+        TokenTree::Punct(open),
+        qualified @ ..,
+        TokenTree::Punct(close),
+    ] = path
+    else {
+        return false;
+    };
+    if open.as_char() != '<' || close.as_char() != '>' {
+        return false;
+    }
+    match qualified.split_at_checked(ty.len()) {
+        Some((qualified_ty, [])) => tokens_eq(qualified_ty, ty),
+        Some((qualified_ty, [TokenTree::Ident(as_), default_trait @ ..])) => {
+            tokens_eq(qualified_ty, ty) && as_.is("as") && is_default_trait(default_trait)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `path` is the path to the `Default` trait
+///
+/// ```text
+/// Default
+/// ::core::default::Default
+/// std::default::Default
+/// ```
+fn is_default_trait(path: &[TokenTree]) -> bool {
+    let path = match path {
+        [TokenTree::Punct(colon), TokenTree::Punct(colon2), path @ ..]
+            if colon.as_char() == ':' && colon2.as_char() == ':' =>
+        {
+            path
+        }
+        path => path,
+    };
+
+    match path {
+        [TokenTree::Ident(trait_)] => trait_.is("Default"),
+        [
+            TokenTree::Ident(krate),
+            TokenTree::Punct(c1),
+            TokenTree::Punct(c2),
+            TokenTree::Ident(module),
+            TokenTree::Punct(c3),
+            TokenTree::Punct(c4),
+            TokenTree::Ident(trait_),
+        ] => {
+            (krate.is("core") || krate.is("std"))
+                && module.is("default")
+                && trait_.is("Default")
+                && [c1, c2, c3, c4].iter().all(|c| c.as_char() == ':')
+        }
+        _ => false,
+    }
+}
+
+/// Functions that are often used in default values, but can't be called in `const` contexts
+///
+/// Only matched when called with the full path, starting with `std` or `alloc`, because
+/// a type with the same name may have a `const` function of the same name. The exception
+/// is a field of type `String` or `Vec`, whose value may call them as `String::from`
+const NON_CONST_FNS: &[&[&str]] = &[
+    &["string", "String", "from"],
+    &["string", "String", "with_capacity"],
+    &["vec", "Vec", "with_capacity"],
+    &["boxed", "Box", "new"],
+    &["rc", "Rc", "new"],
+    &["sync", "Arc", "new"],
+    &["collections", "HashMap", "new"],
+    &["collections", "HashSet", "new"],
+    &["path", "PathBuf", "from"],
+    &["path", "PathBuf", "new"],
+];
+
+/// Macros that can't be used in `const` contexts
+const NON_CONST_MACROS: &[&str] = &["vec", "format"];
+
+/// Methods that can't be called in `const` contexts, because they belong to traits
+/// that are never implemented as `const`
+const NON_CONST_METHODS: &[&str] = &["to_string"];
+
+/// If `value` calls something that can't be called in a `const` context, reports it
+///
+/// Default field values must be `const`. Without this, `rustc` reports the error wherever
+/// the value ends up, which may be far from where it was written, such as for the value of
+/// `#[derivative(Default(value = "..."))]`
+fn check_const(value: &[TokenTree], ty: &[TokenTree], errors: &mut Vec<CompileError>) -> bool {
+    let ty = type_name(ty).filter(|name| name.is("String") || name.is("Vec"));
+    let Some((span, call)) = non_const_call(value, ty) else {
+        return true;
+    };
+
+    // `String::new()` and `Vec::new()` are `const`
+    let empty = match ty {
+        Some(name) if name.is("Vec") => "use `Vec::new()` for an empty `Vec`, or ",
+        Some(name) if name.is("String") => "use `String::new()` for an empty `String`, or ",
+        _ => "",
+    };
+
+    errors.push(
+        CompileError::new(
+            ErrorCode::NotConst,
+            span,
+            format!("`{call}` can't be used in a default field value, because it is not `const`"),
+        )
+        .help(format!(
+            "{empty}remove the default field value and add `#[auto_default(runtime = ...)]` \
+             to the field and `impl_default` to the item, to set it in the implementation of \
+             `Default` instead"
+        )),
+    );
+    false
+}
+
+/// First call in `value` that is known to not be `const`, and how it is written
+///
+/// ```text
+/// Some(std::string::String::from("hello"))
+///      ^^^^^^^^^^^^^^^^^^^^^^^^^
+/// ```
+///
+/// Functions of `ty`, the name of the type of the field, are also matched without
+/// the path to the module, such as `String::from("hello")` for a `String` field
+fn non_const_call(value: &[TokenTree], ty: Option<&Ident>) -> Option<(Span, String)> {
+    for (i, tt) in value.iter().enumerate() {
+        // Only the start of a path: `::std` in `a::std::...` is not
+        let in_path = i
+            .checked_sub(1)
+            .is_some_and(|prev| match (&value[prev], tt) {
+                (TokenTree::Punct(colon), _) => colon.as_char() == ':',
+                (TokenTree::Ident(_), TokenTree::Punct(_)) => true,
+                _ => false,
+            });
+
+        // std::string::String::from("hello")
+        // String::from("hello")
+        if !in_path
+            && let Some((krate, path)) = called_path(&value[i..])
+            && NON_CONST_FNS.iter().any(|non_const| {
+                if krate.is("std") || krate.is("alloc") {
+                    non_const.len() == path.len()
+                        && non_const
+                            .iter()
+                            .zip(&path)
+                            .all(|(name, segment)| segment.is(name))
+                } else {
+                    let [.., ty_name, function] = non_const else {
+                        return false;
+                    };
+                    ty.is_some_and(|ty| ty.is(ty_name) && krate.is(ty_name))
+                        && matches!(path.as_slice(), [segment] if segment.is(function))
+                }
+            })
+        {
+            let span = path
+                .last()
+                .map_or(krate.span(), |last| join_spans(krate.span(), last.span()));
+            let path = path.iter().map(ToString::to_string).collect::<Vec<_>>();
+            return Some((span, format!("{krate}::{}", path.join("::"))));
+        }
+
+        let call = match (tt, &value[i + 1..]) {
+            // vec![1, 2]
+            (TokenTree::Ident(mac), [TokenTree::Punct(bang), TokenTree::Group(_), ..])
+                if bang.as_char() == '!' && NON_CONST_MACROS.iter().any(|name| mac.is(name)) =>
+            {
+                Some((mac.span(), format!("{mac}!")))
+            }
+            // "hello".to_string()
+            (TokenTree::Punct(dot), [TokenTree::Ident(method), TokenTree::Group(args), ..])
+                if dot.as_char() == '.'
+                    && args.stream().is_empty()
+                    && NON_CONST_METHODS.iter().any(|name| method.is(name)) =>
+            {
+                Some((method.span(), format!(".{method}()")))
+            }
+            (TokenTree::Group(group), _) => {
+                non_const_call(&group.stream().into_iter().collect::<Vec<_>>(), ty)
+            }
+            _ => None,
+        };
+        if call.is_some() {
+            return call;
+        }
+    }
+    None
+}
+
+/// Path of the function that is called at the start of `tokens`, split into its first segment
+/// and the rest
+///
+/// ```text
+/// ::std::string::String::from("hello") => std, [string, String, from]
+/// ```
+fn called_path(tokens: &[TokenTree]) -> Option<(&Ident, Vec<&Ident>)> {
+    let mut tokens = match tokens {
+        [TokenTree::Punct(c1), TokenTree::Punct(c2), rest @ ..]
+            if c1.as_char() == ':' && c2.as_char() == ':' =>
+        {
+            rest
+        }
+        tokens => tokens,
+    };
+
+    let mut path = Vec::new();
+    loop {
+        let [TokenTree::Ident(segment), rest @ ..] = tokens else {
+            return None;
+        };
+        path.push(segment);
+        match rest {
+            [TokenTree::Punct(c1), TokenTree::Punct(c2), rest @ ..]
+                if c1.as_char() == ':' && c2.as_char() == ':' =>
+            {
+                tokens = rest;
+            }
+            [TokenTree::Group(args), ..] if args.delimiter() == Delimiter::Parenthesis => {
+                let krate = path.remove(0);
+                return Some((krate, path));
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Whether `a` and `b` are the same tokens, ignoring their spans and spacing
+pub fn tokens_eq(a: &[TokenTree], b: &[TokenTree]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| match (a, b) {
+            (TokenTree::Punct(a), TokenTree::Punct(b)) => a.as_char() == b.as_char(),
+            (TokenTree::Ident(a), TokenTree::Ident(b)) => b.is(&a.to_string()),
+            (TokenTree::Literal(a), TokenTree::Literal(b)) => a.to_string() == b.to_string(),
+            (TokenTree::Group(a), TokenTree::Group(b)) => {
+                a.delimiter() == b.delimiter()
+                    && tokens_eq(
+                        &a.stream().into_iter().collect::<Vec<_>>(),
+                        &b.stream().into_iter().collect::<Vec<_>>(),
+                    )
+            }
+            _ => false,
+        })
+}
+
+/// Calls the function at `path` with no arguments
+///
+/// my_crate::Reset::reset => my_crate::Reset::reset()
+fn call(path: &TokenStream, span: Span) -> Vec<TokenTree> {
+    path.clone()
+        .into_iter()
+        .chain([
+            TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new()))
+                .with_span(span),
+        ])
+        .collect()
+}
+
+/// `<Type>::DEFAULT`, the associated constant of the type of the field
+fn assoc_const(ty: &[TokenTree], span: Span) -> Vec<TokenTree> {
+    tokens![
+        "<",
+        ty,
+        "> ::",
+        TokenTree::Ident(Ident::new("DEFAULT", span))
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// `<Type>::new()`, the constructor of the type of the field
+///
+/// Many types have a `const fn new()`, but no `const Default`
+fn new_call(ty: &[TokenTree], span: Span) -> Vec<TokenTree> {
+    tokens![
+        "<",
+        ty,
+        "> ::",
+        TokenTree::Ident(Ident::new("new", span)),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new())).with_span(span)
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// `<Type as ::const_default::ConstDefault>::DEFAULT`, for a field of type `ty`
+pub fn const_default_trait(ty: &[TokenTree], span: Span) -> Vec<TokenTree> {
+    tokens![
+        "<",
+        ty,
+        "as ::const_default::ConstDefault > ::",
+        TokenTree::Ident(Ident::new("DEFAULT", span))
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// Struct expression with all fields defaulted, for a field of type `ty`
+///
+/// Generic arguments get a turbofish, since `Foo<T> { .. }` is not a valid expression
+///
+/// ```text
+/// Foo<T> => Foo::<T> { .. }
+/// ```
+fn struct_default(ty: &[TokenTree], span: Span) -> Vec<TokenTree> {
+    let mut expr = Vec::with_capacity(ty.len() + 3);
+    let mut angle_depth = 0_usize;
+
+    for tt in ty {
+        if let TokenTree::Punct(p) = tt {
+            match p.as_char() {
+                '<' => {
+                    // Foo<T>
+                    //    ^ needs a `::` before it, unless it already has one
+                    if angle_depth == 0
+                        && !matches!(
+                            expr.last(),
+                            Some(TokenTree::Punct(prev)) if prev.as_char() == ':'
+                        )
+                    {
+                        expr.extend([
+                            TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(p.span()),
+                            TokenTree::Punct(Punct::new(':', Spacing::Alone)).with_span(p.span()),
+                        ]);
+                    }
+                    angle_depth += 1;
+                }
+                '>' if is_closing_angle_bracket(p, expr.last()) => {
+                    angle_depth = angle_depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+        }
+        expr.push(tt.clone());
+    }
+
+    // Foo { .. }
+    //     ^^^^^^
+    expr.push(
+        TokenTree::Group(Group::new(
+            Delimiter::Brace,
+            TokenStream::from_iter([
+                TokenTree::Punct(Punct::new('.', Spacing::Joint)).with_span(span),
+                TokenTree::Punct(Punct::new('.', Spacing::Alone)).with_span(span),
+            ]),
+        ))
+        .with_span(span),
+    );
+
+    expr
+}
+
+/// If `ty` is a primitive type, returns the literal of its default value
+///
+/// u32 => 0
+/// f32 => 0.0
+/// bool => false
+/// char => '\0'
+fn primitive_literal(ty: &[TokenTree], span: Span) -> Option<TokenTree> {
+    let [TokenTree::Ident(ty)] = ty else {
+        return None;
+    };
+
+    let is_any = |names: &[&str]| names.iter().any(|name| ty.is(name));
+
+    let literal = if is_any(&[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ]) {
+        TokenTree::Literal(Literal::u8_unsuffixed(0))
+    } else if is_any(&["f32", "f64"]) {
+        TokenTree::Literal(Literal::f64_unsuffixed(0.0))
+    } else if ty.is("bool") {
+        TokenTree::Ident(Ident::new("false", span))
+    } else if ty.is("char") {
+        TokenTree::Literal(Literal::character('\0'))
+    } else {
+        return None;
+    };
+
+    Some(literal.with_span(span))
+}
+
+/// If `ty` is a well-known type of the standard library, returns its `const` default value
+///
+/// ```text
+/// Option<T> => ::core::option::Option::None
+/// Vec<T> => <Vec<T>>::new()
+/// String => <String>::new()
+/// &'static str => ""
+/// PhantomData<T> => ::core::marker::PhantomData
+/// ```
+///
+/// `Vec` and `String` are referred to with the type of the field, which also works in `no_std`
+/// crates that import them from `alloc`
+fn known_type(ty: &[TokenTree], span: Span) -> Option<Vec<TokenTree>> {
+    let respan =
+        |value: TokenStream| Some(value.into_iter().map(|tt| tt.with_span(span)).collect());
+
+    // &'static str
+    if let [TokenTree::Punct(and), .., TokenTree::Ident(str)] = ty
+        && and.as_char() == '&'
+        && str.is("str")
+        && !ty
+            .iter()
+            .any(|tt| matches!(tt, TokenTree::Ident(ident) if ident.is("mut")))
+    {
+        return Some(vec![
+            TokenTree::Literal(Literal::string("")).with_span(span),
+        ]);
+    }
+
+    let name = type_name(ty)?;
+    if name.is("Option") {
+        respan(tokens!["::core::option::Option::None"])
+    } else if name.is("PhantomData") {
+        respan(tokens!["::core::marker::PhantomData"])
+    } else if name.is("Vec") || name.is("String") {
+        // <Vec<T>>::new()
+        //  ^^^^^^ keeps the spans of the type
+        let mut value = respan(tokens!["<"])?;
+        value.extend_from_slice(ty);
+        value.extend(respan(tokens![">::new()"])?);
+        Some(value)
+    } else {
+        None
+    }
+}
+
+pub trait TokenTreeExt {
+    /// Set span of `TokenTree` without needing to create a new binding
+    fn with_span(self, span: Span) -> TokenTree;
+}
+
+impl TokenTreeExt for TokenTree {
+    fn with_span(mut self, span: Span) -> TokenTree {
+        self.set_span(span);
+        self
+    }
+}
+
+pub trait IdentExt {
+    /// Whether this identifier is `name`
+    ///
+    /// Unlike `ident.to_string() == name`, this does not allocate a `String`
+    fn is(&self, name: &str) -> bool;
+}
+
+impl IdentExt for Ident {
+    fn is(&self, name: &str) -> bool {
+        /// Checks that everything written to it is the start of the remaining name
+        struct Rest<'a>(&'a str);
+
+        impl fmt::Write for Rest<'_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 = self.0.strip_prefix(s).ok_or(fmt::Error)?;
+                Ok(())
+            }
+        }
+
+        let mut rest = Rest(name);
+        write!(rest, "{self}").is_ok() && rest.0.is_empty()
+    }
+}
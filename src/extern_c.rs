@@ -0,0 +1,53 @@
+//! Function that is generated next to the item by `#[auto_default(extern_c)]`,
+//! so that C code can get the default value of a `#[repr(C)]` struct
+//!
+//! ```rust,ignore
+//! #[auto_default(extern_c)]
+//! #[repr(C)]
+//! pub struct Config {
+//!     port: u16 = 8080,
+//! }
+//!
+//! /// Default value of `Config`
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn config_default() -> Config {
+//!     Config { .. }
+//! }
+//! ```
+//!
+//! `cbindgen` writes the declaration of the function to the header. It has the visibility
+//! of the struct, so a private struct doesn't get a public function
+use proc_macro::{Ident, TokenStream};
+
+use crate::expand::DefaultValue;
+use crate::methods::unraw;
+use crate::self_test::snake_case;
+use crate::tokens::{braced, doc, tokens};
+
+/// `extern "C"` function named `name`, which returns `value`
+///
+/// Without a `name`, the function of `Config` is named `config_default`
+pub fn extern_c(
+    ident: &Ident,
+    vis: TokenStream,
+    value: &DefaultValue,
+    name: Option<&Ident>,
+) -> TokenStream {
+    let name = name.cloned().unwrap_or_else(|| {
+        Ident::new(
+            &format!("{}_default", snake_case(&unraw(ident))),
+            ident.span(),
+        )
+    });
+
+    tokens![
+        doc(&format!("Default value of `{ident}`")),
+        "#[unsafe(no_mangle)]",
+        vis,
+        "extern \"C\" fn",
+        &name,
+        "() ->",
+        ident,
+        braced(tokens![&value.path, "{ .. }"])
+    ]
+}
@@ -0,0 +1,338 @@
+//! Detection of the nightly features that the expanded item needs
+//!
+//! ```text
+//! error: [AD009] the default field values of this item need the nightly feature `default_field_values`, which is not enabled
+//!  --> src/config.rs:3:1
+//!   |
+//! 3 | #[auto_default]
+//!   | ^^^^^^^^^^^^^^^
+//!   |
+//!   = help: add `#![feature(default_field_values)]` to the top of `src/lib.rs`,
+//!           or use `#[derive(AutoDefault)]`, which implements `Default` like
+//!           `#[auto_default(impl_default)]` without default field values
+//! ```
+//!
+//! On stable, the features can't be enabled, so only `#[derive(AutoDefault)]` is suggested.
+//!
+//! Without the features, `rustc` reports an error for every default field value. These point
+//! at tokens generated by the macro, so they are replaced with a single error at the attribute.
+//!
+//! Proc macros run inside of `rustc`, so the crate root is found in the arguments of the process,
+//! and the features are read from its `#![feature(...)]` attributes. If they can't be found, such
+//! as when the macro is expanded by `rust-analyzer` or `rustdoc`, the features are assumed to be
+//! enabled
+use std::env;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use proc_macro::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+use crate::diagnostic::{CompileError, ErrorCode};
+use crate::expand::{IdentExt, parse_type};
+
+/// Features enabled in the crate that is being compiled, and the path to its root
+struct Features {
+    /// Crate root, such as `src/lib.rs`
+    root: String,
+    /// Names of the enabled features
+    enabled: Vec<String>,
+}
+
+/// If the default field values of `item` need features that are not enabled, reports an error
+/// and returns `item` without its default field values, so `rustc` doesn't report an error
+/// for each of them
+///
+/// `item` is the expansion of `#[auto_default]`
+pub fn check(item: TokenStream, errors: &mut Vec<CompileError>) -> TokenStream {
+    static FEATURES: OnceLock<Option<Features>> = OnceLock::new();
+    let Some(features) = FEATURES.get_or_init(features) else {
+        return item;
+    };
+
+    let mut values = Values::default();
+    let stripped = strip_item(item.clone(), &mut values);
+
+    let mut missing = Vec::new();
+    if values.any {
+        missing.push("default_field_values");
+    }
+    // field: Type = ::core::default::Default::default()
+    //                                ^^^^^^^^^^^^^^^^^^ can only be called in `const`
+    //                                                   with `const Default`
+    if values.default_call {
+        missing.extend(["const_trait_impl", "const_default"]);
+    }
+    missing.retain(|feature| !features.enabled.iter().any(|enabled| enabled == feature));
+
+    if missing.is_empty() {
+        return item;
+    }
+
+    let list = missing
+        .iter()
+        .map(|feature| format!("`{feature}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    errors.push(
+        CompileError::new(
+            ErrorCode::MissingFeature,
+            Span::call_site(),
+            format!(
+                "the default field values of this item need the nightly {} {list}, \
+                 which {} not enabled",
+                if missing.len() == 1 {
+                    "feature"
+                } else {
+                    "features"
+                },
+                if missing.len() == 1 { "is" } else { "are" },
+            ),
+        )
+        .help(help(&missing, &features.root)),
+    );
+
+    stripped
+}
+
+/// How the `missing` features can be enabled in the crate root `root`,
+/// or avoided with `#[derive(AutoDefault)]`
+#[cfg(auto_default_nightly)]
+fn help(missing: &[&str], root: &str) -> String {
+    format!(
+        "add `#![feature({})]` to the top of `{root}`,\n\
+         or use `#[derive(AutoDefault)]`, which implements `Default` like \
+         `#[auto_default(impl_default)]` without default field values",
+        missing.join(", "),
+    )
+}
+
+/// How the missing features can be avoided, since they can't be enabled on stable
+#[cfg(not(auto_default_nightly))]
+fn help(_missing: &[&str], _root: &str) -> String {
+    "default field values need a nightly compiler. On stable, use `#[derive(AutoDefault)]`, \
+     which implements `Default` like `#[auto_default(impl_default)]` without default field values"
+        .to_string()
+}
+
+/// Default field values found by [`strip_item`]
+#[derive(Default)]
+struct Values {
+    /// A field has a default value
+    any: bool,
+    /// A default value calls `Default::default()`
+    default_call: bool,
+}
+
+/// Removes the default field values of the `struct` or `enum` at the start of `item`,
+/// and the items generated after it, which use the default field values
+fn strip_item(item: TokenStream, values: &mut Values) -> TokenStream {
+    let mut output = Vec::new();
+    let mut kind = None;
+    for tt in item {
+        match tt {
+            TokenTree::Ident(kw) if kind.is_none() && (kw.is("struct") || kw.is("enum")) => {
+                kind = Some(kw.is("struct"));
+                output.push(TokenTree::Ident(kw));
+            }
+            // struct Foo { ... }
+            //            ^^^^^^^
+            TokenTree::Group(fields)
+                if fields.delimiter() == Delimiter::Brace && kind.is_some() =>
+            {
+                let fields = if kind == Some(true) {
+                    strip_fields(&fields, values)
+                } else {
+                    strip_variants(&fields, values)
+                };
+                output.push(TokenTree::Group(fields));
+                break;
+            }
+            tt => output.push(tt),
+        }
+    }
+    output.into_iter().collect()
+}
+
+/// Removes the default field values of every variant of an enum
+fn strip_variants(variants: &Group, values: &mut Values) -> Group {
+    let mut output = Vec::new();
+    for tt in variants.stream() {
+        match tt {
+            // Variant { ... }
+            //         ^^^^^^^ not the `{ ... }` of a discriminant, which follows a `=`
+            TokenTree::Group(fields)
+                if fields.delimiter() == Delimiter::Brace
+                    && matches!(output.last(), Some(TokenTree::Ident(_))) =>
+            {
+                output.push(TokenTree::Group(strip_fields(&fields, values)));
+            }
+            tt => output.push(tt),
+        }
+    }
+    let mut group = Group::new(Delimiter::Brace, output.into_iter().collect());
+    group.set_span(variants.span());
+    group
+}
+
+/// Removes the default field values from the fields of a struct or a variant
+///
+/// ```text
+/// { a: u8 = 4, b: Vec<u8> = Vec::new() } => { a: u8, b: Vec<u8> }
+/// ```
+fn strip_fields(fields: &Group, values: &mut Values) -> Group {
+    let mut source = fields.stream().into_iter().peekable();
+    let mut output = Vec::new();
+
+    while let Some(tt) = source.next() {
+        let is_colon = matches!(&tt, TokenTree::Punct(colon) if colon.as_char() == ':');
+        output.push(tt);
+        if !is_colon {
+            continue;
+        }
+
+        // field: Type = default,
+        //        ^^^^
+        output.extend(parse_type(&mut source));
+
+        // field: Type = default,
+        //             ^^^^^^^^^
+        if source
+            .next_if(|tt| matches!(tt, TokenTree::Punct(eq) if eq.as_char() == '='))
+            .is_some()
+        {
+            values.any = true;
+            let mut value = Vec::new();
+            while let Some(tt) =
+                source.next_if(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == ','))
+            {
+                value.push(tt);
+            }
+            values.default_call |= calls_default(&value);
+        }
+
+        // field: Type,
+        //            ^
+        output.extend(source.next());
+    }
+
+    let mut group = Group::new(Delimiter::Brace, output.into_iter().collect());
+    group.set_span(fields.span());
+    group
+}
+
+/// Whether `value` calls `Default::default()`, possibly inside of a group
+///
+/// ```text
+/// [const { ::core::default::Default::default() }; 64]
+///                           ^^^^^^^^^^^^^^^^^^^^
+/// ```
+fn calls_default(value: &[TokenTree]) -> bool {
+    value.windows(5).any(|call| {
+        matches!(
+            call,
+            [
+                TokenTree::Ident(trait_),
+                TokenTree::Punct(_),
+                TokenTree::Punct(colon),
+                TokenTree::Ident(method),
+                TokenTree::Group(args),
+            ] if trait_.is("Default")
+                && colon.as_char() == ':'
+                && method.is("default")
+                && args.stream().is_empty()
+        )
+    }) || value.iter().any(|tt| {
+        matches!(tt, TokenTree::Group(group)
+            if calls_default(&group.stream().into_iter().collect::<Vec<_>>()))
+    })
+}
+
+/// Features of the crate that `rustc` is compiling
+///
+/// Returns `None` if the macro doesn't run inside of `rustc`, or the crate root can't be read
+fn features() -> Option<Features> {
+    let mut args = env::args();
+
+    // `rustdoc` compiles doctests, whose features are not in the crate root
+    let program = args.next()?;
+    let program = Path::new(&program).file_stem()?.to_str()?;
+    if program != "rustc" && program != "clippy-driver" {
+        return None;
+    }
+
+    let mut root = None;
+    let mut enabled = Vec::new();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        // -Zcrate-attr=feature(default_field_values)
+        // -Z crate-attr=feature(default_field_values)
+        let crate_attr = match arg.as_str() {
+            "-Z" => args.next_if(|arg| arg.starts_with("crate-attr=")),
+            _ => arg.strip_prefix("-Z").map(ToString::to_string),
+        };
+        if let Some(attr) = crate_attr
+            .as_deref()
+            .and_then(|arg| arg.strip_prefix("crate-attr="))
+        {
+            inner_attr_features(attr.parse().ok()?, &mut enabled);
+        } else if root.is_none()
+            && !arg.starts_with('-')
+            && arg.ends_with(".rs")
+            && Path::new(&arg).is_file()
+        {
+            root = Some(arg);
+        }
+    }
+
+    let root = root?;
+    let source = std::fs::read_to_string(&root).ok()?;
+    let source = source.parse::<TokenStream>().ok()?;
+
+    // #![feature(default_field_values)]
+    // ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ inner attributes are at the start of the crate root
+    let mut source = source.into_iter();
+    loop {
+        match (source.next(), source.next(), source.next()) {
+            (
+                Some(TokenTree::Punct(pound)),
+                Some(TokenTree::Punct(bang)),
+                Some(TokenTree::Group(attr)),
+            ) if pound.as_char() == '#'
+                && bang.as_char() == '!'
+                && attr.delimiter() == Delimiter::Bracket =>
+            {
+                inner_attr_features(attr.stream(), &mut enabled);
+            }
+            _ => break,
+        }
+    }
+
+    Some(Features { root, enabled })
+}
+
+/// Adds the features of the inner attribute `attr` to `enabled`
+///
+/// Features behind `#![cfg_attr(...)]` are assumed to be enabled,
+/// since the condition can't be checked
+///
+/// ```text
+/// feature(default_field_values, const_default)
+/// cfg_attr(nightly, feature(default_field_values))
+/// ```
+fn inner_attr_features(attr: TokenStream, enabled: &mut Vec<String>) {
+    let mut attr = attr.into_iter().peekable();
+    while let Some(tt) = attr.next() {
+        match tt {
+            TokenTree::Ident(feature) if feature.is("feature") => {
+                if let Some(TokenTree::Group(list)) = attr.peek() {
+                    enabled.extend(list.stream().into_iter().filter_map(|tt| match tt {
+                        TokenTree::Ident(name) => Some(name.to_string()),
+                        _ => None,
+                    }));
+                }
+            }
+            TokenTree::Group(group) => inner_attr_features(group.stream(), enabled),
+            _ => {}
+        }
+    }
+}
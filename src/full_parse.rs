@@ -0,0 +1,144 @@
+//! Parsing with `syn`, enabled by the `full-parse` feature
+//!
+//! The streaming parser in [`expand`](mod@crate::expand) only looks at as many tokens as it needs,
+//! which is fast but misses some syntax. Most notably, it doesn't see through the invisible
+//! groups that `macro_rules!` wraps fragments such as `$vis:vis` or `$meta:meta` in:
+//!
+//! ```rust,ignore
+//! macro_rules! item {
+//!     ($vis:vis $name:ident) => {
+//!         #[auto_default]
+//!         $vis struct $name { ... }
+//!     };
+//! }
+//! ```
+//!
+//! `syn` can't parse default field values, so it is not used for the whole item. Instead,
+//! each function here parses one part of it, and keeps the tokens as they are if `syn` can't
+//! parse that part.
+//!
+//! `syn` is only given the tokens of that part, which the streaming parser finds. Giving it
+//! the rest of the item every time would make parsing an item with many fields take
+//! quadratic time
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+use syn::parse::{ParseStream, Parser as _};
+
+use crate::expand::{IdentExt, Source};
+use crate::generics::Generics;
+
+/// Visibility at the start of `source`
+///
+/// ```text
+/// pub(crate) field: Type
+/// ^^^^^^^^^^
+/// ```
+///
+/// Returns `None` if the visibility is inherited, and not in a `$vis:vis` fragment
+pub fn vis(source: &mut Source) -> Option<TokenStream> {
+    let vis = match source.peek()? {
+        // $vis
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            TokenStream::from_iter(source.next())
+        }
+        // pub(crate)
+        TokenTree::Ident(ident) if ident.is("pub") => {
+            let mut vis = TokenStream::from_iter(source.next());
+            vis.extend(source.next_if(
+                |tt| matches!(tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis),
+            ));
+            vis
+        }
+        _ => return None,
+    };
+
+    Some(parse(vis, |input| input.parse::<syn::Visibility>()))
+}
+
+/// Type of a field, which the streaming parser collected
+///
+/// ```text
+/// field: Vec<u8> = default
+///        ^^^^^^^
+/// ```
+pub fn ty(ty: Vec<TokenTree>) -> Vec<TokenTree> {
+    parse(ty.into_iter().collect(), |input| input.parse::<syn::Type>())
+        .into_iter()
+        .collect()
+}
+
+/// Generics and the `where` clause, which are everything between the name of the item
+/// and its fields
+///
+/// ```text
+/// struct Foo<T> where T: Trait { ... }
+///           ^^^^^^^^^^^^^^^^^^
+/// ```
+pub fn generics(tokens: TokenStream) -> Option<Generics> {
+    let parser = |input: ParseStream| {
+        let mut generics = input.parse::<syn::Generics>()?;
+        generics.where_clause = input.parse()?;
+        Ok(generics)
+    };
+    let generics = parser.parse2(proc_macro2::TokenStream::from(tokens)).ok()?;
+
+    let (params, args, where_clause) = generics.split_for_impl();
+
+    Some(Generics {
+        params: without_angle_brackets(quote::ToTokens::to_token_stream(&params)),
+        args: without_angle_brackets(quote::ToTokens::to_token_stream(&args)),
+        where_clause: TokenStream::from(quote::ToTokens::to_token_stream(&where_clause)),
+    })
+}
+
+/// Contents of the attribute, without invisible groups, so that the streaming parser can tell
+/// which attribute it is
+///
+/// ```text
+/// #[$meta]
+///   ^^^^^ `auto_default(skip)`, wrapped in an invisible group
+/// ```
+///
+/// The attribute is returned unchanged if it has no invisible groups
+pub fn attr(attr: Group) -> Group {
+    let has_invisible_group = attr
+        .stream()
+        .into_iter()
+        .any(|tt| matches!(tt, TokenTree::Group(group) if group.delimiter() == Delimiter::None));
+    if !has_invisible_group {
+        return attr;
+    }
+
+    let Ok(meta) = syn::parse2::<syn::Meta>(proc_macro2::TokenStream::from(attr.stream())) else {
+        return attr;
+    };
+
+    let mut flattened = Group::new(
+        attr.delimiter(),
+        TokenStream::from(quote::ToTokens::to_token_stream(&meta)),
+    );
+    flattened.set_span(attr.span());
+    flattened
+}
+
+/// Parses all of `tokens` with `parser`
+///
+/// If `parser` fails, or doesn't parse every token, `tokens` are returned unchanged
+fn parse<T: quote::ToTokens>(
+    tokens: TokenStream,
+    parser: impl FnOnce(ParseStream) -> syn::Result<T>,
+) -> TokenStream {
+    match parser.parse2(proc_macro2::TokenStream::from(tokens.clone())) {
+        Ok(parsed) => TokenStream::from(quote::ToTokens::to_token_stream(&parsed)),
+        Err(_) => tokens,
+    }
+}
+
+/// `<'a, T>` => `'a, T`
+fn without_angle_brackets(tokens: proc_macro2::TokenStream) -> TokenStream {
+    let mut tokens = TokenStream::from(tokens).into_iter().collect::<Vec<_>>();
+    if matches!(tokens.first(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<') {
+        tokens.pop();
+        tokens.remove(0);
+    }
+    tokens.into_iter().collect()
+}
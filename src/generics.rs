@@ -0,0 +1,161 @@
+//! Generics of the item, split into the parts that an `impl` block for the item needs
+//!
+//! ```text
+//! struct Foo<'a, T: Trait = u8, const N: usize = 4> where T: Other { ... }
+//!           ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+//!
+//! impl<'a, T: Trait, const N: usize> Foo<'a, T, N> where T: Other { ... }
+//!     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^    ^^^^^^^^^^^^^^^^^^^^^^^^^^^
+//! ```
+use proc_macro::{Ident, Spacing, TokenStream, TokenTree};
+
+use crate::expand::{IdentExt, is_closing_angle_bracket};
+use crate::tokens::tokens;
+
+/// Generics of a `struct` or an `enum`
+#[derive(Default)]
+pub struct Generics {
+    /// Parameters of the `impl` block. Bounds are kept, defaults are removed
+    ///
+    /// ```text
+    /// 'a, T: Trait, const N: usize
+    /// ```
+    pub params: TokenStream,
+    /// Arguments that name the item's type inside of the `impl` block
+    ///
+    /// ```text
+    /// 'a, T, N
+    /// ```
+    pub args: TokenStream,
+    /// The `where` clause, including the `where` keyword
+    pub where_clause: TokenStream,
+}
+
+impl Generics {
+    /// Parses the tokens between the name of the item and its fields
+    ///
+    /// ```text
+    /// struct Foo<T> where T: Trait { ... }
+    ///           ^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub fn parse(tokens: TokenStream) -> Self {
+        #[cfg(feature = "full-parse")]
+        if let Some(generics) = crate::full_parse::generics(tokens.clone()) {
+            return generics;
+        }
+
+        let mut tokens = tokens.into_iter().peekable();
+        let mut this = Self::default();
+
+        if !matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<') {
+            this.where_clause = tokens.collect();
+            return this;
+        }
+        tokens.next();
+
+        // How many `<` we are inside of, not counting the `<` that opens the generics
+        let mut angle_depth = 0_usize;
+        // Tokens of the generic parameter that we are currently parsing
+        let mut param = Vec::new();
+        let mut prev: Option<TokenTree> = None;
+
+        for tt in tokens.by_ref() {
+            if let TokenTree::Punct(punct) = &tt {
+                match punct.as_char() {
+                    '<' => angle_depth += 1,
+                    '>' if is_closing_angle_bracket(punct, prev.as_ref()) => {
+                        if angle_depth == 0 {
+                            this.push_param(std::mem::take(&mut param));
+                            break;
+                        }
+                        angle_depth -= 1;
+                    }
+                    ',' if angle_depth == 0 => {
+                        this.push_param(std::mem::take(&mut param));
+                        prev = Some(tt);
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            prev = Some(tt.clone());
+            param.push(tt);
+        }
+
+        this.where_clause = tokens.collect();
+        this
+    }
+
+    /// Adds a single generic parameter, such as `T: Trait = u8`
+    fn push_param(&mut self, param: Vec<TokenTree>) {
+        let mut param = param.into_iter().peekable();
+
+        // Attributes only belong to the declaration of the parameter
+        //
+        // #[cfg(feature = "x")] T
+        // ^^^^^^^^^^^^^^^^^^^^^
+        let mut attrs = TokenStream::new();
+        while matches!(param.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+            attrs.extend(param.next());
+            attrs.extend(param.next());
+        }
+
+        let param = param.collect::<Vec<_>>();
+
+        // T: Trait = u8
+        //          ^ everything from here is the default of the parameter
+        let mut angle_depth = 0_usize;
+        let mut end = param.len();
+        for (i, tt) in param.iter().enumerate() {
+            let TokenTree::Punct(punct) = tt else {
+                continue;
+            };
+            match punct.as_char() {
+                '<' => angle_depth += 1,
+                '>' if is_closing_angle_bracket(punct, i.checked_sub(1).map(|i| &param[i])) => {
+                    angle_depth = angle_depth.saturating_sub(1);
+                }
+                '=' if angle_depth == 0 && punct.spacing() == Spacing::Alone => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let param = &param[..end];
+
+        // 'a: 'b   => 'a
+        // T: Trait => T
+        // const N: usize => N
+        let name = match param {
+            [quote @ TokenTree::Punct(punct), lifetime, ..] if punct.as_char() == '\'' => {
+                vec![quote.clone(), lifetime.clone()]
+            }
+            [TokenTree::Ident(kw), name, ..] if kw.is("const") => vec![name.clone()],
+            [name, ..] => vec![name.clone()],
+            [] => return,
+        };
+
+        if !self.args.is_empty() {
+            self.params.extend(tokens![","]);
+            self.args.extend(tokens![","]);
+        }
+        self.params.extend(attrs);
+        self.params.extend(param.iter().cloned());
+        self.args.extend(name);
+    }
+
+    /// `impl<params> Ident<args> where_clause`
+    pub fn impl_header(&self, ident: &Ident) -> TokenStream {
+        tokens![
+            "impl <",
+            &self.params,
+            ">",
+            ident,
+            "<",
+            &self.args,
+            ">",
+            &self.where_clause
+        ]
+    }
+}
@@ -0,0 +1,251 @@
+//! Implementation of `Default` that is generated next to the item by `#[auto_default(impl_default)]`
+//!
+//! ```rust,ignore
+//! #[auto_default(impl_default)]
+//! struct Foo<T, U> {
+//!     a: Vec<T>,
+//!     #[auto_default(skip)]
+//!     b: U,
+//! }
+//!
+//! impl<T, U> Default for Foo<T, U> where U: Default {
+//!     fn default() -> Self {
+//!         Foo { b: Default::default(), .. }
+//!     }
+//! }
+//! ```
+//!
+//! Fields with a default value don't need a bound, because their default value is already
+//! checked where the item is defined. Only the types of fields without a default value that
+//! mention a generic parameter get a bound, unlike `#[derive(Default)]` which bounds every
+//! generic parameter with `Default`
+use proc_macro::{Ident, TokenStream, TokenTree};
+
+use crate::args::Args;
+use crate::expand::{DefaultValue, Field, IdentExt, const_default_trait, core_default};
+use crate::generics::Generics;
+use crate::tokens::{braced, parenthesized, tokens};
+
+/// `impl Default` which returns `value`
+///
+/// `bound` of `args` replaces the inferred where predicates, if it is passed.
+/// `finalize` is called with the value before it is returned
+///
+/// With `#[derive(AutoDefault)]`, the item doesn't have the default field values
+/// added by the macro, so they are set by `impl Default` instead. If the item is `stripped`
+/// of all of its default field values, `impl Default` sets every field
+pub fn impl_default(
+    ident: &Ident,
+    generics: &Generics,
+    value: &DefaultValue,
+    fields: &[Field],
+    args: &Args,
+    stripped: bool,
+) -> TokenStream {
+    let fields = value.fields(fields);
+
+    // Only the fields with a default value in the item can be left out
+    let in_item =
+        |field: &Field| field.default.is_some() && !stripped && (field.custom || !args.derive);
+
+    let (lets, mut value) = construct(value, fields, in_item, !(args.derive || stripped));
+
+    // let mut value = Foo { .. };
+    // Foo::validate(&mut value);
+    // value
+    if let Some(finalize) = &args.finalize {
+        value = tokens![
+            "let mut value =",
+            value,
+            ";",
+            finalize,
+            parenthesized(tokens!["&mut value"]),
+            "; value"
+        ];
+    }
+
+    let bound = args.bound.clone().unwrap_or_else(|| {
+        let params = type_params(generics);
+        let mut bound = TokenStream::new();
+        for field in fields {
+            if !in_item(field) && field.runtime.is_none() && mentions(&field.ty, &params) {
+                bound.extend(tokens![&field.ty, ": ::core::default::Default,"]);
+            }
+        }
+        bound
+    });
+
+    tokens![
+        "impl <",
+        &generics.params,
+        "> ::core::default::Default for",
+        ident,
+        "<",
+        &generics.args,
+        ">",
+        where_clause(generics, bound),
+        braced(tokens![
+            "fn default() -> Self",
+            braced(tokens![lets, value])
+        ])
+    ]
+}
+
+/// `value` created from the default values of `fields`, and the `let` statements
+/// that come before it
+///
+/// Fields for which `in_item` returns `true` are left out, so they get their default field
+/// value. `rest` adds `..` even if no field is left out
+pub fn construct(
+    value: &DefaultValue,
+    fields: &[Field],
+    in_item: impl Fn(&Field) -> bool,
+    rest: bool,
+) -> (TokenStream, TokenStream) {
+    let has_fields = value.fields.is_some();
+
+    // Fields without a default value get `Default::default()`, or the value
+    // of `#[auto_default(runtime = expr)]`
+    //
+    // Foo { b: Default::default(), .. }
+    //          ^^^^^^^^^^^^^^^^^^^
+    //
+    // With `#[auto_default(value = expr)]`, every field is bound with `let` in the order
+    // of the fields, so that `expr` can use the fields before it
+    let init = |field: &Field| match (&field.runtime, &field.default) {
+        (Some(runtime), _) => runtime.clone(),
+        (None, Some(default)) => tokens![default],
+        (None, None) => tokens![&core_default(field.ident.span())],
+    };
+
+    let mut value = value.path.clone();
+    let mut lets = TokenStream::new();
+    if fields.iter().any(|field| field.refers_to_fields) {
+        // #[auto_default(value = buffer_size * 2)]
+        //
+        // let buffer_size: usize = 1024;
+        // let cache_size: usize = buffer_size * 2;
+        // Foo { buffer_size, cache_size }
+        let mut inits = TokenStream::new();
+        for field in fields {
+            lets.extend(tokens![
+                &field.cfg,
+                "let",
+                &field.ident,
+                ":",
+                &field.ty,
+                "=",
+                init(field),
+                ";"
+            ]);
+            inits.extend(tokens![&field.cfg, &field.ident, ","]);
+        }
+        value.extend(tokens![braced(inits)]);
+    } else if has_fields {
+        // Foo { b: Default::default(), .. }
+        let mut inits = TokenStream::new();
+        for field in fields.iter().filter(|field| !in_item(field)) {
+            inits.extend(tokens![&field.cfg, &field.ident, ":", init(field), ","]);
+        }
+        if rest || fields.iter().any(&in_item) {
+            inits.extend(tokens![".."]);
+        }
+        value.extend(tokens![braced(inits)]);
+    }
+
+    (lets, value)
+}
+
+/// `impl ConstDefault` of the `const-default` crate, whose `DEFAULT` is `value`
+///
+/// Fields without a default value get `<Type as ConstDefault>::DEFAULT`, and their types
+/// are bounded with `ConstDefault` if they mention a generic parameter
+///
+/// ```rust,ignore
+/// impl<T> ::const_default::ConstDefault for Foo<T> where T: ::const_default::ConstDefault {
+///     const DEFAULT: Self = Foo { b: <T as ::const_default::ConstDefault>::DEFAULT, .. };
+/// }
+/// ```
+pub fn impl_const_default(
+    ident: &Ident,
+    generics: &Generics,
+    value: &DefaultValue,
+    fields: &[Field],
+) -> TokenStream {
+    let has_fields = value.fields.is_some();
+    let fields = value.fields(fields);
+    let params = type_params(generics);
+
+    let mut value = value.path.clone();
+    let mut bound = TokenStream::new();
+    if has_fields {
+        let mut inits = TokenStream::new();
+        for field in fields.iter().filter(|field| field.default.is_none()) {
+            let init = const_default_trait(&field.ty, field.ident.span());
+            inits.extend(tokens![&field.cfg, &field.ident, ":", &init, ","]);
+            if mentions(&field.ty, &params) {
+                bound.extend(tokens![&field.ty, ": ::const_default::ConstDefault,"]);
+            }
+        }
+        value.extend(tokens![braced(tokens![inits, ".."])]);
+    }
+
+    tokens![
+        "impl <",
+        &generics.params,
+        "> ::const_default::ConstDefault for",
+        ident,
+        "<",
+        &generics.args,
+        ">",
+        where_clause(generics, bound),
+        braced(tokens!["const DEFAULT: Self =", value, ";"])
+    ]
+}
+
+/// Where clause of the item, with the predicates of `bound` added to it
+///
+/// ```text
+/// where T: Trait, U: Default
+/// ```
+fn where_clause(generics: &Generics, bound: TokenStream) -> TokenStream {
+    let mut where_clause = generics.where_clause.clone();
+    if !bound.is_empty() {
+        match where_clause.clone().into_iter().last() {
+            None => where_clause.extend(tokens!["where"]),
+            Some(TokenTree::Punct(comma)) if comma.as_char() == ',' => {}
+            Some(_) => where_clause.extend(tokens![","]),
+        }
+        where_clause.extend(bound);
+    }
+    where_clause
+}
+
+/// Names of the type and const parameters
+///
+/// ```text
+/// 'a, T, N => T, N
+/// ```
+pub fn type_params(generics: &Generics) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut lifetime = false;
+    for tt in generics.args.clone() {
+        match tt {
+            TokenTree::Punct(quote) if quote.as_char() == '\'' => lifetime = true,
+            TokenTree::Ident(ident) if !lifetime => params.push(ident.to_string()),
+            _ => lifetime = false,
+        }
+    }
+    params
+}
+
+/// Whether `ty` mentions any of the generic parameters `params`
+pub fn mentions(ty: &[TokenTree], params: &[String]) -> bool {
+    ty.iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => params.iter().any(|param| ident.is(param)),
+        TokenTree::Group(group) => {
+            mentions(&group.stream().into_iter().collect::<Vec<_>>(), params)
+        }
+        _ => false,
+    })
+}
@@ -12,7 +12,7 @@
 //! auto-default = "0.2"
 //! ```
 //!
-//! Note: `auto-default` has *zero* dependencies. Not even `syn`! The compile times are very fast.
+//! Note: `auto-default` has *no* dependencies by default, not even `syn`, so it compiles very fast. `syn` is only pulled in by the optional `full-parse` feature.
 //!
 //! ## Showcase
 //!
@@ -84,58 +84,1438 @@
 //!
 //! If any field or variant has the `#[auto_default(skip)]` attribute, a default field value of `Default::default()`
 //! will **not** be added
-use std::iter::Peekable;
-
-use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+//!
+//! The crate also builds on stable Rust, where [`#[derive(AutoDefault)]`](derive@AutoDefault) and
+//! [`construct!`](macro@construct) can be used. Warnings, and notes that point at other parts of the code,
+//! are only reported on nightly
+#![cfg_attr(auto_default_nightly, feature(proc_macro_diagnostic))]
+#![cfg_attr(auto_default_nightly, feature(proc_macro_span))]
+#![cfg_attr(auto_default_nightly, feature(proc_macro_tracked_env))]
+#![cfg_attr(auto_default_nightly, feature(proc_macro_tracked_path))]
+mod args;
+mod assert_default;
+mod construct;
+mod derives;
+mod diagnostic;
+mod disabled;
+mod expand;
+mod extern_c;
+mod features;
+#[cfg(feature = "full-parse")]
+mod full_parse;
+mod generics;
+mod impl_default;
+mod methods;
+mod policy;
+mod pretty;
+mod self_test;
+mod tokens;
+mod tracked;
+mod wasm;
+
+use std::ffi::OsStr;
+use std::path::Path;
+
+use args::Args;
+use diagnostic::CompileError;
+use expand::{expand, take_container_args};
+use policy::Policy;
+use proc_macro::TokenStream;
+#[cfg(auto_default_nightly)]
+use proc_macro::{Diagnostic, Level, Span};
 
 /// Adds a default field value of `Default::default()` to fields that don't have one
 ///
-/// # Example
+/// # Example
+///
+/// Turns this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default]
+/// struct User {
+///     age: u8,
+///     is_admin: bool = false
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// Into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// struct User {
+///     age: u8 = Default::default(),
+///     is_admin: bool = false
+/// }
+/// ```
+///
+/// This macro applies to `struct`s with named fields, and enums.
+///
+/// If the macro would not change the item, because it has no named fields
+/// or every field already has a default value, a warning is emitted.
+/// Unit structs such as `struct Marker;` are kept as they are, without a warning.
+///
+/// # Do not add `= Default::default()` field value to select fields
+///
+/// If you do not want a specific field to have a default, you can opt-out
+/// with `#[auto_default(skip)]`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default]
+/// struct User {
+///     #[auto_default(skip)]
+///     age: u8,
+///     is_admin: bool
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// struct User {
+///     age: u8,
+///     is_admin: bool = Default::default()
+/// }
+/// ```
+///
+/// The reason for skipping can be given with `reason`. It is added to the documentation
+/// of the field, or of the variant:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default]
+/// struct User {
+///     #[auto_default(skip, reason = "assigned by the database")]
+///     id: u64,
+///     is_admin: bool
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// `#[auto_default::skip]` is a shorter way to write `#[auto_default(skip)]`. With
+/// `#[auto_default(alias = name)]` on the item, `#[name]` can be used instead, which is useful
+/// when many fields are skipped:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(alias = adskip)]
+/// struct User {
+///     #[auto_default::skip]
+///     id: u64,
+///     #[adskip(reason = "chosen by the user")]
+///     name: String,
+///     is_admin: bool
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// If another macro also reads `#[auto_default(...)]` attributes on fields,
+/// `#[auto_default(helper = name)]` on the item makes the options of fields and variants be read
+/// from `#[name(...)]` instead. `#[auto_default(...)]` on the fields is then left to the other macro:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # const fn seven() -> u8 { 7 }
+/// #[auto_default(helper = adf)]
+/// struct User {
+///     #[adf(skip)]
+///     id: u64,
+///     #[adf(with = seven)]
+///     level: u8,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// Variants of an enum can also be skipped with `#[auto_default(skip_variants(...))]` on
+/// the enum, which is useful when the variants can't be annotated, for example because
+/// they are generated by a macro:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(skip_variants(Error))]
+/// enum Event {
+///     Click { x: u32, y: u32 },
+///     Error { code: u32 },
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// Fields can be skipped by their name with `#[auto_default(skip_fields(...))]` on the item.
+/// `prefix = "..."` skips every field whose name starts with the prefix, and `names(...)` lists
+/// the names of skipped fields. This is useful for generated structs, such as bindings to C,
+/// which follow a naming convention:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(skip_fields(prefix = "raw_", names(handle, fd)))]
+/// struct Device {
+///     raw_flags: u32,
+///     raw_mode: u32,
+///     handle: i32,
+///     fd: i32,
+///     timeout: u32,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// Only `timeout` gets a default value. Options on a field, such as `with = path`,
+/// take precedence over `skip_fields`
+///
+/// `#[auto_default(skip_underscored)]` skips every field whose name starts with `_`, such as
+/// `_private: ()` of a sealed struct. Like with `skip_fields`, a field that still needs a default
+/// value can be given one by hand, with `= value`
+///
+/// `PhantomData` always has a default value, so a `PhantomData` field skipped by its variant,
+/// by `skip_variants`, by `skip_fields` or by `skip_underscored` still gets `PhantomData`.
+/// Otherwise, `_marker: PhantomData<T>` would keep `Foo { .. }` from compiling.
+/// `#[auto_default(skip_phantom_data)]` on the item skips them like the other fields
+///
+/// `#[auto_default(include)]` on a field adds a default value to it, even if it is skipped by
+/// its variant, by `skip_variants`, by `skip_fields` or by `skip_underscored`. The skip of the field
+/// itself can't be overridden, so `include` together with `skip` on the same field is an error:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default]
+/// enum Event {
+///     #[auto_default(skip)]
+///     Error {
+///         code: u32,
+///         #[auto_default(include)]
+///         retries: u8,
+///     },
+/// }
+/// # use auto_default::auto_default;
+///
+/// let _ = Event::Error { code: 404, .. };
+/// ```
+///
+/// `#[auto_default(skip)]` on a field that has a default value does nothing, so it is reported
+/// with a warning. Such attributes are usually left over after a default value was added to the
+/// field. The warning can be silenced with `#[auto_default(allow_unused_skip)]` on the item
+///
+/// # Default values in one place
+///
+/// `#[auto_default(values(...))]` on a struct or on a variant gives default values to fields
+/// by their names, so the ones that are not `Default::default()` can be read together at the top
+/// of the item. The other fields get `Default::default()`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// # use std::time::Duration;
+/// #[auto_default(values(timeout = Duration::from_secs(30), retries = 3))]
+/// struct Client {
+///     timeout: Duration,
+///     retries: u8,
+///     verbose: bool,
+/// }
+/// ```
+///
+/// A field in `values(...)` can't also have a default value after its type, or an option such
+/// as `with = path` or `skip`. Fields of a skipped variant still get the values listed for them
+///
+/// # Literals for primitive types
+///
+/// With `#[auto_default(infer_literals)]`, fields of a primitive type get a literal
+/// instead of `Default::default()`:
+///
+/// - Integers get `0`
+/// - Floats get `0.0`
+/// - `bool` gets `false`
+/// - `char` gets `'\0'`
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// #[auto_default(infer_literals)]
+/// struct User {
+///     age: u8,
+///     is_admin: bool
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// struct User {
+///     age: u8 = 0,
+///     is_admin: bool = false
+/// }
+/// ```
+///
+/// Literals can be evaluated in `const` contexts without needing `const Default`.
+///
+/// # Constructors for well-known types
+///
+/// With `#[auto_default(known_types)]`, fields of these types from the standard library get
+/// a `const` constructor instead of `Default::default()`:
+///
+/// - `Option<T>` gets `None`
+/// - `Vec<T>` gets `Vec::new()`
+/// - `String` gets `String::new()`
+/// - `&'static str` gets `""`
+/// - `PhantomData<T>` gets `PhantomData`
+///
+/// Together with `infer_literals`, structs whose fields only have such types can be created
+/// in `const` contexts and `static`s, without `#![feature(const_default)]`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// #[auto_default(known_types, infer_literals)]
+/// struct Config {
+///     name: Option<String>,
+///     paths: Vec<String>,
+///     retries: u8,
+/// }
+///
+/// static CONFIG: Config = Config { .. };
+/// # use auto_default::auto_default;
+/// ```
+///
+/// # Arrays
+///
+/// Arrays only implement `Default` up to a length of 32, so fields of an array type get a repeat
+/// expression instead, with the default value of the elements:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default]
+/// struct Frame {
+///     pixels: [u32; 1024],
+///     labels: [String; 64],
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// struct Frame {
+///     pixels: [u32; 1024] = [const { ::core::default::Default::default() }; 1024],
+///     labels: [String; 64] = [const { ::core::default::Default::default() }; 64],
+/// }
+/// ```
+///
+/// The elements get the same default values as fields of their type, so with
+/// `#[auto_default(infer_literals)]`, the `pixels` get `[0; 1024]`
+///
+/// # Struct expressions for nested structs
+///
+/// If the type of a field is a struct that has a default value for each field, such as one using
+/// `#[auto_default]`, mark the field with `#[auto_default(struct_default)]` to get `Type { .. }`
+/// instead of `Default::default()`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// #[auto_default(infer_literals)]
+/// struct Settings {
+///     volume: u8,
+/// }
+///
+/// #[auto_default]
+/// struct User {
+///     #[auto_default(struct_default)]
+///     settings: Settings,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// struct Settings {
+///     volume: u8 = 0,
+/// }
+///
+/// struct User {
+///     settings: Settings = Settings { .. },
+/// }
+/// ```
+///
+/// Like literals, `Type { .. }` can be evaluated in `const` contexts without needing `const Default`.
+///
+/// Use `#[auto_default(struct_default)]` on the container to apply it to every field.
+/// Fields of a primitive type still get a literal if `infer_literals` is also used.
+///
+/// # Associated constants
+///
+/// Many types provide a `DEFAULT` constant, because `Default::default()` can't be called in
+/// `const` contexts on stable Rust. Mark the field with `#[auto_default(assoc_const)]`
+/// to get `<Type>::DEFAULT` instead of `Default::default()`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// struct Vec2 {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// impl Vec2 {
+///     const DEFAULT: Self = Self { x: 0.0, y: 0.0 };
+/// }
+///
+/// #[auto_default(infer_literals)]
+/// struct Body {
+///     #[auto_default(assoc_const)]
+///     position: Vec2,
+///     mass: f32,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # struct Vec2 { x: f32, y: f32 }
+/// # impl Vec2 { const DEFAULT: Self = Self { x: 0.0, y: 0.0 }; }
+/// struct Body {
+///     position: Vec2 = <Vec2>::DEFAULT,
+///     mass: f32 = 0.0,
+/// }
+/// ```
+///
+/// Use `#[auto_default(assoc_const)]` on the container to apply it to every field.
+/// Fields of a primitive type still get a literal if `infer_literals` is also used.
+///
+/// # Constructors
+///
+/// Many types have a `const fn new()` but don't implement `const Default`, such as `Vec`,
+/// `String` and `BTreeMap`. Use `#[auto_default(prefer_new)]` to get `<Type>::new()` instead of
+/// `Default::default()`, which doesn't need the `const_trait_impl` and `const_default` features:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// use std::collections::BTreeMap;
+///
+/// #[auto_default(prefer_new)]
+/// struct Cache {
+///     entries: BTreeMap<u32, String>,
+///     name: String,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # use std::collections::BTreeMap;
+/// struct Cache {
+///     entries: BTreeMap<u32, String> = <BTreeMap<u32, String>>::new(),
+///     name: String = <String>::new(),
+/// }
+/// ```
+///
+/// Like `assoc_const`, `prefer_new` can also be used on a single field.
+/// Fields of a primitive type still get a literal if `infer_literals` is also used.
+///
+/// # Constants named after the fields
+///
+/// If the default values live in a module of constants, `#[auto_default(consts = defaults)]`
+/// gives each field the constant named after it, such as `defaults::MAX_RETRIES`
+/// for `max_retries`. Fields that don't have a constant get `Default::default()`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// mod defaults {
+///     pub const PORT: u16 = 8080;
+///     pub const HOST: &str = "localhost";
+/// }
+///
+/// #[auto_default(consts = defaults)]
+/// struct Server {
+///     port: u16,
+///     host: &'static str,
+///     workers: u8,
+/// }
+///
+/// let server = Server { .. };
+/// assert_eq!((server.port, server.host, server.workers), (8080, "localhost", 0));
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The macro can't see which constants the module has, so the constant is found by name
+/// resolution in the generated code. With `strict_consts = defaults`, every field gets
+/// its constant, and a missing one is an error that points at the field:
+///
+/// ```rust,compile_fail
+/// # #![feature(default_field_values)]
+/// mod defaults {
+///     pub const PORT: u16 = 8080;
+/// }
+///
+/// #[auto_default(strict_consts = defaults)]
+/// struct Server {
+///     port: u16,
+///     workers: u8, // error: cannot find value `WORKERS` in module `defaults`
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// Fields with a default value, or with an option such as `#[auto_default(with = path)]`,
+/// don't use the constants.
+///
+/// # The `ConstDefault` trait
+///
+/// The [`const-default`](https://docs.rs/const-default) crate provides the `ConstDefault` trait,
+/// whose `DEFAULT` constant is used by many embedded crates. Use
+/// `#[auto_default(const_default_trait)]` to get `<Type as ConstDefault>::DEFAULT` instead of
+/// `Default::default()`, and `impl_const_default` to implement `ConstDefault` for the item:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// use const_default::ConstDefault;
+///
+/// #[auto_default(const_default_trait, impl_const_default)]
+/// struct Config {
+///     retries: Option<u8>,
+///     timeout: u32 = 30,
+/// }
+/// # use auto_default::auto_default;
+///
+/// const CONFIG: Config = Config::DEFAULT;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// struct Config {
+///     retries: Option<u8> = <Option<u8> as ::const_default::ConstDefault>::DEFAULT,
+///     timeout: u32 = 30,
+/// }
+///
+/// impl ::const_default::ConstDefault for Config {
+///     const DEFAULT: Self = Config { .. };
+/// }
+/// ```
+///
+/// Like `assoc_const`, `const_default_trait` can also be used on a single field.
+/// Fields without a default value, such as skipped fields, get `<Type as ConstDefault>::DEFAULT`
+/// in the implementation of `ConstDefault`.
+///
+/// # Custom default function
+///
+/// Use `#[auto_default(with = path)]` to get `path()` instead of `Default::default()`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # mod my_crate {
+/// #     pub const trait Reset { fn reset() -> Self; }
+/// #     impl const Reset for u8 { fn reset() -> Self { 1 } }
+/// #     impl const Reset for bool { fn reset() -> Self { true } }
+/// # }
+/// # const fn two() -> u8 { 2 }
+/// #[auto_default(with = my_crate::Reset::reset)]
+/// struct User {
+///     age: u8,
+///     is_admin: bool,
+///     #[auto_default(with = two)]
+///     level: u8,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # mod my_crate {
+/// #     pub const trait Reset { fn reset() -> Self; }
+/// #     impl const Reset for u8 { fn reset() -> Self { 1 } }
+/// #     impl const Reset for bool { fn reset() -> Self { true } }
+/// # }
+/// # const fn two() -> u8 { 2 }
+/// struct User {
+///     age: u8 = my_crate::Reset::reset(),
+///     is_admin: bool = my_crate::Reset::reset(),
+///     level: u8 = two(),
+/// }
+/// ```
+///
+/// On a field, `with` takes precedence over options of the container.
+/// On the container, `with` only replaces `Default::default()`, so `infer_literals` and
+/// `struct_default` still apply.
+///
+/// # Document default values
+///
+/// With `#[auto_default(doc_defaults)]`, every field that has a default value gets
+/// documentation saying what the default is:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(doc_defaults)]
+/// pub struct User {
+///     /// Age of the user
+///     pub age: u8,
+///     pub is_admin: bool = true,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// pub struct User {
+///     /// Age of the user
+///     ///
+///     /// Defaults to `::core::default::Default::default()`
+///     pub age: u8 = ::core::default::Default::default(),
+///     /// Defaults to `true`
+///     pub is_admin: bool = true,
+/// }
+/// ```
+///
+/// # Normalize hand-written default values
+///
+/// `#[auto_default(normalize)]` replaces default values that are written by hand as
+/// `Default::default()`, `Type::default()`, `<Type>::default()` or `<Type as Default>::default()`
+/// with `::core::default::Default::default()`, which is what the macro adds to the other fields.
+/// With `short_default`, they are replaced with `Default::default()` instead:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(normalize)]
+/// struct User {
+///     age: u8 = Default::default(),
+///     name: String = String::default(),
+///     is_admin: bool,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// struct User {
+///     age: u8 = ::core::default::Default::default(),
+///     name: String = ::core::default::Default::default(),
+///     is_admin: bool = ::core::default::Default::default(),
+/// }
+/// ```
+///
+/// # Report redundant default values
+///
+/// `#[auto_default(deny_redundant)]` reports an error for every default value that is written
+/// by hand as `Default::default()`, `Type::default()`, `<Type>::default()` or
+/// `<Type as Default>::default()`, because the macro would add the same default value.
+/// This keeps the boilerplate from coming back as the item changes:
+///
+/// ```rust,compile_fail
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(deny_redundant)]
+/// struct User {
+///     // error: the default value of `age` is redundant
+///     age: u8 = Default::default(),
+///     is_admin: bool,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// `#[auto_default(warn_redundant)]` reports warnings instead. Default values are not redundant
+/// if the macro adds something else to the field, such as with `struct_default` or `with = path`
+///
+/// # Check that every field has a default value
+///
+/// `#[auto_default(check)]` doesn't add any default values. Instead, every field without one
+/// is reported with an error, unless it is skipped. This keeps the default values written
+/// in the source, while making sure that `Foo { .. }` keeps working when fields are added:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// #[auto_default(check)]
+/// struct Config {
+///     retries: u8 = 3,
+///     name: String = String::new(),
+///     #[auto_default(skip)]
+///     id: u32,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// Options on a field, such as `with = path`, still add its default value. Options on the item
+/// that change the added default values, such as `infer_literals`, can't be used with `check`
+///
+/// # Short default values
+///
+/// By default, fields get `::core::default::Default::default()`, which works even if
+/// another `Default` is in scope. Everything else the macros generate also uses full paths,
+/// so it works in modules with `#![no_implicit_prelude]`. `#[auto_default(short_default)]`
+/// uses `Default::default()` instead, which is easier to read in the output of `cargo expand`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(short_default)]
+/// struct User {
+///     age: u8,
+///     is_admin: bool,
+/// }
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// struct User {
+///     age: u8 = Default::default(),
+///     is_admin: bool = Default::default(),
+/// }
+/// ```
+///
+/// # Reset fields to their defaults
+///
+/// `#[auto_default(reset)]` generates a `reset_to_default` method, which sets every field to
+/// its default value, and a `reset_{field}` method for every field that has a default value:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(reset)]
+/// struct Config {
+///     verbose: bool,
+///     retries: u8 = 3,
+/// }
+/// # use auto_default::auto_default;
+///
+/// let mut config = Config { verbose: true, retries: 10 };
+///
+/// config.reset_retries();
+/// assert_eq!(config.retries, 3);
+///
+/// config.reset_to_default();
+/// assert!(!config.verbose);
+/// ```
+///
+/// Fields marked with `#[auto_default(group = "...")]` are reset together by a `reset_{group}`
+/// method, and by `reset_group`, which takes the name of the group:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// #[auto_default(reset)]
+/// struct Settings {
+///     #[auto_default(group = "network")]
+///     host: &'static str = "localhost",
+///     #[auto_default(group = "network")]
+///     port: u16 = 8080,
+///     #[auto_default(group = "appearance")]
+///     theme: &'static str = "dark",
+/// }
+/// # use auto_default::auto_default;
+///
+/// let mut settings = Settings { host: "example.com", port: 443, theme: "light" };
+///
+/// settings.reset_network();
+/// assert_eq!((settings.host, settings.port), ("localhost", 8080));
+///
+/// settings.reset_group("appearance");
+/// assert_eq!(settings.theme, "dark");
+/// ```
+///
+/// Every field must have a default value, and this is only supported on `struct`s
+///
+/// # Check if fields have their default values
+///
+/// `#[auto_default(is_default)]` generates an `is_default` method, which returns `true` if every
+/// field that has a default value is equal to it. Only these fields need to implement `PartialEq`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(is_default)]
+/// struct Config {
+///     verbose: bool,
+///     retries: u8 = 3,
+/// }
+/// # use auto_default::auto_default;
+///
+/// let mut config = Config { .. };
+/// assert!(config.is_default());
+///
+/// config.retries = 10;
+/// assert!(!config.is_default());
+/// ```
+///
+/// This is only supported on `struct`s
+///
+/// # List the fields that were changed
+///
+/// `#[auto_default(changed_fields)]` generates a `changed_fields` method, which returns
+/// the name of every field that is not equal to its default value, such as to only show
+/// the settings that were changed:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(changed_fields)]
+/// struct Config {
+///     verbose: bool,
+///     retries: u8 = 3,
+/// }
+/// # use auto_default::auto_default;
+///
+/// let mut config = Config { .. };
+/// config.retries = 10;
+/// assert_eq!(config.changed_fields().collect::<Vec<_>>(), ["retries"]);
+/// ```
+///
+/// Like `is_default`, only fields that have a default value are compared.
+/// This is only supported on `struct`s
+///
+/// # Change a few fields of the default value
+///
+/// `#[auto_default(with_fn)]` generates a `with` function, which creates the default value
+/// and passes it to a closure. Unlike `Config { .. }`, this can be used outside of the module
+/// even if the struct has private fields:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// mod config {
+///     # use auto_default::auto_default;
+///     #[auto_default(with_fn)]
+///     pub struct Config {
+///         pub verbose: bool,
+///         pub retries: u8 = 3,
+///         secret: u32,
+///     }
+/// }
+///
+/// let config = config::Config::with(|config| config.verbose = true);
+/// assert!(config.verbose);
+/// assert_eq!(config.retries, 3);
+/// ```
+///
+/// Every field must have a default value, and this is only supported on `struct`s
+///
+/// # Use the default values in `clap`
+///
+/// `#[auto_default(clap)]` adds `#[arg(default_value_t = ...)]` with the default value
+/// to every field that has one, so the defaults of a [`clap`](https://docs.rs/clap) argument
+/// struct are the same as the defaults of the struct itself:
+///
+/// ```rust,ignore
+/// #[auto_default(clap, infer_literals)]
+/// #[derive(clap::Parser)]
+/// struct Cli {
+///     #[arg(long)]
+///     retries: u8 = 3,
+///     #[arg(long)]
+///     verbose: bool,
+/// }
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust,ignore
+/// #[derive(clap::Parser)]
+/// struct Cli {
+///     #[arg(long)]
+///     #[arg(default_value_t = 3)]
+///     retries: u8 = 3,
+///     #[arg(long)]
+///     #[arg(default_value_t = false)]
+///     verbose: bool = false,
+/// }
+/// ```
+///
+/// Fields that already have a `default_value` in `#[arg(...)]` are kept as they are,
+/// and so are `#[command(flatten)]` and `#[command(subcommand)]` fields. `Option<T>` and
+/// `Vec<T>` fields are also kept as they are, because `clap` treats them as optional
+///
+/// # Add attributes to fields with a default value
+///
+/// `#[auto_default(annotate(...))]` adds the listed attributes to every field that has a
+/// default value. Skipped fields without a default value are kept as they are. This is useful
+/// for helper attributes of other derives, such as `#[serde(default)]`:
+///
+/// ```rust,ignore
+/// #[auto_default(annotate(serde(default)))]
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     retries: u8,
+///     #[auto_default(skip)]
+///     path: String,
+/// }
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust,ignore
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     #[serde(default)]
+///     retries: u8 = ::core::default::Default::default(),
+///     path: String,
+/// }
+/// ```
+///
+/// # Use the default values of `serde`
+///
+/// `#[auto_default(from_serde)]` reads the default value of a field from its `serde` attribute,
+/// so the defaults of deserialization are written once. A field with `#[serde(default = "path")]`
+/// gets `path()`, and a field with `#[serde(default)]` gets `Default::default()`.
+/// Other fields get their default value as usual:
+///
+/// ```rust,ignore
+/// #[auto_default(from_serde)]
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     #[serde(default = "default_port")]
+///     port: u16,
+///     retries: u8,
+/// }
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust,ignore
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     #[serde(default = "default_port")]
+///     port: u16 = default_port(),
+///     retries: u8 = ::core::default::Default::default(),
+/// }
+/// ```
+///
+/// Options on the field, such as `#[auto_default(with = path)]`, take precedence over
+/// the attribute of `serde`
+///
+/// # Use the default values in `serde`
+///
+/// `#[auto_default(serde_defaults)]` is the reverse of `from_serde`: the default field values
+/// are also used by `serde` when a field is missing. Every field whose default value is not
+/// `Default::default()` gets `#[serde(default = "...")]`, which calls a hidden function
+/// that returns the default value:
+///
+/// ```rust,ignore
+/// #[auto_default(serde_defaults)]
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     port: u16 = 8080,
+///     retries: u8,
+/// }
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust,ignore
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     #[serde(default = "Config::__port_field_default")]
+///     port: u16 = 8080,
+///     retries: u8 = ::core::default::Default::default(),
+/// }
+///
+/// impl Config {
+///     #[doc(hidden)]
+///     fn __port_field_default() -> u16 { 8080 }
+/// }
+/// ```
+///
+/// Fields that already have `#[serde(default)]` or `#[serde(default = "...")]` are kept as they are,
+/// and so are fields with `Default::default()`, which can be marked `#[serde(default)]` instead
+///
+/// # Migrate from `SmartDefault`, `Derivative` or `Educe`
+///
+/// The `Default` of these crates ignores the default field values, so the two can disagree.
+/// `#[derive(SmartDefault)]`, `#[derivative(Default)]` and `#[educe(Default)]` on an item marked
+/// `#[auto_default]` are an error. `#[auto_default(from_derives)]` turns their default values
+/// into default field values, and replaces their `Default` with `#[derive(Default)]`:
+///
+/// ```rust,ignore
+/// #[auto_default(from_derives)]
+/// #[derive(Debug, SmartDefault)]
+/// struct Config {
+///     #[default(8080)]
+///     port: u16,
+///     #[default(_code = "Duration::from_secs(30)")]
+///     timeout: Duration,
+///     verbose: bool,
+/// }
+/// ```
+///
+/// The above is transformed into this:
+///
+/// ```rust,ignore
+/// #[derive(Debug, Default)]
+/// struct Config {
+///     port: u16 = 8080,
+///     timeout: Duration = Duration::from_secs(30),
+///     verbose: bool = ::core::default::Default::default(),
+/// }
+/// ```
+///
+/// `#[derivative(Default(value = "expr"))]`, `#[educe(Default = expr)]` and
+/// `#[educe(Default(expression = expr))]` on fields are read the same way. A field that has
+/// both one of these attributes and a default field value is an error
+///
+/// Default field values must be `const`, so a value such as `_code = "vec![1]"` is an error.
+/// Move it to `#[auto_default(runtime = vec![1])]` on the field, and add `impl_default` to the item
+///
+/// # List default values at runtime
+///
+/// `#[auto_default(field_defaults)]` generates a `FIELD_DEFAULTS` constant, which contains the
+/// name of every field and its default value as a string. This is useful for generating
+/// documentation of configuration files, or the output of `--help`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// #[auto_default(field_defaults, infer_literals)]
+/// pub struct Config {
+///     pub retries: u8,
+///     pub theme: &'static str = "dark",
+///     #[auto_default(skip)]
+///     pub path: String,
+/// }
+///
+/// assert_eq!(
+///     Config::FIELD_DEFAULTS,
+///     [("retries", "0"), ("theme", "custom"), ("path", "required")]
+/// );
+/// # use auto_default::auto_default;
+/// ```
+///
+/// The default value is `"custom"` if it is written by hand, and `"required"` if the field
+/// has no default value. This is only supported on `struct`s
+///
+/// # Order of attributes
+///
+/// Attributes are applied from top to bottom, so `#[auto_default]` has to be placed above
+/// `#[derive(Default)]`. Otherwise, `Default` is derived before the default field values are added,
+/// and the derived implementation uses `Default::default()` for every field:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// #[auto_default(infer_literals)]
+/// #[derive(Default)]
+/// struct Config {
+///     retries: u8,
+///     verbose: bool = true,
+/// }
+/// ```
+///
+/// Attribute macros that can't parse default field values, such as `#[pin_project]`,
+/// `#[serde_as]` and `#[wasm_bindgen]`, have to be placed above `#[auto_default]`. An error is reported if they are
+/// placed below it. All other attributes are kept as they are, in the same order
+///
+/// # Test the default values
+///
+/// `#[auto_default(self_test)]` generates a `#[test]` which checks that the default field values
+/// create the same value as `Default::default()`. This catches a hand-written implementation
+/// of `Default` that was not updated after a field was added or changed:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// #[auto_default(self_test)]
+/// #[derive(Default, PartialEq)]
+/// struct Config {
+///     retries: u8,
+///     verbose: bool = true,
+/// }
+/// ```
+///
+/// The above generates this test:
+///
+/// ```rust,ignore
+/// #[cfg(test)]
+/// #[test]
+/// fn auto_default_self_test_config() {
+///     assert!(Config { .. } == <Config as Default>::default(), "...");
+/// }
+/// ```
+///
+/// On an `enum`, the variant marked `#[default]` is compared against `Default::default()`.
+/// Every field must have a default value, and the item can't be generic
+///
+/// `#[auto_default(validate_test)]` generates a `#[test]` which checks that the default value
+/// passes the validation of [`validator`](https://docs.rs/validator) or
+/// [`garde`](https://docs.rs/garde), so a default value can't break a constraint of the field:
+///
+/// ```rust,ignore
+/// use validator::Validate;
+///
+/// #[auto_default(validate_test)]
+/// #[derive(Validate)]
+/// struct Config {
+///     #[validate(range(min = 1, max = 10))]
+///     retries: u8 = 3,
+///     #[validate(length(min = 1))]
+///     name: String = String::from("app"),
+/// }
+/// ```
+///
+/// The test calls `Config { .. }.validate()` and panics with the error, so the `Validate` trait
+/// must be in scope. It has the same requirements as `self_test`
+///
+/// # Default values for C
+///
+/// `#[auto_default(extern_c)]` generates an `extern "C"` function which returns the default
+/// value of a `#[repr(C)]` struct. [`cbindgen`](https://docs.rs/cbindgen) writes it
+/// to the header, so C callers get the same default values as Rust:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// #[auto_default(extern_c)]
+/// #[repr(C)]
+/// pub struct Config {
+///     port: u16 = 8080,
+///     retries: u8,
+/// }
+/// ```
+///
+/// The above generates this function:
+///
+/// ```rust,ignore
+/// #[unsafe(no_mangle)]
+/// pub extern "C" fn config_default() -> Config {
+///     Config { .. }
+/// }
+/// ```
+///
+/// `extern_c = "config_new"` names the function `config_new` instead. The function has
+/// the visibility of the struct. It has the same requirements as `self_test`, and is not
+/// supported on enums
+///
+/// # Constructors for JavaScript
+///
+/// `#[auto_default(wasm)]` generates a [`wasm-bindgen`](https://docs.rs/wasm-bindgen)
+/// constructor which returns the default value, so JavaScript can create the struct
+/// with `new Config()`. `#[wasm_bindgen]` must be placed above `#[auto_default]`:
+///
+/// ```rust,ignore
+/// #[wasm_bindgen]
+/// #[auto_default(wasm(setters))]
+/// pub struct Config {
+///     port: u16 = 8080,
+///     #[auto_default(skip)]
+///     token: String,
+/// }
+/// ```
+///
+/// The above generates this constructor:
+///
+/// ```rust,ignore
+/// #[wasm_bindgen]
+/// impl Config {
+///     #[wasm_bindgen(constructor)]
+///     pub fn new() -> Config {
+///         Config { token: Default::default(), .. }
+///     }
+///
+///     #[wasm_bindgen(setter)]
+///     pub fn set_token(&mut self, token: String) {
+///         self.token = token;
+///     }
+/// }
+/// ```
+///
+/// Fields without a default value get `Default::default()`, or their value from `runtime = ...`.
+/// With `setters`, each of them gets a setter, so JavaScript can set them after creating
+/// the struct. Enums and generic structs are not supported
+///
+/// # Check that fields implement `Default`
+///
+/// `#[auto_default(assert_default)]` checks that the type of every field that gets
+/// `Default::default()` implements `Default`, in a `const _: () = { ... };` next to the item.
+/// The error points at the type of the field:
 ///
-/// Turns this:
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// #[auto_default(assert_default)]
+/// struct Config<T> {
+///     retries: u8,
+///     items: Vec<T>,
+/// }
+/// ```
+///
+/// The above generates this check:
+///
+/// ```rust,ignore
+/// const _: () = {
+///     fn assert_default<T: ::core::default::Default>() {}
+///     fn assert_fields<T>() {
+///         assert_default::<u8>();
+///         assert_default::<Vec<T>>();
+///     }
+/// };
+/// ```
+///
+/// # Implement `Default`
+///
+/// `#[auto_default(impl_default)]` implements `Default` next to the item, so that
+/// `#[derive(Default)]` is not needed. Fields without a default value, such as skipped fields,
+/// get `Default::default()`. Enums use the variant marked `#[default]`:
 ///
 /// ```rust
 /// # #![feature(default_field_values)]
 /// # #![feature(const_trait_impl)]
 /// # #![feature(const_default)]
-/// #[auto_default]
-/// struct User {
-///     age: u8,
-///     is_admin: bool = false
+/// # use auto_default::auto_default;
+/// #[auto_default(impl_default)]
+/// struct Cache<K, V> {
+///     entries: Vec<(K, V)>,
+///     #[auto_default(skip)]
+///     last: Option<V>,
+/// }
+/// ```
+///
+/// The above generates this implementation:
+///
+/// ```rust,ignore
+/// impl<K, V> ::core::default::Default for Cache<K, V>
+/// where
+///     Option<V>: ::core::default::Default,
+/// {
+///     fn default() -> Self {
+///         Cache { last: ::core::default::Default::default(), .. }
+///     }
+/// }
+/// ```
+///
+/// Unlike `#[derive(Default)]`, which requires `K: Default` and `V: Default`, only the types
+/// of fields without a default value that mention a generic parameter get a bound.
+/// `#[auto_default(impl_default, bound = "V: Clone")]` replaces the inferred bounds
+///
+/// Default field values must be `const`. `#[auto_default(runtime = expr)]` on a field gives it
+/// a value that is only used by the implementation of `Default`, so it can call functions that
+/// are not `const`. The field doesn't get a default field value, and using `runtime` without
+/// `impl_default` is an error:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// # use std::collections::HashMap;
+/// #[auto_default(impl_default)]
+/// struct Router {
+///     #[auto_default(runtime = HashMap::from([("/", "index.html")]))]
+///     routes: HashMap<&'static str, &'static str>,
+///     port: u16,
 /// }
+///
+/// assert_eq!(Router::default().routes["/"], "index.html");
+/// ```
+///
+/// `#[auto_default(value = expr)]` is like `runtime`, but `expr` can use the default values
+/// of the fields declared before it, by their names:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
 /// # use auto_default::auto_default;
+/// #[auto_default(impl_default)]
+/// struct Buffers {
+///     buffer_size: usize = 1024,
+///     #[auto_default(value = buffer_size * 2)]
+///     cache_size: usize,
+/// }
+///
+/// assert_eq!(Buffers::default().cache_size, 2048);
 /// ```
 ///
-/// Into this:
+/// The implementation of `Default` then binds every field with `let`, in the order of the fields
+///
+/// Fields such as `Box<dyn Trait>` can't get a default value in `const`, because it would need
+/// to be allocated. `#[auto_default(boxed = Type)]` is a shorthand for
+/// `runtime = Box::new(<Type as Default>::default())`, and works with `Box`, `Arc` and `Rc`:
 ///
 /// ```rust
 /// # #![feature(default_field_values)]
 /// # #![feature(const_trait_impl)]
 /// # #![feature(const_default)]
-/// struct User {
-///     age: u8 = Default::default(),
-///     is_admin: bool = false
+/// # use auto_default::auto_default;
+/// # trait Storage {}
+/// # #[derive(Default)]
+/// # struct MemoryStorage;
+/// # impl Storage for MemoryStorage {}
+/// #[auto_default(impl_default)]
+/// struct App {
+///     #[auto_default(boxed = MemoryStorage)]
+///     storage: Box<dyn Storage>,
+///     port: u16,
 /// }
 /// ```
 ///
-/// This macro applies to `struct`s with named fields, and enums.
+/// Values that are expensive to create, such as parsed templates or compiled regexes, can be
+/// created only once with `#[auto_default(lazy = expr)]`. The first call to `default()` keeps
+/// the value in a `static` [`OnceLock`](std::sync::OnceLock), and every call clones it:
 ///
-/// # Do not add `= Default::default()` field value to select fields
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// # use std::collections::HashMap;
+/// # fn build_template() -> HashMap<String, String> { HashMap::new() }
+/// #[auto_default(impl_default)]
+/// struct Renderer {
+///     #[auto_default(lazy = build_template())]
+///     template: HashMap<String, String>,
+///     indent: usize,
+/// }
+/// ```
 ///
-/// If you do not want a specific field to have a default, you can opt-out
-/// with `#[auto_default(skip)]`:
+/// The type of the field must implement `Clone`, `Send` and `Sync`, and can't use the generic
+/// parameters of the item, since a `static` can't
+///
+/// `#[auto_default(impl_default, finalize = path)]` calls `path(&mut value)` before
+/// `default()` returns, to compute fields that depend on the others, or check invariants:
 ///
 /// ```rust
 /// # #![feature(default_field_values)]
 /// # #![feature(const_trait_impl)]
 /// # #![feature(const_default)]
-/// #[auto_default]
-/// struct User {
+/// # use auto_default::auto_default;
+/// #[auto_default(impl_default, finalize = Self::update_len)]
+/// struct Name {
+///     text: String = String::new(),
 ///     #[auto_default(skip)]
-///     age: u8,
-///     is_admin: bool
+///     len: usize,
+/// }
+///
+/// impl Name {
+///     fn update_len(&mut self) {
+///         self.len = self.text.len();
+///     }
+/// }
+/// ```
+///
+/// `#[auto_default(first_variant_default)]` on an enum makes its first variant the default,
+/// as if it was marked `#[default]`. This helps code generators, which don't know the names
+/// of the variants. `Default` is implemented next to the enum, or with `#[derive(Default)]`
+/// on the enum, the first variant is marked `#[default]` instead:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default;
+/// #[auto_default(first_variant_default)]
+/// #[derive(PartialEq, Debug)]
+/// enum Shape {
+///     Circle { radius: f32 },
+///     Square { side: f32 },
+/// }
+///
+/// assert_eq!(Shape::default(), Shape::Circle { radius: 0.0 });
+/// ```
+///
+/// # `#[non_exhaustive]` structs
+///
+/// A `#[non_exhaustive]` struct can't have default field values, because other crates couldn't
+/// write `Foo { .. }` anyway. `#[auto_default]` removes the default field values from the struct,
+/// and implements `Default` with them instead, so other crates can call `Foo::default()`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # use auto_default::auto_default;
+/// #[auto_default]
+/// #[non_exhaustive]
+/// pub struct Config {
+///     pub port: u16 = 8080,
+///     pub name: String,
 /// }
+///
+/// let config = Config::default();
+/// assert_eq!(config.port, 8080);
+/// ```
+///
+/// Options that need `Foo { .. }`, such as `reset` or `self_test`, are not supported.
+/// If the struct has `#[derive(Default)]`, it is kept and a warning is reported,
+/// since it doesn't use the default values
+///
+/// # Default field values behind a cfg
+///
+/// Default values of fields such as `String` need the nightly `const_trait_impl` and
+/// `const_default` features. A library can make them optional with
+/// `#[auto_default(const_if = "...")]`, which emits the item twice:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
 /// # use auto_default::auto_default;
+/// #[auto_default(const_if = "feature = \"const-defaults\"")]
+/// pub struct Config {
+///     pub name: String,
+///     pub port: u16 = 8080,
+/// }
 /// ```
 ///
 /// The above is transformed into this:
@@ -144,659 +1524,376 @@ use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenSt
 /// # #![feature(default_field_values)]
 /// # #![feature(const_trait_impl)]
 /// # #![feature(const_default)]
-/// struct User {
-///     age: u8,
-///     is_admin: bool = Default::default()
+/// #[cfg(feature = "const-defaults")]
+/// pub struct Config {
+///     pub name: String = ::core::default::Default::default(),
+///     pub port: u16 = 8080,
+/// }
+///
+/// #[cfg(feature = "const-defaults")]
+/// impl ::core::default::Default for Config {
+///     fn default() -> Self {
+///         Config { .. }
+///     }
+/// }
+///
+/// #[cfg(not(feature = "const-defaults"))]
+/// pub struct Config {
+///     pub name: String,
+///     pub port: u16,
+/// }
+///
+/// #[cfg(not(feature = "const-defaults"))]
+/// impl ::core::default::Default for Config {
+///     fn default() -> Self {
+///         Config {
+///             name: ::core::default::Default::default(),
+///             port: 8080,
+///         }
+///     }
+/// }
+/// ```
+///
+/// `Default` is implemented in both versions, so it can't be used with `#[derive(Default)]`.
+/// Like with `#[non_exhaustive]` structs, options that need `Foo { .. }` are not supported.
+/// The features can be enabled with the same cfg:
+///
+/// ```rust
+/// #![feature(default_field_values)]
+/// #![cfg_attr(feature = "const-defaults", feature(const_trait_impl, const_default))]
+/// ```
+///
+/// # Crate-wide policy
+///
+/// An `auto_default.toml` file next to `Cargo.toml` applies to every `#[auto_default]`
+/// in the crate, without passing arguments to each of them:
+///
+/// ```toml
+/// # Fields of these types are skipped, as if they were marked `#[auto_default(skip)]`
+/// skip_types = ["RawFd", "Option<Handle>"]
+///
+/// # Redundant default values are errors, as with `#[auto_default(deny_redundant)]`
+/// strict = true
+///
+/// # Default values of fields with these types, instead of `Default::default()`
+/// [types]
+/// Duration = "Duration::ZERO"
+/// "Vec<u8>" = "Vec::new()"
+/// ```
+///
+/// A type with only a name, such as `Duration`, matches every type with that name, such as
+/// `std::time::Duration`. Other types must be written exactly as in the field. Options on the item
+/// or on the field take precedence, and `warn_redundant` turns the errors of `strict` into warnings.
+///
+/// The `AUTO_DEFAULT_CONFIG` environment variable points at another file,
+/// relative to the directory of `Cargo.toml`
+///
+/// # Migrate an existing crate
+///
+/// `cargo auto-default` rewrites structs with at least 2 fields that are `= Default::default()`
+/// to use `#[auto_default]`, keeping the rest of the source as it is. Fields without a default
+/// value are marked `#[auto_default(skip)]`, so the structs mean the same as before:
+///
+/// ```sh
+/// cargo install cargo-auto-default
+///
+/// # print the changes as a diff
+/// cargo auto-default --dry-run
+///
+/// # rewrite the files in `src`
+/// cargo auto-default
+/// ```
+///
+/// `--min-fields <N>` changes how many fields must be `= Default::default()`. The attribute is
+/// added as `#[auto_default::auto_default]`, so no imports are needed
+///
+/// # Stop using `auto-default`
+///
+/// `cargo auto-default --eject` replaces every item marked `#[auto_default]` with what it
+/// expands to, and removes the `use auto_default::...;` imports. After that, the crate no longer
+/// depends on `auto-default`:
+///
+/// ```sh
+/// # print the changes as a diff
+/// cargo auto-default --eject --dry-run
+///
+/// # rewrite the files in `src`
+/// cargo auto-default --eject
+/// ```
+///
+/// The expanded items are formatted again, so comments inside of them are removed
+///
+/// # Debugging
+///
+/// `#[auto_default(debug)]` prints the item that the macro expands to to stderr,
+/// during compilation. The item itself is not changed
+///
+/// To check whether an error or a slow build is caused by the macro, turn it off for a build:
+///
+/// ```sh
+/// RUSTFLAGS="--cfg auto_default_disabled" cargo build
+/// # or
+/// AUTO_DEFAULT_DISABLED=1 cargo build
+/// ```
+///
+/// Items are then emitted as they were written, without the `#[auto_default(...)]` attributes
+/// on their fields and variants, and `#[derive(AutoDefault)]` implements nothing
+///
+/// # Parse with `syn`
+///
+/// The item is parsed without any dependencies, which keeps compile times low. This parser
+/// doesn't understand all syntax. For example, it can't tell that `$vis` is the visibility
+/// of the struct if the struct is generated by a `macro_rules!` macro:
+///
+/// ```rust,ignore
+/// macro_rules! config {
+///     ($vis:vis $name:ident { $($field:ident: $ty:ty),* }) => {
+///         #[auto_default]
+///         $vis struct $name {
+///             $($field: $ty),*
+///         }
+///     };
 /// }
 /// ```
+///
+/// The `full-parse` feature parses the visibility, generics, field types and attributes
+/// with [`syn`](https://docs.rs/syn) instead, which understands all of them:
+///
+/// ```toml
+/// auto-default = { version = "0.2", features = ["full-parse"] }
+/// ```
+///
+/// # Error codes
+///
+/// Every error and warning reported by the macros starts with a code, such as `[AD002]`:
+///
+/// | Code    | Meaning                                                                                    |
+/// | ------- | ------------------------------------------------------------------------------------------ |
+/// | `AD001` | An argument is not known, or it is written incorrectly                                     |
+/// | `AD002` | The same argument is passed more than once                                                 |
+/// | `AD003` | Arguments that can't be used together, such as `skip` and `with = path`                    |
+/// | `AD004` | An argument or an attribute is not allowed where it is written, such as `skip` on the item |
+/// | `AD005` | An argument does nothing, such as `skip` on a field with a default value                   |
+/// | `AD006` | The item is not a `struct` with named fields or an `enum`, or its tokens can't be parsed   |
+/// | `AD007` | The input of [`construct!`] is not a struct expression                                     |
+/// | `AD008` | A default value is written by hand, but the macro would add the same one                   |
+/// | `AD009` | The crate doesn't enable the nightly features that the default field values need           |
+/// | `AD010` | A field has no default value, with `#[auto_default(check)]`                                |
+/// | `AD011` | An option or attribute can't be used on a `#[non_exhaustive]` struct                       |
+/// | `AD012` | A default value calls something that is not `const`, such as `vec!`                        |
 #[proc_macro_attribute]
 pub fn auto_default(args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut compile_errors = TokenStream::new();
-
-    if !args.is_empty() {
-        compile_errors.extend(create_compile_error!(
-            args.into_iter().next(),
-            "no arguments expected",
-        ));
-    }
-
-    // Input supplied by the user. All tokens from here will
-    // get sent back to `output`
-    let mut source = input.into_iter().peekable();
-
-    // We collect all tokens into here and then return this
-    let mut sink = TokenStream::new();
-
-    stream_attrs(
-        &mut source,
-        &mut sink,
-        &mut compile_errors,
-        // no skip allowed on the container, would make no sense
-        // (just don't use the `#[auto_default]` at all at that point!)
-        IsSkipAllowed(false),
-    );
-    stream_vis(&mut source, &mut sink);
-
-    // pub(in crate) struct Foo
-    //               ^^^^^^
-    let item_kind = match source.next() {
-        Some(TokenTree::Ident(kw)) if kw.to_string() == "struct" => {
-            sink.extend([kw]);
-            ItemKind::Struct
-        }
-        Some(TokenTree::Ident(kw)) if kw.to_string() == "enum" => {
-            sink.extend([kw]);
-            ItemKind::Enum
-        }
-        tt => {
-            compile_errors.extend(create_compile_error!(
-                tt,
-                "expected a `struct` or an `enum`"
-            ));
-            return compile_errors;
-        }
-    };
-
-    // struct Foo
-    //        ^^^
-    let item_ident_span = stream_ident(&mut source, &mut sink)
-        .expect("`struct` or `enum` keyword is always followed by an identifier");
-
-    // Generics
-    //
-    // struct Foo<Bar, Baz: Trait> where Baz: Quux { ... }
-    //           ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
-    let source_item_fields = loop {
-        match source.next() {
-            // Fields of the struct
-            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => break group,
-            // This token is part of the generics of the struct
-            Some(tt) => sink.extend([tt]),
-            // reached end of input
-            None => {
-                // note: if enum, this is unreachable because `enum Foo` is invalid (requires `{}`),
-                // whilst `struct Foo;` is completely valid
-                compile_errors.extend(CompileError::new(
-                    item_ident_span,
-                    "expected struct with named fields",
-                ));
-                return compile_errors;
-            }
-        }
-    };
-
-    match item_kind {
-        ItemKind::Struct => {
-            sink.extend([add_default_field_values(
-                source_item_fields,
-                &mut compile_errors,
-                // none of the fields are considered to be skipped initially
-                IsSkip(false),
-            )]);
-        }
-        ItemKind::Enum => {
-            let mut source_variants = source_item_fields.stream().into_iter().peekable();
-            let mut sink_variants = TokenStream::new();
-
-            loop {
-                // if this variant is marked #[auto_default(skip)]
-                let is_skip = stream_attrs(
-                    &mut source_variants,
-                    &mut sink_variants,
-                    &mut compile_errors,
-                    // can skip the variant, which removes auto-default for all
-                    // fields
-                    IsSkipAllowed(true),
-                );
-
-                // variants technically can have visibility, at least on a syntactic level
-                //
-                // pub Variant {  }
-                // ^^^
-                stream_vis(&mut source_variants, &mut sink_variants);
-
-                // Variant {  }
-                // ^^^^^^^
-                let Some(variant_ident_span) =
-                    stream_ident(&mut source_variants, &mut sink_variants)
-                else {
-                    // that means we have an enum with no variants, e.g.:
-                    //
-                    // enum Never {}
-                    //
-                    // When we parse the variants, there won't be an identifier
-                    break;
-                };
-
-                // only variants with named fields can be marked `#[auto_default(skip)]`
-                let mut disallow_skip = || {
-                    if is_skip.0 {
-                        compile_errors.extend(CompileError::new(
-                            variant_ident_span,
-                            concat!(
-                                "`#[auto_default(skip)]` is",
-                                " only allowed on variants with named fields"
-                            ),
-                        ));
-                    }
-                };
-
-                match source_variants.peek() {
-                    // Enum variant with named fields. Add default field values.
-                    Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
-                        let Some(TokenTree::Group(named_variant_fields)) = source_variants.next()
-                        else {
-                            unreachable!()
-                        };
-                        sink_variants.extend([add_default_field_values(
-                            named_variant_fields,
-                            &mut compile_errors,
-                            is_skip,
-                        )]);
-
-                        stream_enum_variant_discriminant_and_comma(
-                            &mut source_variants,
-                            &mut sink_variants,
-                        );
-                    }
-                    // Enum variant with unnamed fields.
-                    Some(TokenTree::Group(group))
-                        if group.delimiter() == Delimiter::Parenthesis =>
-                    {
-                        disallow_skip();
-                        let Some(TokenTree::Group(unnamed_variant_fields)) = source_variants.next()
-                        else {
-                            unreachable!()
-                        };
-                        sink_variants.extend([unnamed_variant_fields]);
-
-                        stream_enum_variant_discriminant_and_comma(
-                            &mut source_variants,
-                            &mut sink_variants,
-                        );
-                    }
-                    // This was a unit variant. Next variant may exist,
-                    // if it does it is parsed on next iteration
-                    Some(TokenTree::Punct(punct))
-                        if punct.as_char() == ',' || punct.as_char() == '=' =>
-                    {
-                        disallow_skip();
-                        stream_enum_variant_discriminant_and_comma(
-                            &mut source_variants,
-                            &mut sink_variants,
-                        );
-                    }
-                    // Unit variant, with no comma at the end. This is the last variant
-                    None => {
-                        disallow_skip();
-                        break;
-                    }
-                    Some(_) => unreachable!(),
-                }
-            }
-
-            let mut sink_variants = Group::new(source_item_fields.delimiter(), sink_variants);
-            sink_variants.set_span(source_item_fields.span());
-            sink.extend([sink_variants]);
-        }
-    }
-
-    sink.extend(compile_errors);
-
-    sink
+    auto_default_with_args(args, input, false, Vec::new())
 }
 
-struct IsSkip(bool);
-struct IsSkipAllowed(bool);
-
-/// Streams enum variant discriminant + comma at the end from `source` into `sink`
-///
-/// enum Example {
-///     Three,
-///          ^
-///     Two(u32) = 2,
-///             ^^^^^
-///     Four { hello: u32 } = 4,
-///                        ^^^^^
-/// }
-fn stream_enum_variant_discriminant_and_comma(source: &mut Source, sink: &mut Sink) {
-    match source.next() {
-        // No discriminant, there may be another variant after this
-        Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {
-            sink.extend([punct]);
-        }
-        // No discriminant, this is the final enum variant
-        None => {}
-        // Enum variant has a discriminant
-        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
-            sink.extend([punct]);
-
-            // Stream discriminant expression from `source` into `sink`
-            loop {
-                match source.next() {
-                    // End of discriminant, there may be a variant after this
-                    Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {
-                        sink.extend([punct]);
-                        break;
-                    }
-                    // This token is part of the variant's expression
-                    Some(tt) => {
-                        sink.extend([tt]);
-                    }
-                    // End of discriminant, this is the last variant
-                    None => break,
-                }
-            }
-        }
-        Some(_) => unreachable!(),
-    }
+/// The same as [`#[auto_default]`](macro@auto_default), but as a function-like macro
+///
+/// This is useful where attribute macros can't be applied, such as to items generated
+/// by a `macro_rules!` macro. Arguments of the macro are passed in an `#[auto_default(...)]`
+/// attribute on the item:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # #![feature(const_trait_impl)]
+/// # #![feature(const_default)]
+/// # use auto_default::auto_default_item;
+/// auto_default_item! {
+///     #[auto_default(infer_literals)]
+///     pub struct User {
+///         age: u8,
+///         #[auto_default(skip)]
+///         is_admin: bool,
+///     }
+/// }
+/// ```
+///
+/// It can't be called `auto_default!`, because function-like and attribute macros share a namespace
+#[proc_macro]
+pub fn auto_default_item(input: TokenStream) -> TokenStream {
+    let (args, item) = take_container_args(input);
+    auto_default_with_args(args.unwrap_or_default(), item, false, Vec::new())
 }
 
-type Source = Peekable<proc_macro::token_stream::IntoIter>;
-type Sink = TokenStream;
-
-/// Streams the identifier from `input` into `output`, returning its span, if the identifier exists
-fn stream_ident(source: &mut Source, sink: &mut Sink) -> Option<Span> {
-    let ident = source.next()?;
-    let span = ident.span();
-    sink.extend([ident]);
-    Some(span)
+/// Implements `Default` like [`#[auto_default(impl_default)]`](macro@auto_default),
+/// without changing the item
+///
+/// Fields with a default field value use it, and the other fields use `Default::default()`.
+/// Arguments of the macro are passed in an `#[auto_default(...)]` attribute on the item,
+/// and the options of fields work the same as with `#[auto_default]`:
+///
+/// ```rust
+/// # #![feature(default_field_values)]
+/// # use auto_default::AutoDefault;
+/// #[derive(AutoDefault)]
+/// #[auto_default(infer_literals)]
+/// struct User {
+///     age: u8,
+///     name: &'static str = "ferris",
+///     #[auto_default(runtime = vec![1, 2])]
+///     ids: Vec<u32>,
+/// }
+///
+/// let user = User::default();
+/// assert_eq!(user.age, 0);
+/// assert_eq!(user.name, "ferris");
+/// assert_eq!(user.ids, [1, 2]);
+/// ```
+///
+/// This is useful when the fields can't have the default field values that
+/// `#[auto_default]` adds, such as when `const_default` isn't enabled.
+/// Options that change the item, such as `doc_defaults` or `reset`, are not supported.
+///
+/// Helper attributes of the derive can't be told apart from the attribute macro, so
+/// [`#[auto_default]`](macro@auto_default) can't be imported in the same module.
+/// It can still be used as `#[auto_default::auto_default]`
+#[proc_macro_derive(AutoDefault, attributes(auto_default, default))]
+pub fn derive_auto_default(input: TokenStream) -> TokenStream {
+    let (args, item) = take_container_args(input);
+    auto_default_with_args(args.unwrap_or_default(), item, true, Vec::new())
 }
 
-// Parses attributes
-//
-// #[attr] #[attr] pub field: Type
-// #[attr] #[attr] struct Foo
-// #[attr] #[attr] enum Foo
-//
-// Returns `true` if `#[auto_default(skip)]` was encountered
-fn stream_attrs(
-    source: &mut Source,
-    sink: &mut Sink,
-    errors: &mut TokenStream,
-    is_skip_allowed: IsSkipAllowed,
-) -> IsSkip {
-    let mut is_skip = None;
-
-    let is_skip = loop {
-        if !matches!(source.peek(), Some(TokenTree::Punct(hash)) if *hash == '#') {
-            break is_skip;
-        };
-
-        // #[some_attr]
-        // ^
-        let pound = source.next();
-
-        // #[some_attr]
-        //  ^^^^^^^^^^^
-        let Some(TokenTree::Group(attr)) = source.next() else {
-            unreachable!()
+/// Expansion of `#[auto_default(args)]` applied to `input`
+///
+/// With `derive`, only the implementation of `Default` is returned
+fn auto_default_with_args(
+    args: TokenStream,
+    input: TokenStream,
+    derive: bool,
+    mut compile_errors: Vec<CompileError>,
+) -> TokenStream {
+    // Items that tell `cargo` what the expansion read, on stable
+    let mut tracked = TokenStream::new();
+
+    // --cfg auto_default_disabled
+    if disabled::disabled(&mut tracked) {
+        let mut item = if derive {
+            TokenStream::new()
+        } else {
+            expand::strip_helper_attrs(input, &Args::parse(args, &mut Vec::new()))
         };
-
-        // #[some_attr = hello]
-        //   ^^^^^^^^^^^^^^^^^
-        let mut attr_tokens = attr.stream().into_iter().peekable();
-
-        // Check if this attribute is `#[auto_default(skip)]`
-        if let Some(skip_span) = is_skip_attribute(&mut attr_tokens, errors) {
-            if is_skip.is_some() {
-                // Disallow 2 attributes on a single field:
-                //
-                // #[auto_default(skip)]
-                // #[auto_default(skip)]
-                errors.extend(CompileError::new(
-                    skip_span,
-                    "duplicate `#[auto_default(skip)]`",
-                ));
-            } else {
-                is_skip = Some(skip_span);
-            }
-            continue;
-        }
-
-        // #[attr]
-        // ^
-        sink.extend(pound);
-
-        // Re-construct the `[..]` for the attribute
-        //
-        // #[attr]
-        //  ^^^^^^
-        let mut group = Group::new(attr.delimiter(), attr_tokens.collect());
-        group.set_span(attr.span());
-
-        // #[attr]
-        //  ^^^^^^
-        sink.extend([group]);
-    };
-
-    if let Some(skip_span) = is_skip
-        && !is_skip_allowed.0
-    {
-        errors.extend(CompileError::new(
-            skip_span,
-            "`#[auto_default(skip)]` is not allowed on container",
-        ));
+        item.extend(tracked);
+        return item;
     }
 
-    IsSkip(is_skip.is_some())
-}
-
-/// if `source` is exactly `auto_default(skip)`, returns `Some(span)`
-/// with `span` being the `Span` of the `skip` identifier
-fn is_skip_attribute(source: &mut Source, errors: &mut TokenStream) -> Option<Span> {
-    let Some(TokenTree::Ident(ident)) = source.peek() else {
-        return None;
-    };
-
-    if ident.to_string() != "auto_default" {
-        return None;
-    };
-
-    // #[auto_default(skip)]
-    //   ^^^^^^^^^^^^
-    let ident = source.next().unwrap();
-
-    // We know it is `#[auto_default ???]`, we need to validate that `???`
-    // is exactly `(skip)` now
-
-    // #[auto_default(skip)]
-    //   ^^^^^^^^^^^^
-    let auto_default_span = ident.span();
-
-    // #[auto_default(skip)]
-    //               ^^^^^^
-    let group = match source.next() {
-        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => group,
-        Some(tt) => {
-            errors.extend(CompileError::new(tt.span(), "expected `(skip)`"));
-            return None;
-        }
-        None => {
-            errors.extend(CompileError::new(
-                auto_default_span,
-                "expected `(skip)` after this",
-            ));
-            return None;
-        }
-    };
-
-    // #[auto_default(skip)]
-    //                ^^^^
-    let mut inside = group.stream().into_iter();
-
-    // #[auto_default(skip)]
-    //                ^^^^
-    let ident_skip = match inside.next() {
-        Some(TokenTree::Ident(ident)) => ident,
-        Some(tt) => {
-            errors.extend(CompileError::new(tt.span(), "expected `skip`"));
-            return None;
-        }
-        None => {
-            errors.extend(CompileError::new(
-                group.span(),
-                "expected `(skip)`, found `()`",
-            ));
-            return None;
-        }
+    let mut args = if derive {
+        Args::parse_derive(args, &mut compile_errors)
+    } else {
+        Args::parse(args, &mut compile_errors)
     };
+    args.set_policy(policy(&mut compile_errors, &mut tracked));
 
-    if ident_skip.to_string() != "skip" {
-        errors.extend(CompileError::new(ident_skip.span(), "expected `skip`"));
-        return None;
+    let mut expansion = expand(input, &args, &mut compile_errors);
+    // The item of a derive keeps the default field values it was written with
+    if !derive {
+        expansion.item = features::check(expansion.item, &mut compile_errors);
     }
 
-    // Validate that there's nothing after `skip`
-    //
-    // #[auto_default(skip    )]
-    //                    ^^^^
-    if let Some(tt) = inside.next() {
-        errors.extend(CompileError::new(tt.span(), "unexpected token"));
-        return None;
+    if args.debug {
+        eprintln!(
+            "#[auto_default(debug)] expanded to:\n{}",
+            pretty::item_to_string(expansion.item.clone())
+        );
     }
 
-    Some(ident_skip.span())
+    let mut item = expansion.item;
+    item.extend(report(expansion.noop, expansion.warnings, compile_errors));
+    item.extend(tracked);
+    item
 }
 
-fn stream_vis(source: &mut Source, sink: &mut Sink) {
-    // Remove visibility if it is present
-    //
-    // pub(in crate) struct
-    // ^^^^^^^^^^^^^
-    if let Some(TokenTree::Ident(vis)) = source.peek()
-        && vis.to_string() == "pub"
-    {
-        // pub(in crate) struct
-        // ^^^
-        sink.extend(source.next());
+/// Policy of the crate that is being compiled, or `None` if it has no policy file
+fn policy(errors: &mut Vec<CompileError>, tracked: &mut TokenStream) -> Option<Policy> {
+    // `cargo` sets this for `rustc`, and proc macros run inside of it
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")?;
 
-        if let Some(TokenTree::Group(group)) = source.peek()
-            && let Delimiter::Parenthesis = group.delimiter()
-        {
-            // pub(in crate) struct
-            //    ^^^^^^^^^^
-            sink.extend(source.next());
-        }
-    };
-}
+    // `cargo` only compiles the crate again if it knows that the variable was read,
+    // and that the file was used
+    let config = tracked::env_var(policy::ENV_VAR, tracked);
+    let path = policy::path(Path::new(&manifest_dir), config.as_deref().map(OsStr::new))?;
+    tracked::path(&path, tracked);
 
-#[derive(PartialEq)]
-enum ItemKind {
-    Struct,
-    Enum,
+    Policy::load(&path, errors)
 }
 
-/// `fields` is [`StructFields`] in the grammar.
-///
-/// It is the curly braces, and everything within, for a struct with named fields,
-/// or an enum variant with named fields.
-///
-/// These fields are transformed by adding `= Default::default()` to every
-/// field that doesn't already have a default value.
-///
-/// If a field is marked with `#[auto_default(skip)]`, no default value will be
-/// added
-///
-/// [`StructFields`]: https://doc.rust-lang.org/reference/items/structs.html#grammar-StructFields
-fn add_default_field_values(
-    fields: Group,
-    compile_errors: &mut TokenStream,
-    is_skip_variant: IsSkip,
-) -> Group {
-    // All the tokens corresponding to the struct's field, passed by the user
-    // These tokens will eventually all be sent to `output_fields`,
-    // plus a few extra for any `Default::default()` that we output
-    let mut input_fields = fields.stream().into_iter().peekable();
-
-    // The tokens corresponding to the fields of the output struct
-    let mut output_fields = TokenStream::new();
-
-    // Parses all fields.
-    // Each iteration parses a single field
-    'parse_field: loop {
-        let is_skip_field = stream_attrs(
-            &mut input_fields,
-            &mut output_fields,
-            compile_errors,
-            IsSkipAllowed(true),
-        );
-        let is_skip = is_skip_field.0 || is_skip_variant.0;
-        stream_vis(&mut input_fields, &mut output_fields);
-        let Some(field_ident_span) = stream_ident(&mut input_fields, &mut output_fields) else {
-            // No fields. e.g.: `struct Struct {}`
-            break;
-        };
-
-        // field: Type
-        //      ^
-        output_fields.extend(input_fields.next());
-
-        // Everything after the `:` in the field
-        //
-        // Involves:
-        //
-        // - Adding default value of `= Default::default()` if one is not present
-        // - Continue to next iteration of the loop
-        loop {
-            match input_fields.peek() {
-                // This field has a custom default field value
-                //
-                // field: Type = default
-                //             ^
-                Some(TokenTree::Punct(p)) if p.as_char() == '=' => {
-                    if is_skip {
-                        compile_errors.extend(CompileError::new(
-                            field_ident_span,
-                            concat!(
-                                "this field is marked `#[auto_default(skip)]`,",
-                                " which does nothing since this field has a",
-                                " default value: `= ...`\n",
-                                "the attribute `#[auto_default(skip)]` can be removed"
-                            ),
-                        ));
-                    }
+/// Reports the warnings and errors of the expansion, returning tokens to add to it
+///
+/// `noop` is the reason why `#[auto_default]` did nothing, if it did nothing
+#[cfg(auto_default_nightly)]
+fn report(
+    noop: Option<&str>,
+    warnings: Vec<CompileError>,
+    errors: Vec<CompileError>,
+) -> TokenStream {
+    if let Some(reason) = noop {
+        Diagnostic::spanned(Span::call_site(), Level::Warning, reason)
+            .help("remove the `#[auto_default]` attribute")
+            .emit();
+    }
 
-                    loop {
-                        match input_fields.next() {
-                            Some(TokenTree::Punct(p)) if p == ',' => {
-                                output_fields.extend([p]);
-                                // Comma after field. Field is finished.
-                                continue 'parse_field;
-                            }
-                            Some(tt) => output_fields.extend([tt]),
-                            // End of input. Field is finished. This is the last field
-                            None => break 'parse_field,
-                        }
-                    }
-                }
-                // Reached end of field, has comma at the end, no custom default value
-                //
-                // field: Type,
-                //            ^
-                Some(TokenTree::Punct(p)) if p.as_char() == ',' => {
-                    // Insert default value before the comma
-                    //
-                    // field: Type = Default::default(),
-                    //             ^^^^^^^^^^^^^^^^^^^^
-                    if !is_skip {
-                        output_fields.extend(default(field_ident_span));
-                    }
-                    // field: Type = Default::default(),
-                    //                                 ^
-                    output_fields.extend(input_fields.next());
-                    // Next iteration handles the next field
-                    continue 'parse_field;
-                }
-                // This token is part of the field's type
-                //
-                // field: some::Type
-                //              ^^^^
-                Some(_) => output_fields.extend(input_fields.next()),
-                // Reached end of input, and it has no comma.
-                // This is the last field.
-                //
-                // struct Foo {
-                //     field: Type
-                //                ^
-                // }
-                None => {
-                    if !is_skip {
-                        output_fields.extend(default(field_ident_span));
-                    }
-                    // No more fields
-                    break 'parse_field;
-                }
-            }
-        }
+    for warning in warnings {
+        emit(warning, Level::Warning);
     }
-    let mut g = Group::new(Delimiter::Brace, output_fields);
-    g.set_span(fields.span());
-    g
-}
 
-// = ::core::default::Default::default()
-fn default(span: Span) -> [TokenTree; 14] {
-    [
-        TokenTree::Punct(Punct::new('=', Spacing::Alone)),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Ident(Ident::new("core", span)),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Ident(Ident::new("default", span)),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Ident(Ident::new("Default", span)),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(span),
-        TokenTree::Ident(Ident::new("default", span)),
-        TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new())).with_span(span),
-    ]
-}
+    for error in errors {
+        emit(error, Level::Error);
+    }
 
-macro_rules! create_compile_error {
-    ($spanned:expr, $($tt:tt)*) => {{
-        let span = if let Some(spanned) = $spanned {
-            spanned.span()
-        } else {
-            Span::call_site()
-        };
-        CompileError::new(span, format!($($tt)*))
-    }};
+    TokenStream::new()
 }
-use create_compile_error;
 
-/// `.into_iter()` generates `compile_error!($message)` at `$span`
-struct CompileError {
-    /// Where the compile error is generates
-    pub span: Span,
-    /// Message of the compile error
-    pub message: String,
+/// Reports the errors of the expansion with `compile_error!`, returning the tokens to add to it
+///
+/// Warnings can only be reported on nightly
+#[cfg(not(auto_default_nightly))]
+fn report(
+    _noop: Option<&str>,
+    _warnings: Vec<CompileError>,
+    errors: Vec<CompileError>,
+) -> TokenStream {
+    errors.into_iter().flatten().collect()
 }
 
-impl CompileError {
-    /// Create a new compile error
-    pub fn new(span: Span, message: impl AsRef<str>) -> Self {
-        Self {
-            span,
-            message: message.as_ref().to_string(),
-        }
+/// Reports `error` as an error or a warning, pointing at every span of the error
+#[cfg(auto_default_nightly)]
+fn emit(error: CompileError, level: Level) {
+    let mut diagnostic = Diagnostic::spanned(error.span, level, error.title());
+    for note in error.notes {
+        diagnostic = diagnostic.span_note(note.span, note.message);
     }
-}
-
-impl IntoIterator for CompileError {
-    type Item = TokenTree;
-    type IntoIter = std::array::IntoIter<Self::Item, 8>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        [
-            TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(self.span),
-            TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(self.span),
-            TokenTree::Ident(Ident::new("core", self.span)),
-            TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(self.span),
-            TokenTree::Punct(Punct::new(':', Spacing::Joint)).with_span(self.span),
-            TokenTree::Ident(Ident::new("compile_error", self.span)),
-            TokenTree::Punct(Punct::new('!', Spacing::Alone)).with_span(self.span),
-            TokenTree::Group(Group::new(Delimiter::Brace, {
-                TokenStream::from(
-                    TokenTree::Literal(Literal::string(&self.message)).with_span(self.span),
-                )
-            }))
-            .with_span(self.span),
-        ]
-        .into_iter()
+    if let Some(help) = error.help {
+        diagnostic = diagnostic.help(help);
     }
+    diagnostic.emit();
 }
 
-trait TokenTreeExt {
-    /// Set span of `TokenTree` without needing to create a new binding
-    fn with_span(self, span: Span) -> TokenTree;
-}
-
-impl TokenTreeExt for TokenTree {
-    fn with_span(mut self, span: Span) -> TokenTree {
-        self.set_span(span);
-        self
-    }
+/// Struct expression that fills the remaining fields with `Default::default()`
+///
+/// ```rust
+/// # use auto_default::construct;
+/// #[derive(Default)]
+/// struct User {
+///     age: u8,
+///     is_admin: bool,
+/// }
+///
+/// let user = construct!(User { age: 4, .. });
+/// ```
+///
+/// The above expands to:
+///
+/// ```rust
+/// # #[derive(Default)]
+/// # struct User {
+/// #     age: u8,
+/// #     is_admin: bool,
+/// # }
+/// let user = User { age: 4, ..::core::default::Default::default() };
+/// ```
+///
+/// This does not require the `default_field_values` feature, so it can be used on stable Rust.
+/// A library that supports both stable and nightly can use `#[auto_default]` on nightly,
+/// and callers on stable construct its structs with `construct!`
+#[proc_macro]
+pub fn construct(input: TokenStream) -> TokenStream {
+    construct::construct(input)
 }
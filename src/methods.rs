@@ -0,0 +1,532 @@
+//! Methods that are generated for the struct, in an `impl` block next to it
+//!
+//! ```rust,ignore
+//! #[auto_default(reset)]
+//! struct Foo {
+//!     a: u32,
+//! }
+//!
+//! impl Foo {
+//!     pub fn reset_to_default(&mut self) { ... }
+//!     pub fn reset_a(&mut self) { ... }
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! #[auto_default(reset)]
+//! struct Foo {
+//!     #[auto_default(group = "network")]
+//!     a: u32,
+//! }
+//!
+//! impl Foo {
+//!     pub fn reset_network(&mut self) { ... }
+//!     pub fn reset_group(&mut self, group: &str) { ... }
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! #[auto_default(is_default)]
+//! struct Foo {
+//!     a: u32,
+//! }
+//!
+//! impl Foo {
+//!     pub fn is_default(&self) -> bool { ... }
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! #[auto_default(changed_fields)]
+//! struct Foo {
+//!     a: u32,
+//! }
+//!
+//! impl Foo {
+//!     pub fn changed_fields(&self) -> impl Iterator<Item = &'static str> { ... }
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! #[auto_default(serde_defaults)]
+//! struct Foo {
+//!     #[serde(default = "Foo::__port_field_default")]
+//!     port: u16 = 8080,
+//! }
+//!
+//! impl Foo {
+//!     fn __port_field_default() -> u16 { 8080 }
+//! }
+//! ```
+use proc_macro::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+
+use crate::args::Args;
+use crate::expand::{Field, IdentExt};
+use crate::generics::Generics;
+use crate::impl_default::{mentions, type_params};
+use crate::pretty;
+use crate::tokens::{braced, doc, parenthesized, tokens};
+
+/// `impl` block with all methods enabled by `args`, or nothing if no methods are enabled
+pub fn methods(ident: &Ident, generics: &Generics, fields: &[Field], args: &Args) -> TokenStream {
+    let mut methods = TokenStream::new();
+
+    if args.reset {
+        methods.extend(reset(fields));
+    }
+
+    if args.is_default {
+        methods.extend(is_default(generics, fields));
+    }
+
+    if args.changed_fields {
+        methods.extend(changed_fields(generics, fields));
+    }
+
+    if args.with_fn {
+        methods.extend(with_fn());
+    }
+
+    if args.field_defaults {
+        methods.extend(field_defaults(fields));
+    }
+
+    if args.serde_defaults {
+        methods.extend(serde_defaults(fields));
+    }
+
+    if methods.is_empty() {
+        return methods;
+    }
+
+    tokens![generics.impl_header(ident), braced(methods)]
+}
+
+/// `reset_to_default`, a `reset_{field}` for every field with a default value,
+/// and a `reset_{group}` for every group of fields
+fn reset(fields: &[Field]) -> TokenStream {
+    let mut methods = tokens![
+        doc("Resets every field to its default value"),
+        "pub fn reset_to_default(&mut self) { *self = Self { .. }; }"
+    ];
+
+    for field in fields {
+        let Some(default) = &field.default else {
+            continue;
+        };
+
+        let name = unraw(&field.ident);
+        let method = Ident::new(&format!("reset_{name}"), field.ident.span());
+
+        methods.extend(tokens![
+            &field.cfg,
+            doc(&format!("Resets `{name}` to its default value")),
+            "pub fn",
+            &method,
+            "(&mut self)",
+            braced(tokens!["self .", &field.ident, "=", default, ";"]),
+        ]);
+    }
+
+    // #[auto_default(group = "network")]
+    let groups = groups(fields);
+    if groups.is_empty() {
+        return methods;
+    }
+
+    // match group { "network" => self.reset_network(), _ => {} }
+    let mut arms = TokenStream::new();
+    for (group, group_fields) in &groups {
+        // Reported by `expand`
+        if group_conflict(fields, group).is_some() {
+            continue;
+        }
+        let method = Ident::new(&format!("reset_{group}"), group.span());
+
+        // Fields without a default value are reported by `expand`
+        let mut body = TokenStream::new();
+        for field in group_fields {
+            if let Some(default) = &field.default {
+                body.extend(tokens![
+                    &field.cfg,
+                    braced(tokens!["self .", &field.ident, "=", default, ";"])
+                ]);
+            }
+        }
+
+        methods.extend(tokens![
+            doc(&format!(
+                "Resets the fields in the `{group}` group to their default values"
+            )),
+            "pub fn",
+            &method,
+            "(&mut self)",
+            braced(body),
+        ]);
+        arms.extend(tokens![
+            TokenTree::Literal(Literal::string(&group.to_string())),
+            "=> self .",
+            &method,
+            "(),"
+        ]);
+    }
+
+    methods.extend(tokens![
+        doc("Resets the fields in the group named `group` to their default values"),
+        doc(""),
+        doc("Does nothing if there is no such group"),
+        "pub fn reset_group(&mut self, group: &str)",
+        braced(tokens!["match group", braced(tokens![arms, "_ => {}"])]),
+    ]);
+
+    methods
+}
+
+/// Another method with the same name as `reset_{group}`
+pub enum GroupConflict<'a> {
+    /// `reset_to_default` or `reset_group`
+    Method,
+    /// `reset_{field}` of a field with the same name as the group
+    Field(&'a Field),
+}
+
+/// The method that `reset_{group}` would have the same name as, if any
+pub fn group_conflict<'a>(fields: &'a [Field], group: &Ident) -> Option<GroupConflict<'a>> {
+    let name = group.to_string();
+    if name == "to_default" || name == "group" {
+        return Some(GroupConflict::Method);
+    }
+    fields
+        .iter()
+        .find(|field| field.default.is_some() && unraw(&field.ident) == name)
+        .map(GroupConflict::Field)
+}
+
+/// Groups of the fields from `#[auto_default(group = "...")]`, in the order they are first used
+pub fn groups(fields: &[Field]) -> Vec<(&Ident, Vec<&Field>)> {
+    let mut groups = Vec::<(&Ident, Vec<&Field>)>::new();
+    for field in fields {
+        let Some(group) = &field.group else {
+            continue;
+        };
+        match groups
+            .iter_mut()
+            .find(|(other, _)| other.is(&group.to_string()))
+        {
+            Some((_, fields)) => fields.push(field),
+            None => groups.push((group, vec![field])),
+        }
+    }
+    groups
+}
+
+/// `is_default`, which compares every field that has a default value against that value
+fn is_default(generics: &Generics, fields: &[Field]) -> TokenStream {
+    let mut body = TokenStream::new();
+
+    for field in fields {
+        if let Some(default) = &field.default {
+            body.extend(compare(field, default, tokens!["return false;"]));
+        }
+    }
+
+    tokens![
+        doc("Returns `true` if every field that has a default value is equal to it"),
+        "pub fn is_default(&self) -> bool",
+        partial_eq_bounds(generics, fields),
+        braced(tokens![body, "true"]),
+    ]
+}
+
+/// Runs `on_change` if the field is not equal to its `default` value
+///
+/// ```text
+/// { let default: u8 = 3; if self.a != default { on_change } }
+/// ```
+///
+/// Fields that are disabled by `#[cfg(...)]` are not compared
+fn compare(field: &Field, default: &[TokenTree], on_change: TokenStream) -> TokenStream {
+    // Not visible to the default values, which may refer to something else called `default`
+    let local = Ident::new("default", Span::mixed_site());
+
+    // The type is required, because a field can implement `PartialEq` for multiple types:
+    // `String == Default::default()` would be ambiguous
+    tokens![
+        &field.cfg,
+        braced(tokens![
+            "let",
+            &local,
+            ":",
+            &field.ty,
+            "=",
+            default,
+            "; if self .",
+            &field.ident,
+            "!=",
+            &local,
+            braced(on_change)
+        ])
+    ]
+}
+
+/// `where` clause of a method that compares the fields with their default values
+///
+/// ```text
+/// where Option<T>: PartialEq
+/// ```
+///
+/// Only the types of compared fields that mention a generic parameter get a bound, like
+/// with `#[auto_default(impl_default)]`. Fields with `#[cfg(...)]` don't get a bound,
+/// because attributes on where predicates are unstable
+fn partial_eq_bounds(generics: &Generics, fields: &[Field]) -> TokenStream {
+    let params = type_params(generics);
+    let mut bounds = TokenStream::new();
+    for field in fields {
+        if field.default.is_some() && field.cfg.is_empty() && mentions(&field.ty, &params) {
+            bounds.extend(tokens![&field.ty, ": ::core::cmp::PartialEq,"]);
+        }
+    }
+    if bounds.is_empty() {
+        return bounds;
+    }
+    tokens!["where", bounds]
+}
+
+/// `changed_fields`, which returns the name of every field that is not equal to its default value
+///
+/// ```rust,ignore
+/// pub fn changed_fields(&self) -> impl Iterator<Item = &'static str> + use<T> {
+///     let mut changed = [None; 2];
+///     { let default: u8 = 3; if self.a != default { changed[0] = Some("a"); } }
+///     { let default: String = Default::default(); if self.b != default { changed[1] = Some("b"); } }
+///     changed.into_iter().flatten()
+/// }
+/// ```
+///
+/// The iterator doesn't borrow `self`, so the fields can be changed while iterating
+fn changed_fields(generics: &Generics, fields: &[Field]) -> TokenStream {
+    let mut body = TokenStream::new();
+
+    let changed = Ident::new("changed", Span::mixed_site());
+
+    let mut len = 0;
+    for field in fields {
+        let Some(default) = &field.default else {
+            continue;
+        };
+
+        // Fields that are disabled by `#[cfg(...)]` stay `None`
+        body.extend(compare(
+            field,
+            default,
+            tokens![
+                &changed,
+                Group::new(
+                    Delimiter::Bracket,
+                    tokens![TokenTree::Literal(Literal::usize_unsuffixed(len))]
+                ),
+                "= ::core::option::Option::Some",
+                parenthesized(tokens![TokenTree::Literal(Literal::string(&unraw(
+                    &field.ident
+                )))]),
+                ";"
+            ],
+        ));
+        len += 1;
+    }
+
+    tokens![
+        doc("Returns the name of every field that is not equal to its default value"),
+        "pub fn changed_fields(&self) -> impl ::core::iter::Iterator<Item = &'static str> + use <",
+        &generics.args,
+        ">",
+        partial_eq_bounds(generics, fields),
+        braced(tokens![
+            "let mut",
+            &changed,
+            ":",
+            Group::new(
+                Delimiter::Bracket,
+                tokens![
+                    "::core::option::Option<&'static str>;",
+                    TokenTree::Literal(Literal::usize_unsuffixed(len))
+                ]
+            ),
+            "=",
+            Group::new(
+                Delimiter::Bracket,
+                tokens![
+                    "::core::option::Option::None;",
+                    TokenTree::Literal(Literal::usize_unsuffixed(len))
+                ]
+            ),
+            ";",
+            body,
+            // Methods of traits can't be called without the prelude
+            "::core::iter::Iterator::flatten",
+            parenthesized(tokens![
+                "::core::iter::IntoIterator::into_iter",
+                parenthesized(tokens![&changed])
+            ])
+        ]),
+    ]
+}
+
+/// `with`, which creates the default value of the struct and passes it to a closure to change it
+fn with_fn() -> TokenStream {
+    let value = Ident::new("value", Span::mixed_site());
+
+    tokens![
+        doc("Creates the default value, and changes it with `f`"),
+        "pub fn with(f: impl ::core::ops::FnOnce(&mut Self)) -> Self",
+        braced(tokens![
+            "let mut",
+            &value,
+            "= Self { .. }; f",
+            parenthesized(tokens!["&mut", &value]),
+            ";",
+            &value
+        ]),
+    ]
+}
+
+/// `FIELD_DEFAULTS`, which maps the name of every field to its default value as a string
+///
+/// ```rust,ignore
+/// pub const FIELD_DEFAULTS: &'static [(&'static str, &'static str)] = &[
+///     ("age", "::core::default::Default::default()"),
+///     ("volume", "custom"),
+///     ("name", "required"),
+/// ];
+/// ```
+///
+/// Fields with `#[cfg(...)]` are only included if they are enabled. Attributes are not allowed
+/// on elements of an array, so instead the disabled fields are filtered out during const evaluation
+fn field_defaults(fields: &[Field]) -> TokenStream {
+    let mut entries = TokenStream::new();
+    let has_cfg = fields.iter().any(|field| !field.cfg.is_empty());
+
+    for field in fields {
+        let default = match &field.default {
+            Some(_) if field.custom => "custom".to_string(),
+            Some(default) => pretty::tokens_to_string(default.iter().cloned()),
+            None => "required".to_string(),
+        };
+
+        // #[cfg(a)] #[cfg(b)] => cfg!(all(a, b))
+        let mut predicates = TokenStream::new();
+        for tt in &field.cfg {
+            if let TokenTree::Group(attr) = tt
+                && let Some(TokenTree::Group(predicate)) = attr.stream().into_iter().nth(1)
+            {
+                predicates.extend(tokens![predicate.stream(), ","]);
+            }
+        }
+
+        // ("age", "0", true)
+        //              ^^^^ whether the field is enabled, only needed if some field has a cfg
+        let mut entry = tokens![
+            TokenTree::Literal(Literal::string(&unraw(&field.ident))),
+            ",",
+            TokenTree::Literal(Literal::string(&default)),
+        ];
+        if has_cfg {
+            entry.extend(tokens![
+                ", ::core::cfg!",
+                parenthesized(tokens!["all", parenthesized(predicates)])
+            ]);
+        }
+
+        entries.extend(tokens![parenthesized(entry), ","]);
+    }
+
+    let value = if has_cfg {
+        let len = TokenTree::Literal(Literal::usize_unsuffixed(fields.len()));
+        tokens![braced(tokens![
+            "const FIELDS:",
+            Group::new(Delimiter::Bracket, tokens!["(&str, &str, bool);", len]),
+            "=",
+            Group::new(Delimiter::Bracket, entries),
+            ";",
+            "const LEN: usize = {
+                let mut len = 0;
+                let mut i = 0;
+                while i < FIELDS.len() {
+                    if FIELDS[i].2 { len += 1; }
+                    i += 1;
+                }
+                len
+            };
+            const ENABLED: [(&str, &str); LEN] = {
+                let mut enabled = [(\"\", \"\"); LEN];
+                let mut i = 0;
+                let mut j = 0;
+                while i < FIELDS.len() {
+                    if FIELDS[i].2 {
+                        enabled[j] = (FIELDS[i].0, FIELDS[i].1);
+                        j += 1;
+                    }
+                    i += 1;
+                }
+                enabled
+            };
+            &ENABLED"
+        ])]
+    } else {
+        tokens!["&", Group::new(Delimiter::Bracket, entries)]
+    };
+
+    tokens![
+        doc("Name of every field, and its default value as a string"),
+        doc(""),
+        doc(
+            "The default value is `\"custom\"` if it is written by hand, or `\"required\"` if the field"
+        ),
+        doc("has no default value"),
+        "pub const FIELD_DEFAULTS: &'static [(&'static str, &'static str)] =",
+        value,
+        ";"
+    ]
+}
+
+/// `__{field}_field_default` for every field with `serde_default` set, which `serde` calls
+/// through `#[serde(default = "...")]` when the field is missing
+fn serde_defaults(fields: &[Field]) -> TokenStream {
+    let mut functions = TokenStream::new();
+
+    for field in fields.iter().filter(|field| field.serde_default) {
+        let Some(default) = &field.default else {
+            continue;
+        };
+
+        functions.extend(tokens![
+            &field.cfg,
+            "#[doc(hidden)] fn",
+            &serde_default_fn(&field.ident),
+            "() ->",
+            &field.ty,
+            braced(tokens![default]),
+        ]);
+    }
+
+    functions
+}
+
+/// Name of the function that returns the default value of the field `ident`,
+/// for `#[auto_default(serde_defaults)]`
+///
+/// ```text
+/// port => __port_field_default
+/// ```
+pub fn serde_default_fn(ident: &Ident) -> Ident {
+    Ident::new(&format!("__{}_field_default", unraw(ident)), ident.span())
+}
+
+/// Name of `ident`, without the `r#` of a raw identifier
+pub fn unraw(ident: &Ident) -> String {
+    let name = ident.to_string();
+    match name.strip_prefix("r#") {
+        Some(name) => name.to_string(),
+        None => name,
+    }
+}
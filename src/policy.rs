@@ -0,0 +1,364 @@
+//! Policy of the whole crate, from an `auto_default.toml` file next to its `Cargo.toml`
+//!
+//! ```toml
+//! # Fields of these types are skipped, as if they were marked `#[auto_default(skip)]`
+//! skip_types = ["RawFd", "OwnedHandle"]
+//!
+//! # Every item reports redundant default values, like `#[auto_default(deny_redundant)]`
+//! strict = true
+//!
+//! # Default values of fields with these types, instead of `Default::default()`
+//! [types]
+//! Duration = "Duration::ZERO"
+//! "Option<Handle>" = "None"
+//! ```
+//!
+//! The `AUTO_DEFAULT_CONFIG` environment variable points at another file, relative to the
+//! directory of `Cargo.toml`. Only the parts of TOML that the file needs are supported:
+//! strings, booleans, arrays of strings and the `[types]` table
+//!
+//! The caller finds the file with [`path`], since only it knows how to read the environment
+//! so that `cargo` compiles the crate again when the variable or the file changes
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use proc_macro::{Span, TokenStream, TokenTree};
+
+use crate::diagnostic::{CompileError, ErrorCode};
+use crate::expand::{IdentExt, tokens_eq, type_name};
+
+/// Name of the policy file in the directory of `Cargo.toml`
+const FILE_NAME: &str = "auto_default.toml";
+
+/// Environment variable with the path to the policy file, if it is not `auto_default.toml`
+pub const ENV_VAR: &str = "AUTO_DEFAULT_CONFIG";
+
+/// Options that apply to every item of the crate
+pub struct Policy {
+    /// Types of the fields that are skipped
+    pub skip_types: Vec<Vec<TokenTree>>,
+    /// Types of the fields, and their default values
+    pub types: Vec<(Vec<TokenTree>, TokenStream)>,
+    /// Report redundant default values as errors
+    pub strict: bool,
+}
+
+impl Policy {
+    /// Policy from the file at `path`
+    pub fn load(path: &Path, errors: &mut Vec<CompileError>) -> Option<Self> {
+        // Proc macros may expand items of several crates in the same process,
+        // such as in `rust-analyzer`, so files are cached by their path. A file is read again
+        // when it was modified since it was cached
+        static FILES: Mutex<Vec<Cached>> = Mutex::new(Vec::new());
+        let mut files = FILES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let modified = match std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => modified,
+            Err(error) => {
+                errors.push(invalid(path, &error.to_string()));
+                return None;
+            }
+        };
+        let file = match files
+            .iter()
+            .position(|cached| cached.path == path && cached.modified == modified)
+        {
+            Some(i) => &files[i].file,
+            None => {
+                let file = std::fs::read_to_string(path)
+                    .map_err(|error| error.to_string())
+                    .and_then(|source| File::parse(&source));
+                files.retain(|cached| cached.path != path);
+                files.push(Cached {
+                    path: path.to_path_buf(),
+                    modified,
+                    file,
+                });
+                &files.last()?.file
+            }
+        };
+
+        let file = match file {
+            Ok(file) => file,
+            Err(error) => {
+                errors.push(invalid(path, error));
+                return None;
+            }
+        };
+
+        let mut parse = |value: &str| match value.parse::<TokenStream>() {
+            Ok(tokens) => Some(tokens),
+            Err(_) => {
+                errors.push(invalid(path, &format!("`{value}` is not valid Rust code")));
+                None
+            }
+        };
+
+        Some(Self {
+            skip_types: file
+                .skip_types
+                .iter()
+                .filter_map(|ty| Some(parse(ty)?.into_iter().collect()))
+                .collect(),
+            types: file
+                .types
+                .iter()
+                .filter_map(|(ty, value)| Some((parse(ty)?.into_iter().collect(), parse(value)?)))
+                .collect(),
+            strict: file.strict,
+        })
+    }
+
+    /// Whether fields of type `ty` are skipped
+    pub fn skips(&self, ty: &[TokenTree]) -> bool {
+        self.skip_types.iter().any(|skipped| matches(ty, skipped))
+    }
+
+    /// Default value of fields of type `ty`
+    pub fn default(&self, ty: &[TokenTree]) -> Option<&TokenStream> {
+        let (_, value) = self.types.iter().find(|(other, _)| matches(ty, other))?;
+        Some(value)
+    }
+}
+
+/// Whether the type of a field is the type `policy` from the policy file
+///
+/// A type without a path or generics matches every type with that name, so `Duration` matches
+/// `std::time::Duration`. Other types must be written the same way as the type of the field
+fn matches(ty: &[TokenTree], policy: &[TokenTree]) -> bool {
+    match policy {
+        [TokenTree::Ident(name)] => type_name(ty).is_some_and(|ty| ty.is(&name.to_string())),
+        _ => tokens_eq(ty, policy),
+    }
+}
+
+/// Path to the policy file of the crate in `manifest_dir`, if it has one
+///
+/// `config` is the value of `AUTO_DEFAULT_CONFIG`
+pub fn path(manifest_dir: &Path, config: Option<&OsStr>) -> Option<PathBuf> {
+    match config {
+        // Reported as an error if it doesn't exist, since it was asked for
+        Some(path) => Some(manifest_dir.join(path)),
+        None => Some(manifest_dir.join(FILE_NAME)).filter(|path| path.is_file()),
+    }
+}
+
+/// The policy file can't be used
+fn invalid(path: &Path, error: &str) -> CompileError {
+    CompileError::new(
+        ErrorCode::InvalidArgument,
+        Span::call_site(),
+        format!("invalid policy file `{}`: {error}", path.display()),
+    )
+}
+
+/// A policy file that was read
+struct Cached {
+    path: PathBuf,
+    /// When the file was modified before it was read
+    modified: SystemTime,
+    file: Result<File, String>,
+}
+
+/// Contents of the policy file, which can be shared between threads unlike [`Policy`]
+#[derive(Default)]
+struct File {
+    /// `skip_types = ["RawFd"]`
+    skip_types: Vec<String>,
+    /// `[types]`
+    types: Vec<(String, String)>,
+    /// `strict = true`
+    strict: bool,
+}
+
+impl File {
+    /// Parse the source of the policy file
+    fn parse(source: &str) -> Result<Self, String> {
+        let mut file = Self::default();
+        let mut in_types = false;
+        let mut lines = source.lines().enumerate();
+
+        while let Some((i, line)) = lines.next() {
+            let error = |message: &str| format!("line {}: {message}", i + 1);
+            let mut line = strip_comment(line).trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+
+            // [types]
+            if let Some(table) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                if table.trim() != "types" {
+                    return Err(error(&format!(
+                        "unknown table `[{table}]`, expected `[types]`"
+                    )));
+                }
+                in_types = true;
+                continue;
+            }
+
+            let Some((key, _)) = split_key(&line) else {
+                return Err(error("expected `key = value`"));
+            };
+
+            // skip_types = [
+            //     "RawFd",
+            // ]
+            let is_open_array = |line: &str| {
+                split_key(line).is_some_and(|(_, value)| {
+                    value.trim_start().starts_with('[') && find_unquoted(value, ']').is_none()
+                })
+            };
+            if is_open_array(&line) {
+                for (_, next) in lines.by_ref() {
+                    line.push_str(strip_comment(next).trim());
+                    if !is_open_array(&line) {
+                        break;
+                    }
+                }
+            }
+            let value = split_key(&line)
+                .map(|(_, value)| value.trim())
+                .unwrap_or_default();
+
+            match (in_types, key.as_str()) {
+                (true, _) => {
+                    let value = string(value).ok_or_else(|| {
+                        error(&format!("the default value of `{key}` must be a string"))
+                    })?;
+                    file.types.push((key, value));
+                }
+                (false, "skip_types") => {
+                    file.skip_types = array(value)
+                        .ok_or_else(|| error("`skip_types` must be an array of strings"))?;
+                }
+                (false, "strict") => {
+                    file.strict = match value {
+                        "true" => true,
+                        "false" => false,
+                        _ => return Err(error("`strict` must be `true` or `false`")),
+                    };
+                }
+                (false, key) => {
+                    return Err(error(&format!(
+                        "unknown key `{key}`, expected `skip_types`, `strict` or `[types]`"
+                    )));
+                }
+            }
+        }
+
+        Ok(file)
+    }
+}
+
+/// The line without a `#` comment, which can't be inside of a string
+fn strip_comment(line: &str) -> &str {
+    &line[..find_unquoted(line, '#').unwrap_or(line.len())]
+}
+
+/// Index of the first `target` in `line` that is not inside of a string
+fn find_unquoted(line: &str, target: char) -> Option<usize> {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            c if c == target && !in_string => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Key of a `key = value` line, and the rest of the line after the `=`
+///
+/// ```text
+/// "Option<Handle>" = "None"
+/// Duration = "Duration::ZERO"
+/// ```
+fn split_key(line: &str) -> Option<(String, &str)> {
+    if line.starts_with('"') {
+        let (key, rest) = parse_string(line)?;
+        return Some((key, rest.trim_start().strip_prefix('=')?));
+    }
+    let (key, rest) = line.split_once('=')?;
+    let key = key.trim();
+    key.chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        .then(|| (key.to_string(), rest))
+}
+
+/// Contents of a string, such as `Duration::ZERO` for `"Duration::ZERO"`
+fn string(value: &str) -> Option<String> {
+    let (string, rest) = parse_string(value.trim())?;
+    rest.trim().is_empty().then_some(string)
+}
+
+/// Contents of the strings in an array, such as `RawFd` and `HashMap<u8, u8>`
+/// for `["RawFd", "HashMap<u8, u8>"]`
+fn array(value: &str) -> Option<Vec<String>> {
+    let mut rest = value.trim().strip_prefix('[')?.trim_start();
+    let mut strings = Vec::new();
+    loop {
+        if let Some(after) = rest.strip_prefix(']') {
+            return after.trim().is_empty().then_some(strings);
+        }
+        let (string, after) = parse_string(rest)?;
+        strings.push(string);
+        rest = after.trim_start();
+
+        // The `,` after the last string is optional
+        match rest.strip_prefix(',') {
+            Some(after) => rest = after.trim_start(),
+            None if rest.starts_with(']') => {}
+            None => return None,
+        }
+    }
+}
+
+/// Contents of the string at the start of `value`, and the rest of `value` after it
+///
+/// ```text
+/// "Option<\"a\">" = "None"
+///  ^^^^^^^^^^^^^ Option<"a">
+/// ```
+fn parse_string(value: &str) -> Option<(String, &str)> {
+    let quoted = value.strip_prefix('"')?;
+    let mut string = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((string, &quoted[i + 1..])),
+            '\\' => {
+                let escaped = match chars.next()?.1 {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    '"' => '"',
+                    '\\' => '\\',
+                    // \u00E9 or \U000000E9
+                    u @ ('u' | 'U') => {
+                        let len = if u == 'u' { 4 } else { 8 };
+                        let hex = (0..len)
+                            .map(|_| chars.next().map(|(_, c)| c))
+                            .collect::<Option<String>>()?;
+                        char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                    }
+                    _ => return None,
+                };
+                string.push(escaped);
+            }
+            c => string.push(c),
+        }
+    }
+    None
+}
@@ -0,0 +1,355 @@
+//! Turns tokens into a string that looks like hand-written Rust code
+//!
+//! `TokenStream`'s `Display` implementation puts a space between almost every token:
+//!
+//! ```text
+//! :: core :: default :: Default :: default ()
+//! ```
+//!
+//! Whilst [`tokens_to_string`] gives:
+//!
+//! ```text
+//! ::core::default::Default::default()
+//! ```
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+use crate::expand::IdentExt;
+
+/// Converts `tokens` into a string of Rust code, on a single line
+pub fn tokens_to_string(tokens: impl IntoIterator<Item = TokenTree>) -> String {
+    let mut out = String::new();
+    write_tokens(&mut out, tokens, None);
+    out
+}
+
+/// Converts `tokens`, which is one or more items, into a string of Rust code
+///
+/// Items are spread over multiple lines and indented,
+/// similar to how `rustfmt` would format them
+pub fn item_to_string(tokens: impl IntoIterator<Item = TokenTree>) -> String {
+    let mut out = String::new();
+    write_tokens(&mut out, tokens, Some(0));
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Writes `tokens` into `out`
+///
+/// If `indent` is `Some`, `tokens` are inside of a block (or at the top level of the item),
+/// and are written over multiple lines with the given level of indentation.
+/// Otherwise, they are written on a single line
+fn write_tokens(
+    out: &mut String,
+    tokens: impl IntoIterator<Item = TokenTree>,
+    indent: Option<usize>,
+) {
+    // The previous two tokens, to figure out if we need a space before the next token
+    let mut prev: Option<TokenTree> = None;
+    let mut prev_prev: Option<TokenTree> = None;
+
+    // How many `<` of generic arguments we are inside of
+    //
+    // Vec::<u8>::new()
+    //      ^^^^
+    let mut generics_depth = 0_usize;
+    // If the previous token is a `<` that opens generic arguments
+    let mut opened_generics = false;
+    // If the previous token is a `>` that closes generic arguments
+    let mut closed_generics = false;
+    // If we are inside of the generics of an `impl`
+    //
+    // impl<T> ::core::default::Default for Foo<T>
+    //     ^^^
+    let mut impl_generics = false;
+    // If the previous token is the `>` that closes the generics of an `impl`
+    let mut closed_impl_generics = false;
+
+    // If we are inside of a block, but writing an expression on a single line
+    //
+    // field: Type = Default::default(),
+    //               ^^^^^^^^^^^^^^^^^^
+    let mut in_expr = false;
+    // If the next token starts a new line, so it does not need a space before it
+    let mut line_start = true;
+    // If the previous token is a `}` that ends a block written over multiple lines
+    let mut closed_block = false;
+
+    for tt in tokens {
+        let is_punct = |c: char| matches!(&tt, TokenTree::Punct(punct) if punct.as_char() == c);
+        let prev_is_punct =
+            |c: char| matches!(&prev, Some(TokenTree::Punct(punct)) if punct.as_char() == c);
+
+        // Indentation of the current line, if we are writing over multiple lines
+        let block = indent.filter(|_| !in_expr);
+        let after_impl_generics = std::mem::take(&mut closed_impl_generics);
+
+        // struct Foo { ... }
+        // impl Foo { ... }
+        //                 ^ next item goes on a new line
+        if std::mem::take(&mut closed_block) && !is_punct(',') && !is_punct(';') && !is_punct('=') {
+            new_line(out, indent.unwrap_or_default());
+            line_start = true;
+        }
+
+        // fn() -> Type
+        //       ^ this is not a closing angle bracket
+        let closes_generics = generics_depth > 0
+            && is_punct('>')
+            && !matches!(
+                &prev,
+                Some(TokenTree::Punct(punct)) if punct.as_char() == '-' && punct.spacing() == Spacing::Joint
+            );
+
+        // Vec::<u8>
+        //      ^
+        // <T as Trait>::CONST
+        // ^
+        // struct Foo<T>
+        //           ^
+        let opens_generics = is_punct('<')
+            && (generics_depth > 0
+                || prev.is_none()
+                || prev_is_punct(':')
+                || (block.is_some() && matches!(&prev, Some(TokenTree::Ident(_)))));
+
+        let space = if std::mem::take(&mut line_start) {
+            false
+        } else if closes_generics {
+            // Vec::<u8>
+            //         ^
+            false
+        } else if opens_generics && matches!(&prev, Some(TokenTree::Ident(_))) {
+            // struct Foo<T>
+            //           ^
+            false
+        } else if opened_generics {
+            // Vec::<u8>
+            //       ^
+            false
+        } else if std::mem::take(&mut closed_generics) {
+            // f::<u8>()
+            //        ^
+            // Vec::<u8>::new()
+            //          ^
+            // impl<T> ::core::default::Default
+            //        ^
+            after_impl_generics
+                || !is_punct(':')
+                    && !is_punct(',')
+                    && !is_punct('>')
+                    && !matches!(&tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis)
+        } else {
+            needs_space(prev_prev.as_ref(), prev.as_ref(), &tt)
+        };
+
+        if space {
+            out.push(' ');
+        }
+
+        closed_generics = closes_generics;
+        if closes_generics {
+            generics_depth -= 1;
+            closed_impl_generics = generics_depth == 0 && std::mem::take(&mut impl_generics);
+        }
+        opened_generics = opens_generics;
+        if opens_generics {
+            if generics_depth == 0 {
+                impl_generics = matches!(&prev, Some(TokenTree::Ident(ident)) if ident.is("impl"));
+            }
+            generics_depth += 1;
+        }
+
+        match &tt {
+            TokenTree::Group(group) => {
+                let inner = group.stream();
+                match group.delimiter() {
+                    Delimiter::Parenthesis => {
+                        out.push('(');
+                        write_tokens(out, inner, None);
+                        out.push(')');
+                    }
+                    Delimiter::Bracket => {
+                        out.push('[');
+                        write_tokens(out, inner, None);
+                        out.push(']');
+
+                        // #[attr]
+                        //        ^ attributes are on their own line
+                        // #![attr]
+                        //         ^
+                        // but not vec![]
+                        if let Some(indent) = block
+                            && (prev_is_punct('#')
+                                || (prev_is_punct('!')
+                                    && matches!(&prev_prev, Some(TokenTree::Punct(punct)) if punct.as_char() == '#')))
+                        {
+                            new_line(out, indent);
+                            line_start = true;
+                        }
+                    }
+                    Delimiter::Brace if inner.is_empty() => out.push_str("{}"),
+                    // macro_name! { ... } is written on a single line
+                    Delimiter::Brace
+                        if block.is_some() && !is_rest(&inner) && !prev_is_punct('!') =>
+                    {
+                        let indent = block.unwrap_or_default();
+                        out.push('{');
+                        new_line(out, indent + 1);
+                        write_tokens(out, inner, Some(indent + 1));
+                        new_line(out, indent);
+                        out.push('}');
+                        closed_block = true;
+                    }
+                    Delimiter::Brace => {
+                        out.push_str("{ ");
+                        write_tokens(out, inner, None);
+                        out.push_str(" }");
+                    }
+                    Delimiter::None => write_tokens(out, inner, block),
+                }
+            }
+            TokenTree::Ident(ident) => out.push_str(&ident.to_string()),
+            TokenTree::Punct(punct) => {
+                out.push(punct.as_char());
+
+                if let Some(indent) = indent
+                    && generics_depth == 0
+                {
+                    match punct.as_char() {
+                        // field: Type,
+                        //            ^ next field goes on a new line
+                        // let a = 4;
+                        //          ^ next statement goes on a new line
+                        ',' | ';' => {
+                            in_expr = false;
+                            new_line(out, indent);
+                            line_start = true;
+                        }
+                        // field: Type = Default::default(),
+                        //             ^ the value goes on a single line
+                        '=' if punct.spacing() == Spacing::Alone
+                            && !matches!(&prev, Some(TokenTree::Punct(prev)) if prev.spacing() == Spacing::Joint) =>
+                        {
+                            in_expr = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            TokenTree::Literal(literal) => out.push_str(&literal.to_string()),
+        }
+
+        prev_prev = prev;
+        prev = Some(tt);
+    }
+}
+
+/// Starts a new line in `out` with the given level of indentation,
+/// removing any trailing whitespace on the current line
+fn new_line(out: &mut String, indent: usize) {
+    out.truncate(out.trim_end_matches(' ').len());
+    if out.ends_with('\n') {
+        // do not leave lines that only contain whitespace
+        out.truncate(out.trim_end().len());
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&"    ".repeat(indent));
+}
+
+/// Whether `stream` is `..`, as in `Foo { .. }`
+fn is_rest(stream: &TokenStream) -> bool {
+    let mut tokens = stream.clone().into_iter();
+    matches!(
+        (tokens.next(), tokens.next(), tokens.next()),
+        (Some(TokenTree::Punct(a)), Some(TokenTree::Punct(b)), None) if a.as_char() == '.' && b.as_char() == '.'
+    )
+}
+
+/// Whether a space should be inserted between `prev` and `next`
+fn needs_space(prev_prev: Option<&TokenTree>, prev: Option<&TokenTree>, next: &TokenTree) -> bool {
+    let Some(prev) = prev else {
+        // first token
+        return false;
+    };
+
+    match (prev, next) {
+        // ::core
+        //  ^
+        // a.b
+        //  ^
+        (TokenTree::Punct(punct), _)
+            if punct.spacing() == Spacing::Joint || punct.as_char() == '.' =>
+        {
+            false
+        }
+        // core::default
+        //     ^
+        // a.b
+        //  ^
+        // [a, b]
+        //   ^
+        // a: b
+        //  ^
+        // vec![]
+        //    ^
+        (_, TokenTree::Punct(punct)) if matches!(punct.as_char(), ':' | '.' | ',' | ';' | '!') => {
+            // `= ::core` and `field: ::core`, but not `foo::bar`
+            (punct.as_char() == ':'
+                && punct.spacing() == Spacing::Joint
+                && matches!(prev, TokenTree::Punct(prev) if prev.as_char() != ':' || !is_path_separator(prev_prev)))
+                // `a != b`, but not `vec![]`
+                || (punct.as_char() == '!' && punct.spacing() == Spacing::Joint)
+        }
+        // ::core
+        //  ^^
+        (TokenTree::Punct(punct), _) if punct.as_char() == ':' => !is_path_separator(prev_prev),
+        // vec![]
+        //     ^
+        // #[attr]
+        //  ^
+        (TokenTree::Punct(punct), TokenTree::Group(_)) if matches!(punct.as_char(), '!' | '#') => {
+            false
+        }
+        // -1
+        // &a
+        // *a
+        // !a
+        (TokenTree::Punct(punct), _) if matches!(punct.as_char(), '-' | '&' | '*' | '!') => {
+            // binary operator, e.g. `a - 1`
+            matches!(
+                prev_prev,
+                Some(TokenTree::Ident(_) | TokenTree::Literal(_) | TokenTree::Group(_))
+            )
+        }
+        // Default::default()
+        //                 ^
+        // array[0]
+        //      ^
+        (TokenTree::Ident(_) | TokenTree::Group(_), TokenTree::Group(group))
+            if matches!(
+                group.delimiter(),
+                Delimiter::Parenthesis | Delimiter::Bracket
+            ) =>
+        {
+            // keywords such as `if (...)` or `in [...]` are followed by a space,
+            // and so are lifetimes: `&'static [u8]`
+            matches!(prev, TokenTree::Ident(ident) if is_keyword(&ident.to_string()))
+                || matches!(prev_prev, Some(TokenTree::Punct(punct)) if punct.as_char() == '\'')
+        }
+        _ => true,
+    }
+}
+
+/// Whether `tt` is the first `:` of a `::`
+fn is_path_separator(tt: Option<&TokenTree>) -> bool {
+    matches!(tt, Some(TokenTree::Punct(punct)) if punct.as_char() == ':' && punct.spacing() == Spacing::Joint)
+}
+
+fn is_keyword(ident: &str) -> bool {
+    matches!(
+        ident,
+        "as" | "if" | "in" | "match" | "return" | "while" | "move" | "mut" | "const" | "unsafe"
+    )
+}
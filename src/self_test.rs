@@ -0,0 +1,135 @@
+//! Tests that are generated next to the item by `#[auto_default(self_test)]`
+//! and `#[auto_default(validate_test)]`
+//!
+//! ```rust,ignore
+//! #[auto_default(self_test)]
+//! #[derive(Default, PartialEq)]
+//! struct Foo {
+//!     a: u32,
+//! }
+//!
+//! #[cfg(test)]
+//! #[test]
+//! fn auto_default_self_test_foo() {
+//!     assert!(Foo { .. } == <Foo as Default>::default(), ...);
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! #[auto_default(validate_test)]
+//! #[derive(Validate)]
+//! struct Foo {
+//!     #[validate(range(min = 1))]
+//!     a: u32 = 1,
+//! }
+//!
+//! #[cfg(test)]
+//! #[test]
+//! fn auto_default_validate_test_foo() {
+//!     if let Err(error) = Foo { .. }.validate() {
+//!         panic!(...);
+//!     }
+//! }
+//! ```
+use proc_macro::{Ident, Literal, TokenStream, TokenTree};
+
+use crate::expand::DefaultValue;
+use crate::methods::unraw;
+use crate::pretty;
+use crate::tokens::{braced, parenthesized, tokens};
+
+/// `#[test]` which checks that `value` is equal to `Default::default()`
+///
+/// `value` is created from the default field values, such as `Foo { .. }` or `Foo::Variant { .. }`
+pub fn self_test(ident: &Ident, value: &DefaultValue) -> TokenStream {
+    let value = if value.fields.is_some() {
+        tokens![&value.path, "{ .. }"]
+    } else {
+        value.path.clone()
+    };
+
+    let test = Ident::new(
+        &format!("auto_default_self_test_{}", snake_case(&unraw(ident))),
+        ident.span(),
+    );
+
+    let message = format!(
+        "`{}` is not equal to `{ident}::default()`, so the default field values \
+         are different from the implementation of `Default`",
+        pretty::tokens_to_string(value.clone())
+    );
+
+    tokens![
+        "#[cfg(test)] #[test] fn",
+        &test,
+        "()",
+        braced(tokens![
+            "::core::assert!",
+            parenthesized(tokens![
+                value,
+                "== <",
+                ident,
+                "as ::core::default::Default>::default(),",
+                "\"{}\",",
+                TokenTree::Literal(Literal::string(&message)),
+            ]),
+            ";"
+        ])
+    ]
+}
+
+/// `#[test]` which checks that `value` passes the validation of `validator` or `garde`
+///
+/// Both crates have a `Validate` trait with a `validate` method, whose error implements `Display`.
+/// The trait is called as a method, so it must be in scope like for `#[derive(Validate)]`
+pub fn validate_test(ident: &Ident, value: &DefaultValue) -> TokenStream {
+    let value = if value.fields.is_some() {
+        tokens![&value.path, "{ .. }"]
+    } else {
+        value.path.clone()
+    };
+
+    let test = Ident::new(
+        &format!("auto_default_validate_test_{}", snake_case(&unraw(ident))),
+        ident.span(),
+    );
+
+    let message = format!(
+        "the default value of `{ident}` is not valid, `{}` fails validation",
+        pretty::tokens_to_string(value.clone())
+    );
+
+    tokens![
+        "#[cfg(test)] #[test] fn",
+        &test,
+        "()",
+        braced(tokens![
+            "if let ::core::result::Result::Err(error) =",
+            parenthesized(value),
+            ". validate()",
+            braced(tokens![
+                "::core::panic!",
+                parenthesized(tokens![
+                    "\"{}: {}\",",
+                    TokenTree::Literal(Literal::string(&message)),
+                    ", error"
+                ]),
+                ";"
+            ])
+        ])
+    ]
+}
+
+/// `HttpConfig` => `http_config`
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev_lowercase = false;
+    for c in name.chars() {
+        if c.is_uppercase() && prev_lowercase {
+            snake.push('_');
+        }
+        prev_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
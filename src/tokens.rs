@@ -0,0 +1,110 @@
+//! Building blocks for items that are generated next to the item, such as `impl` blocks
+//!
+//! ```text
+//! tokens!["impl", ident, braced(methods)]
+//! ```
+//!
+//! Strings are parsed as Rust code, so they must not contain unbalanced delimiters.
+//! Tokens that come from the user's input are spliced in as-is, keeping their spans
+use proc_macro::{Delimiter, Group, Ident, TokenStream, TokenTree};
+
+/// Concatenates pieces of Rust code into a [`TokenStream`]
+///
+/// Every piece implements [`ToTokens`]
+macro_rules! tokens {
+    ($($piece:expr),* $(,)?) => {{
+        let mut tokens = ::proc_macro::TokenStream::new();
+        $(
+            $crate::tokens::ToTokens::to_tokens($piece, &mut tokens);
+        )*
+        tokens
+    }};
+}
+pub(crate) use tokens;
+
+/// A piece of Rust code that can be appended to a [`TokenStream`]
+pub trait ToTokens {
+    /// Appends `self` to `tokens`
+    fn to_tokens(self, tokens: &mut TokenStream);
+}
+
+impl ToTokens for &str {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        tokens.extend(
+            self.parse::<TokenStream>()
+                .expect("generated code is valid Rust"),
+        );
+    }
+}
+
+impl ToTokens for String {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        self.as_str().to_tokens(tokens);
+    }
+}
+
+impl ToTokens for TokenStream {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        tokens.extend(self);
+    }
+}
+
+impl ToTokens for &TokenStream {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        tokens.extend(self.clone());
+    }
+}
+
+impl ToTokens for TokenTree {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        tokens.extend([self]);
+    }
+}
+
+impl ToTokens for &[TokenTree] {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        tokens.extend(self.iter().cloned());
+    }
+}
+
+impl ToTokens for &Vec<TokenTree> {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        self.as_slice().to_tokens(tokens);
+    }
+}
+
+impl ToTokens for &Ident {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        tokens.extend([TokenTree::Ident(self.clone())]);
+    }
+}
+
+impl ToTokens for Group {
+    fn to_tokens(self, tokens: &mut TokenStream) {
+        tokens.extend([TokenTree::Group(self)]);
+    }
+}
+
+/// `{ tokens }`
+pub fn braced(tokens: TokenStream) -> Group {
+    Group::new(Delimiter::Brace, tokens)
+}
+
+/// `(tokens)`
+pub fn parenthesized(tokens: TokenStream) -> Group {
+    Group::new(Delimiter::Parenthesis, tokens)
+}
+
+/// `#[doc = "doc"]`
+pub fn doc(doc: &str) -> TokenStream {
+    tokens![
+        "#",
+        Group::new(
+            Delimiter::Bracket,
+            tokens![
+                "doc =",
+                TokenTree::Literal(proc_macro::Literal::string(doc))
+            ]
+        )
+    ]
+}
@@ -0,0 +1,59 @@
+//! Telling `cargo` about the environment variables and files that the expansion reads,
+//! so that the crate is compiled again when they change
+//!
+//! On nightly, `proc_macro::tracked` does this. Otherwise, items that read them are added
+//! to the expansion, because `rustc` tracks what `option_env!` and `include_bytes!` read:
+//!
+//! ```rust,ignore
+//! const _: ::core::option::Option<&str> = ::core::option_env!("AUTO_DEFAULT_CONFIG");
+//! const _: &[u8] = ::core::include_bytes!("/home/user/project/auto_default.toml");
+//! ```
+use std::path::Path;
+
+use proc_macro::TokenStream;
+#[cfg(not(auto_default_nightly))]
+use proc_macro::{Literal, TokenTree};
+
+#[cfg(not(auto_default_nightly))]
+use crate::tokens::{parenthesized, tokens};
+
+/// Value of the environment variable `name`
+///
+/// Items that track it are added to `items`
+#[cfg(auto_default_nightly)]
+pub fn env_var(name: &str, _items: &mut TokenStream) -> Option<String> {
+    proc_macro::tracked::env_var(name).ok()
+}
+
+/// Value of the environment variable `name`
+///
+/// Items that track it are added to `items`
+#[cfg(not(auto_default_nightly))]
+pub fn env_var(name: &str, items: &mut TokenStream) -> Option<String> {
+    items.extend(tokens![
+        "const _: ::core::option::Option<&str> = ::core::option_env!",
+        parenthesized(tokens![TokenTree::Literal(Literal::string(name))]),
+        ";"
+    ]);
+    std::env::var(name).ok()
+}
+
+/// Tracks the file at `path`, adding the items that track it to `items`
+#[cfg(auto_default_nightly)]
+pub fn path(path: &Path, _items: &mut TokenStream) {
+    proc_macro::tracked::path(path);
+}
+
+/// Tracks the file at `path`, adding the items that track it to `items`
+#[cfg(not(auto_default_nightly))]
+pub fn path(path: &Path, items: &mut TokenStream) {
+    // A path that is not UTF-8 can't be written in a string literal
+    let Some(path) = path.to_str() else {
+        return;
+    };
+    items.extend(tokens![
+        "const _: &[u8] = ::core::include_bytes!",
+        parenthesized(tokens![TokenTree::Literal(Literal::string(path))]),
+        ";"
+    ]);
+}
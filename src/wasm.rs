@@ -0,0 +1,68 @@
+//! Constructor for JavaScript that is generated next to the item by `#[auto_default(wasm)]`
+//!
+//! ```rust,ignore
+//! #[wasm_bindgen]
+//! #[auto_default(wasm(setters))]
+//! pub struct Config {
+//!     port: u16 = 8080,
+//!     #[auto_default(skip)]
+//!     token: String,
+//! }
+//!
+//! #[wasm_bindgen]
+//! impl Config {
+//!     #[wasm_bindgen(constructor)]
+//!     pub fn new() -> Config {
+//!         Config { token: Default::default(), .. }
+//!     }
+//!
+//!     #[wasm_bindgen(setter)]
+//!     pub fn set_token(&mut self, token: String) {
+//!         self.token = token;
+//!     }
+//! }
+//! ```
+//!
+//! `wasm-bindgen` only recognizes `#[wasm_bindgen(...)]` on the methods by its name, so it is
+//! not a path like the attribute on the `impl`. With `setters`, the fields that JavaScript
+//! can't leave out get a setter, and the fields with a default value don't
+use proc_macro::{Ident, TokenStream};
+
+use crate::expand::{DefaultValue, Field};
+use crate::impl_default::construct;
+use crate::methods::unraw;
+use crate::tokens::{braced, parenthesized, tokens};
+
+/// `#[wasm_bindgen] impl` with a constructor that returns `value`, and the setters
+/// of the fields without a default value if `setters` is set
+pub fn wasm(ident: &Ident, value: &DefaultValue, fields: &[Field], setters: bool) -> TokenStream {
+    let fields = value.fields(fields);
+    let (lets, value) = construct(value, fields, |field| field.default.is_some(), false);
+
+    let mut methods = tokens![
+        "#[wasm_bindgen(constructor)] pub fn new() ->",
+        ident,
+        braced(tokens![lets, value])
+    ];
+
+    // #[wasm_bindgen(setter)]
+    // pub fn set_token(&mut self, token: String) { self.token = token; }
+    if setters {
+        for field in fields.iter().filter(|field| field.default.is_none()) {
+            let setter = Ident::new(&format!("set_{}", unraw(&field.ident)), field.ident.span());
+            methods.extend(tokens![
+                &field.cfg,
+                "#[wasm_bindgen(setter)] pub fn",
+                &setter,
+                parenthesized(tokens!["&mut self,", &field.ident, ":", &field.ty]),
+                braced(tokens!["self.", &field.ident, "=", &field.ident, ";"])
+            ]);
+        }
+    }
+
+    tokens![
+        "#[::wasm_bindgen::prelude::wasm_bindgen] impl",
+        ident,
+        braced(methods)
+    ]
+}
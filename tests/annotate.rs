@@ -0,0 +1,27 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![deny(missing_docs)]
+
+//! `#[auto_default(annotate(...))]` adds attributes to fields with a default value
+
+use auto_default::auto_default;
+
+/// Every field with a default value is documented and allowed to be unused
+#[auto_default(annotate(doc = "Has a default value", allow(dead_code),))]
+pub struct Config {
+    retries: u8,
+    theme: &'static str = "dark",
+    /// Skipped fields are not annotated
+    #[auto_default(skip)]
+    pub path: String,
+}
+
+#[test]
+fn test() {
+    let config = Config {
+        path: String::new(),
+        ..
+    };
+    assert!(config.path.is_empty());
+}
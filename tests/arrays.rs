@@ -0,0 +1,41 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// Arrays only implement `Default` up to a length of 32
+
+const LEN: usize = 64;
+
+#[auto_default]
+#[derive(PartialEq, Debug)]
+struct Buffers<const N: usize> {
+    bytes: [u8; 64],
+    names: [String; LEN],
+    grid: [[bool; 40]; 2],
+    generic: [u8; N],
+}
+
+#[auto_default(infer_literals, known_types)]
+#[derive(PartialEq, Debug)]
+struct Literals {
+    bytes: [u8; 64],
+    names: [Option<String>; 40],
+}
+
+#[test]
+fn test() {
+    let buffers = Buffers::<4> { .. };
+    assert_eq!(buffers.bytes, [0; 64]);
+    assert!(buffers.names.iter().all(String::is_empty));
+    assert_eq!(buffers.grid, [[false; 40]; 2]);
+    assert_eq!(buffers.generic, [0; 4]);
+
+    assert_eq!(
+        Literals { .. },
+        Literals {
+            bytes: [0; 64],
+            names: [const { None }; 40],
+        }
+    );
+}
@@ -0,0 +1,45 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `#[auto_default(assert_default)]` checks that the fields implement `Default` next to the item
+
+#[auto_default(assert_default, infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Settings<'a, T>
+where
+    T: PartialEq,
+{
+    volume: u8,
+    name: Vec<T>,
+    label: &'a str = "label",
+    #[cfg(any())]
+    disabled: u8,
+    #[auto_default(with = three)]
+    count: u8,
+}
+
+const fn three() -> u8 {
+    3
+}
+
+#[auto_default(assert_default)]
+#[derive(PartialEq, Debug)]
+enum Mode {
+    Custom { level: u8 },
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        Settings::<u8> { .. },
+        Settings {
+            volume: 0,
+            name: Vec::new(),
+            label: "label",
+            count: 3
+        }
+    );
+    assert_eq!(Mode::Custom { .. }, Mode::Custom { level: 0 });
+}
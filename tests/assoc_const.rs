@@ -0,0 +1,60 @@
+#![feature(default_field_values)]
+
+// `const_trait_impl` and `const_default` are not enabled:
+// `<Type>::DEFAULT` is const-evaluable without `const Default`
+
+use auto_default::auto_default;
+
+#[derive(PartialEq, Debug)]
+struct Vec2 {
+    x: f32,
+    y: f32,
+}
+
+impl Vec2 {
+    const DEFAULT: Self = Self { x: 1.0, y: 1.0 };
+}
+
+#[derive(PartialEq, Debug)]
+struct Wrapper<T>(T);
+
+impl Wrapper<u8> {
+    const DEFAULT: Self = Self(4);
+}
+
+#[auto_default(infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Body {
+    #[auto_default(assoc_const)]
+    position: Vec2,
+    #[auto_default(assoc_const)]
+    wrapper: Wrapper<u8>,
+    mass: f32,
+}
+
+#[auto_default(assoc_const, infer_literals)]
+#[derive(PartialEq, Debug)]
+enum Shape {
+    Point { position: Vec2, size: u8 },
+}
+
+const BODY: Body = Body { .. };
+
+#[test]
+fn test() {
+    assert_eq!(
+        BODY,
+        Body {
+            position: Vec2 { x: 1.0, y: 1.0 },
+            wrapper: Wrapper(4),
+            mass: 0.0,
+        }
+    );
+    assert_eq!(
+        Shape::Point { .. },
+        Shape::Point {
+            position: Vec2 { x: 1.0, y: 1.0 },
+            size: 0
+        }
+    );
+}
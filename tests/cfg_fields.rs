@@ -0,0 +1,33 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// Methods generated for a field that is disabled by `#[cfg]` are disabled too
+
+#[auto_default(reset, is_default, with_fn)]
+#[derive(PartialEq, Debug)]
+struct Settings {
+    volume: u8 = 50,
+    #[cfg(any())]
+    disabled: u8,
+    #[cfg(not(any()))]
+    enabled: bool,
+}
+
+#[test]
+fn test() {
+    let mut settings = Settings::with(|s| s.volume = 10);
+    assert!(!settings.is_default());
+
+    settings.reset_volume();
+    settings.reset_enabled();
+    assert!(settings.is_default());
+    assert_eq!(
+        settings,
+        Settings {
+            volume: 50,
+            enabled: false
+        }
+    );
+}
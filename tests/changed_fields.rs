@@ -0,0 +1,53 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use auto_default::auto_default;
+
+#[auto_default(changed_fields)]
+struct Settings<T> {
+    volume: u8 = 9,
+    name: String,
+    #[cfg(any())]
+    disabled: u8,
+    r#type: u8,
+    // not compared, since it has no default value
+    #[auto_default(skip)]
+    id: T,
+}
+
+#[test]
+fn test() {
+    let mut settings = Settings { id: 1, .. };
+    assert_eq!(settings.changed_fields().count(), 0);
+
+    settings.volume = 1;
+    settings.r#type = 2;
+    settings.id = 2;
+    assert_eq!(
+        settings.changed_fields().collect::<Vec<_>>(),
+        ["volume", "type"]
+    );
+
+    // the iterator doesn't borrow the settings
+    for field in settings.changed_fields() {
+        if field == "volume" {
+            settings.volume = 9;
+        }
+    }
+    assert_eq!(settings.changed_fields().collect::<Vec<_>>(), ["type"]);
+}
+
+#[auto_default(changed_fields)]
+struct Generic<T: Copy> {
+    value: Option<T>,
+}
+
+#[test]
+fn generic() {
+    let mut generic = Generic::<u8> { .. };
+    assert_eq!(generic.changed_fields().count(), 0);
+
+    generic.value = Some(1);
+    assert_eq!(generic.changed_fields().collect::<Vec<_>>(), ["value"]);
+}
@@ -0,0 +1,32 @@
+#![feature(default_field_values)]
+use auto_default::auto_default;
+
+// `#[auto_default(check)]` adds nothing, and only checks that every field has a default value
+
+const fn default_level() -> u8 {
+    3
+}
+
+#[auto_default(check)]
+#[derive(PartialEq, Debug)]
+struct Settings {
+    volume: u8 = 9,
+    name: String = String::new(),
+    #[auto_default(with = default_level)]
+    level: u8,
+    #[auto_default(skip)]
+    id: u32,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        Settings { id: 1, .. },
+        Settings {
+            volume: 9,
+            name: String::new(),
+            level: 3,
+            id: 1,
+        }
+    );
+}
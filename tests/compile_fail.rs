@@ -3,6 +3,7 @@
 fn compile_test() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/compile_fail/*.rs");
+    t.pass("tests/pass/*.rs");
 }
 
 #[rustversion::not(nightly(2026-01-09))]
@@ -1,7 +1,28 @@
 use auto_default::auto_default;
 
-// arguments are not expected
+// unknown arguments are rejected
 #[auto_default(arguments)]
 struct X {}
 
+#[auto_default(infer_literals, infer_literals)]
+struct Y {}
+
+#[auto_default(infer_literals = true)]
+struct Z {}
+
+#[auto_default(infer_literals(true))]
+struct W {}
+
+#[auto_default(infer_literals; arguments)]
+struct V {}
+
+#[auto_default(annotate())]
+struct U {}
+
+#[auto_default(annotate(serde(default),, other))]
+struct T {}
+
+#[auto_default(annotate = serde)]
+struct S {}
+
 fn main() {}
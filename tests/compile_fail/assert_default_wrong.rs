@@ -0,0 +1,14 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+struct NoDefault;
+
+#[auto_default(assert_default)]
+struct Foo {
+    a: u32,
+    b: NoDefault,
+}
+
+fn main() {}
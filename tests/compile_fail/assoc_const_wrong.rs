@@ -0,0 +1,32 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+struct Inner {
+    a: () = (),
+}
+
+#[auto_default(struct_default, assoc_const)]
+struct Container {
+    a: Inner,
+}
+
+#[auto_default]
+struct Field {
+    #[auto_default(assoc_const, struct_default)]
+    a: (),
+    #[auto_default(assoc_const)]
+    b: NoConst,
+}
+
+struct NoConst;
+
+#[auto_default(prefer_new, assoc_const)]
+struct PreferNew {
+    a: Vec<u8>,
+    #[auto_default(prefer_new, with = Vec::new)]
+    b: Vec<u8>,
+}
+
+fn main() {}
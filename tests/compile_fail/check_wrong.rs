@@ -0,0 +1,25 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(check)]
+struct Settings {
+    volume: u8 = 9,
+    name: String,
+    items: Vec<u8>,
+}
+
+#[auto_default(check)]
+enum Event {
+    Click { x: u32 = 0, y: u32 },
+    #[auto_default(skip)]
+    Error { code: u32 },
+}
+
+#[auto_default(infer_literals, check)]
+struct Conflict {
+    a: u8 = 0,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+#![feature(default_field_values)]
+use auto_default::auto_default;
+
+#[auto_default(assoc_const, const_default_trait)]
+struct Container {
+    a: u8,
+}
+
+#[auto_default]
+struct Field {
+    #[auto_default(const_default_trait, assoc_const)]
+    a: u8,
+}
+
+#[auto_default(impl_const_default)]
+enum NoDefaultVariant {
+    A { a: u8 = 0 },
+}
+
+fn main() {}
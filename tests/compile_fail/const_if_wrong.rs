@@ -0,0 +1,28 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::{AutoDefault, auto_default};
+
+#[auto_default(const_if = feature)]
+struct NotString {
+    a: u8,
+}
+
+#[auto_default(const_if = "test", reset, self_test)]
+struct Methods {
+    a: u8,
+}
+
+#[auto_default(const_if = "test")]
+#[derive(Default)]
+struct Derived {
+    a: u8,
+}
+
+#[derive(AutoDefault)]
+#[auto_default(const_if = "test")]
+struct Derive {
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use auto_default::construct;
+
+#[derive(Default)]
+struct User {
+    age: u8,
+}
+
+fn main() {
+    let _ = construct!(User);
+    let _ = construct!({ age: 4, .. });
+    let _ = construct!();
+}
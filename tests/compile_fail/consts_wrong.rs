@@ -0,0 +1,31 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+mod defaults {
+    pub const PORT: u16 = 8080;
+}
+
+#[auto_default(strict_consts = defaults)]
+struct Missing {
+    port: u16,
+    workers: u8,
+}
+
+#[auto_default(consts = defaults, strict_consts = defaults)]
+struct Both {
+    port: u16,
+}
+
+#[auto_default(consts = defaults, infer_literals, prefer_new)]
+struct Unused {
+    port: u16,
+}
+
+#[auto_default(consts)]
+struct NoModule {
+    port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![feature(default_field_values)]
+use auto_default::AutoDefault;
+
+#[derive(AutoDefault)]
+#[auto_default(reset, doc_defaults)]
+struct Unsupported {
+    a: u8,
+}
+
+#[derive(AutoDefault)]
+#[auto_default(impl_default)]
+struct Redundant {
+    a: u8,
+}
+
+fn main() {}
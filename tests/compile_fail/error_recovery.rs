@@ -0,0 +1,31 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// items are still emitted when there are errors,
+// so there are no "cannot find" errors where they are used
+
+#[auto_default(unknown)]
+struct Named {
+    field: u8,
+}
+
+#[auto_default]
+struct Tuple(#[allow(unused)] u8);
+
+#[auto_default]
+trait Trait {}
+
+#[auto_default]
+union Union {
+    a: u8,
+    b: u16,
+}
+
+fn main() {
+    let _ = Named { .. };
+    let _ = Tuple(1);
+    let _: Option<&dyn Trait> = None;
+    let _ = Union { a: 1 };
+}
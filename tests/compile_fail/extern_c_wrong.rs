@@ -0,0 +1,52 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(extern_c)]
+struct NotReprC {
+    a: u32,
+}
+
+#[auto_default(extern_c)]
+#[repr(C)]
+struct Skipped {
+    a: u32,
+    #[auto_default(skip)]
+    b: u32,
+}
+
+#[auto_default(extern_c)]
+#[repr(C)]
+struct Generic<T> {
+    a: Vec<T>,
+}
+
+#[auto_default(extern_c)]
+#[repr(C)]
+#[derive(Default)]
+enum Enum {
+    #[default]
+    A,
+}
+
+#[auto_default(extern_c = "not a name")]
+#[repr(C)]
+struct InvalidName {
+    a: u32,
+}
+
+#[auto_default(extern_c(name))]
+#[repr(C)]
+struct List {
+    a: u32,
+}
+
+#[auto_default(extern_c)]
+#[repr(C)]
+#[non_exhaustive]
+struct NonExhaustive {
+    a: u32,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(first_variant_default)]
+struct NotEnum {
+    a: u8,
+}
+
+#[auto_default(first_variant_default)]
+#[derive(Default)]
+enum AlreadyMarked {
+    A,
+    #[default]
+    B,
+}
+
+#[auto_default(first_variant_default)]
+enum Tuple {
+    A(u8),
+    B,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default]
+#[derive(Debug, SmartDefault)]
+struct Smart {
+    #[default(4)]
+    a: u8,
+}
+
+#[auto_default]
+#[derivative(Default)]
+struct Derived {
+    a: u8,
+}
+
+#[auto_default(from_derives)]
+#[derive(SmartDefault)]
+struct Both {
+    #[default(4)]
+    a: u8 = 5,
+}
+
+fn main() {}
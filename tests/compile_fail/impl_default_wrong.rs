@@ -0,0 +1,83 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(bound = "T: Default")]
+struct Unused<T> {
+    a: Vec<T>,
+}
+
+#[auto_default(impl_default, bound = T)]
+struct NotString<T> {
+    a: Vec<T>,
+}
+
+#[auto_default(impl_default)]
+enum NoDefault {
+    A { a: u32 },
+}
+
+struct NotDefault;
+
+#[auto_default(impl_default)]
+struct Skipped {
+    #[auto_default(skip)]
+    a: NotDefault,
+}
+
+// default field values must be `const`
+#[auto_default]
+struct NotImplDefault {
+    #[auto_default(runtime = String::from("a"))]
+    a: String,
+}
+
+#[auto_default(finalize = Self::check)]
+struct NoImplDefault {
+    a: u8,
+}
+
+// `Box::new` can't be called in `const`
+#[auto_default]
+struct NotImplDefaultBoxed {
+    #[auto_default(boxed = NotDefault)]
+    a: Box<dyn Send>,
+}
+
+#[auto_default(impl_default)]
+struct NotBox {
+    #[auto_default(boxed = u8)]
+    a: Option<u8>,
+}
+
+// only the fields before it can be used
+#[auto_default(impl_default)]
+struct LaterField {
+    #[auto_default(value = b + 1)]
+    a: u8,
+    b: u8,
+}
+
+#[auto_default]
+struct NotImplDefaultValue {
+    a: u8,
+    #[auto_default(value = a + 1)]
+    b: u8,
+}
+
+// default field values are evaluated every time
+#[auto_default]
+struct NotImplDefaultLazy {
+    #[auto_default(lazy = String::from("a"))]
+    a: String,
+}
+
+// a `static` can't use the generic parameters of the item
+#[auto_default(impl_default)]
+struct GenericLazy<T> {
+    #[auto_default(lazy = Vec::new())]
+    a: Vec<T>,
+}
+
+fn main() {}
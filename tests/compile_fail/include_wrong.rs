@@ -0,0 +1,28 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default]
+enum Misplaced {
+    #[auto_default(include)]
+    A { a: u8 },
+}
+
+#[auto_default]
+struct Wrong {
+    #[auto_default(include, include)]
+    a: u8,
+    #[auto_default(include, skip)]
+    b: u8,
+}
+
+#[auto_default(skip_underscored)]
+struct Unused {
+    #[auto_default(include)]
+    a: u8,
+    #[auto_default(include)]
+    _b: u8 = 4,
+}
+
+fn main() {}
@@ -20,4 +20,16 @@ macro_rules! x {
 #[auto_default(arguments)]
 mod a {}
 
+#[auto_default]
+impl M {}
+
+#[auto_default]
+type Alias = M;
+
+#[auto_default]
+pub const unsafe extern "C" fn y() {}
+
+#[auto_default]
+const N: u8 = 0;
+
 fn main() {}
@@ -0,0 +1,16 @@
+#![feature(default_field_values)]
+use auto_default::auto_default;
+
+// Literals don't need `const Default`
+#[auto_default(infer_literals)]
+struct Literals {
+    port: u16,
+}
+
+#[auto_default]
+struct Config {
+    port: u16,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use auto_default::auto_default;
+
+#[auto_default]
+struct Config {
+    port: u16,
+    name: String,
+    retries: u8 = 3,
+}
+
+fn main() {}
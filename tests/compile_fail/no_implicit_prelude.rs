@@ -0,0 +1,22 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+// Errors of the macro don't need the prelude, or the `compile_error!` in scope
+
+#[no_implicit_prelude]
+mod hygiene {
+    use ::auto_default::auto_default;
+
+    #[allow(unused_macros)]
+    macro_rules! compile_error {
+        ($($tt:tt)*) => {};
+    }
+
+    #[auto_default(unknown)]
+    struct Config {
+        retries: u8,
+    }
+}
+
+fn main() {}
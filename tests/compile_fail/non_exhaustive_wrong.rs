@@ -0,0 +1,17 @@
+#![feature(default_field_values)]
+use auto_default::auto_default;
+
+#[auto_default(reset, self_test)]
+#[non_exhaustive]
+pub struct Methods {
+    a: u8,
+}
+
+#[auto_default]
+#[non_exhaustive]
+#[derive(Default)]
+pub struct Derived {
+    a: u8 = 4,
+}
+
+fn main() {}
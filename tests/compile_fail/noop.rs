@@ -0,0 +1,36 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use auto_default::auto_default;
+
+#[auto_default]
+struct Empty {}
+
+// no warning, since unit structs often come from generated code
+#[auto_default]
+struct Unit;
+
+#[auto_default]
+struct AllDefaults {
+    a: u8 = 1,
+    #[auto_default(skip)]
+    b: u8,
+}
+
+#[auto_default]
+enum NoNamedFields {
+    A,
+    B(u8),
+}
+
+#[auto_default]
+enum SkippedVariant {
+    #[auto_default(skip)]
+    A { a: u8 },
+}
+
+// warnings are only visible in the snapshot if compilation fails
+compile_error!("end of test");
+
+fn main() {}
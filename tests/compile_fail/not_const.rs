@@ -0,0 +1,31 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default]
+struct Strings {
+    a: String = std::string::String::from("hello"),
+    b: Option<String> = Some("hello".to_string()),
+    c: Vec<u8> = vec![],
+    // `const`
+    d: String = String::new(),
+    e: String = String::from("hello"),
+    f: Vec<u8> = Vec::with_capacity(4),
+}
+
+#[auto_default(from_derives)]
+#[derivative(Default)]
+struct Derived {
+    #[derivative(Default(value = "std::sync::Arc::new(4)"))]
+    a: std::sync::Arc<u8>,
+}
+
+// Not reported, since the values are only used by `impl Default`
+#[auto_default]
+#[non_exhaustive]
+pub struct NonExhaustive {
+    a: String = String::from("hello"),
+}
+
+fn main() {}
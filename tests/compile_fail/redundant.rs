@@ -0,0 +1,32 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(deny_redundant)]
+struct Denied {
+    a: u8 = Default::default(),
+    b: String = String::default(),
+    // not redundant
+    c: u8 = 4,
+}
+
+#[auto_default(warn_redundant)]
+struct Warned {
+    a: Vec<u8> = <Vec<u8>>::default(),
+    b: u8,
+}
+
+// `Default::default()` is not what the macro would add
+#[auto_default(deny_redundant, struct_default)]
+struct Other {
+    a: u8 = Default::default(),
+    b: Denied,
+}
+
+#[auto_default(deny_redundant, warn_redundant)]
+struct Both {
+    a: u8,
+}
+
+fn main() {}
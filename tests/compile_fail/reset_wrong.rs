@@ -0,0 +1,36 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use auto_default::auto_default;
+
+#[auto_default(reset)]
+enum Foo {
+    A { a: u8 },
+}
+
+#[auto_default(reset = true)]
+struct Bar {
+    a: u8,
+}
+
+#[auto_default(is_default)]
+struct NoReset {
+    #[auto_default(group = "network")]
+    a: u8,
+}
+
+#[auto_default(reset)]
+struct Groups {
+    #[auto_default(group = "b")]
+    a: u8,
+    b: u8,
+    #[auto_default(skip, group = "network")]
+    c: u8,
+    #[auto_default(group = "to_default")]
+    d: u8,
+    #[auto_default(group = "not a name", group = "e")]
+    e: u8,
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(self_test)]
+#[derive(Default, PartialEq)]
+struct Skipped {
+    a: u32,
+    #[auto_default(skip)]
+    b: u32,
+}
+
+#[auto_default(self_test)]
+#[derive(PartialEq)]
+enum NoDefault {
+    A { a: u32 },
+}
+
+#[auto_default(self_test)]
+#[derive(Default, PartialEq)]
+struct Generic<T> {
+    a: Vec<T>,
+}
+
+#[auto_default(validate_test)]
+struct ValidateSkipped {
+    #[auto_default(skip)]
+    a: u32,
+}
+
+#[auto_default(validate_test)]
+struct ValidateGeneric<T> {
+    a: Vec<T>,
+}
+
+fn main() {}
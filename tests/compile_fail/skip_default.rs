@@ -3,11 +3,22 @@
 #![feature(const_default)]
 use auto_default::auto_default;
 
-// invalid syntax of the skip attribute (#[auto_default(skip)])
+// the skip attribute does nothing (#[auto_default(skip)])
 #[auto_default]
 struct X {
     #[auto_default(skip)]
     a: () = (),
+    b: u8,
 }
 
+#[auto_default(allow_unused_skip)]
+struct Allowed {
+    #[auto_default(skip)]
+    a: () = (),
+    b: u8,
+}
+
+// warnings are only visible in the snapshot if compilation fails
+compile_error!("end of test");
+
 fn main() {}
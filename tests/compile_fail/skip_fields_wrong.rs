@@ -0,0 +1,16 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(skip_fields(prefix = raw, prefix = "a\\b", names(a, a, missing), other))]
+struct Struct {
+    a: u32,
+}
+
+#[auto_default(skip_fields = a)]
+struct Eq {
+    a: u32,
+}
+
+fn main() {}
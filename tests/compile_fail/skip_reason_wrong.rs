@@ -0,0 +1,20 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default]
+struct Foo {
+    #[auto_default(reason = "without skip")]
+    a: u32,
+    #[auto_default(skip, reason = 4)]
+    b: u32,
+    #[auto_default(skip, reason)]
+    c: u32,
+    #[auto_default(skip, reason = "first", reason = "second")]
+    d: u32,
+    #[auto_default(skip, reason = "has a default")]
+    e: u32 = 4,
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(alias = adskip)]
+struct Foo {
+    #[auto_default::with]
+    a: u32,
+    #[adskip = "reason"]
+    b: u32,
+    #[auto_default::skip]
+    #[adskip]
+    c: u32,
+}
+
+#[auto_default(alias = "adskip")]
+struct Bar {
+    a: u32,
+}
+
+fn main() {}
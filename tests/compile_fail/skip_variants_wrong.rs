@@ -0,0 +1,23 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(skip_variants(Tuple, Unit, Missing, Named, Named))]
+enum Enum {
+    Named { a: u32 },
+    Tuple(u32),
+    Unit,
+}
+
+#[auto_default(skip_variants(A))]
+struct Struct {
+    a: u32,
+}
+
+#[auto_default(skip_variants = A)]
+enum Eq {
+    A { a: u32 },
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `struct_default` is only allowed on fields that get a default value
+
+struct Y {
+    a: () = (),
+}
+
+#[auto_default]
+#[auto_default(struct_default)]
+struct X {
+    #[auto_default(skip, struct_default)]
+    a: (),
+    #[auto_default(struct_default)]
+    b: () = (),
+    #[auto_default(struct_default)]
+    #[auto_default(struct_default)]
+    c: Y,
+}
+
+#[auto_default]
+enum Foo {
+    #[auto_default(struct_default)]
+    A { a: () },
+}
+
+fn main() {}
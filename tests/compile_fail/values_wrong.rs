@@ -0,0 +1,34 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(values(a = 1, a = 2, missing = 3, b = 4))]
+struct Wrong {
+    a: u8,
+    b: u8 = 5,
+    #[auto_default(with = seven)]
+    c: u8,
+}
+
+#[auto_default(values(c = 8))]
+struct Conflict {
+    #[auto_default(with = seven)]
+    c: u8,
+}
+
+const fn seven() -> u8 {
+    7
+}
+
+#[auto_default(values(a = 1))]
+enum OnEnum {
+    #[auto_default(values(b = 2))]
+    Tuple(u8),
+    Named {
+        #[auto_default(values(a = 3))]
+        a: u8,
+    },
+}
+
+fn main() {}
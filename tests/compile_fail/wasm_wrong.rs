@@ -0,0 +1,35 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(wasm)]
+#[derive(Default)]
+enum Enum {
+    #[default]
+    A,
+}
+
+#[auto_default(wasm)]
+struct Generic<T> {
+    a: Vec<T>,
+}
+
+#[auto_default(wasm)]
+#[non_exhaustive]
+struct NonExhaustive {
+    a: u32,
+}
+
+#[auto_default(wasm = "new")]
+struct Eq {
+    a: u32,
+}
+
+#[auto_default]
+#[wasm_bindgen]
+struct Below {
+    a: u32,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(with_fn)]
+struct Skipped {
+    a: u32,
+    #[auto_default(skip)]
+    b: u32,
+}
+
+#[auto_default(with_fn, reset)]
+struct Both {
+    #[auto_default(skip)]
+    a: u32,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// invalid usages of `with = ...`
+
+const fn zero() -> u32 {
+    0
+}
+
+#[auto_default(with)]
+struct X {
+    #[auto_default(with = zero, struct_default)]
+    a: u32,
+    #[auto_default(with = zero)]
+    #[auto_default(with = zero)]
+    b: u32,
+    #[auto_default(skip, with = zero)]
+    c: u32,
+    #[auto_default(with = zero)]
+    d: u32 = 0,
+    #[auto_default(with(zero))]
+    e: u32,
+    #[auto_default(with =)]
+    f: u32,
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+#![feature(default_field_values)]
+use auto_default::auto_default;
+use const_default::ConstDefault;
+
+// `#[auto_default(const_default_trait)]` uses the `ConstDefault` trait of the `const-default` crate
+
+struct Vec2 {
+    x: f32,
+    y: f32,
+}
+
+impl ConstDefault for Vec2 {
+    const DEFAULT: Self = Self { x: 1.0, y: 2.0 };
+}
+
+#[auto_default(const_default_trait, impl_const_default)]
+struct Body {
+    position: Vec2,
+    mass: f32,
+    name: Option<&'static str> = Some("body"),
+}
+
+#[auto_default(infer_literals, impl_const_default)]
+struct Particle<T> {
+    #[auto_default(const_default_trait)]
+    position: Vec2,
+    charge: i8,
+    #[auto_default(skip)]
+    data: T,
+}
+
+const BODY: Body = Body::DEFAULT;
+const PARTICLE: Particle<Option<u8>> = Particle::DEFAULT;
+
+#[test]
+fn const_default_trait() {
+    assert_eq!((BODY.position.x, BODY.position.y), (1.0, 2.0));
+    assert_eq!(BODY.mass, 0.0);
+    assert_eq!(BODY.name, Some("body"));
+
+    let body = Body { mass: 4.0, .. };
+    assert_eq!((body.position.x, body.mass), (1.0, 4.0));
+
+    assert_eq!((PARTICLE.position.x, PARTICLE.position.y), (1.0, 2.0));
+    assert_eq!(PARTICLE.charge, 0);
+    assert_eq!(PARTICLE.data, None);
+}
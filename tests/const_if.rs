@@ -0,0 +1,67 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+// Integration tests are compiled with `--cfg test`
+
+use auto_default::auto_default;
+
+// Has the default field values
+#[auto_default(const_if = "test", infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Const {
+    name: String,
+    retries: u8 = 3,
+    ratio: f32,
+}
+
+// Has no default field values, only `impl Default`
+#[auto_default(const_if = "not(test)", infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Runtime {
+    name: String,
+    retries: u8 = 3,
+    ratio: f32,
+}
+
+#[auto_default(const_if = "all(test, not(test))")]
+#[derive(PartialEq, Debug)]
+enum Mode {
+    Fast,
+    #[default]
+    Custom {
+        level: u8 = 2,
+        names: Vec<u8>,
+    },
+}
+
+const CONST: Const = Const { .. };
+
+#[test]
+fn test() {
+    assert_eq!(
+        CONST,
+        Const {
+            name: String::new(),
+            retries: 3,
+            ratio: 0.0,
+        }
+    );
+    assert_eq!(Const::default(), CONST);
+    assert_eq!(
+        Runtime::default(),
+        Runtime {
+            name: String::new(),
+            retries: 3,
+            ratio: 0.0,
+        }
+    );
+    assert_eq!(
+        Mode::default(),
+        Mode::Custom {
+            level: 2,
+            names: Vec::new(),
+        }
+    );
+    assert_ne!(Mode::default(), Mode::Fast);
+}
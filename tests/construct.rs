@@ -0,0 +1,54 @@
+// `construct!` works without `#![feature(default_field_values)]`, and CI runs this test on stable
+
+use auto_default::construct;
+
+#[derive(Default, PartialEq, Debug)]
+struct User {
+    age: u8,
+    name: String,
+}
+
+mod nested {
+    #[derive(Default, PartialEq, Debug)]
+    pub struct Generic<T> {
+        pub items: Vec<T>,
+        pub count: usize,
+    }
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        construct!(User { age: 4, .. }),
+        User {
+            age: 4,
+            name: String::new()
+        }
+    );
+    assert_eq!(
+        construct!(User { .. }),
+        User {
+            age: 0,
+            name: String::new()
+        }
+    );
+    assert_eq!(
+        construct!(nested::Generic::<u8> { count: 2, .. }),
+        nested::Generic {
+            items: vec![],
+            count: 2
+        }
+    );
+    // an explicit base is left alone
+    let base = User {
+        age: 1,
+        name: "base".to_string(),
+    };
+    assert_eq!(
+        construct!(User { age: 2, ..base }),
+        User {
+            age: 2,
+            name: "base".to_string()
+        }
+    );
+}
@@ -0,0 +1,86 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::{AutoDefault, auto_default};
+
+// `#[auto_default(consts = defaults)]` gives each field the constant named after it
+
+mod defaults {
+    pub const PORT: u16 = 8080;
+    pub const HOST: &str = "localhost";
+    pub const TYPE: u8 = 2;
+    pub const LEVEL: u8 = 3;
+}
+
+#[auto_default(consts = defaults)]
+#[derive(PartialEq, Debug)]
+struct Server<T> {
+    port: u16,
+    host: &'static str,
+    r#type: u8,
+    // no constant, so it gets `Default::default()`
+    workers: u8,
+    items: Vec<T>,
+    // default values written on the field are kept
+    timeout: u32 = 30,
+}
+
+#[auto_default(strict_consts = crate::defaults)]
+struct Strict {
+    port: u16,
+    #[auto_default(skip)]
+    workers: u8,
+}
+
+#[auto_default(consts = defaults)]
+#[derive(PartialEq, Debug, Default)]
+enum Mode {
+    Fast,
+    #[default]
+    Custom {
+        level: u8,
+        retries: u8,
+    },
+}
+
+#[derive(AutoDefault, PartialEq, Debug)]
+#[auto_default(consts = defaults)]
+struct Derived {
+    host: &'static str,
+    name: String,
+}
+
+#[test]
+fn consts() {
+    assert_eq!(
+        Server::<u8> { .. },
+        Server {
+            port: 8080,
+            host: "localhost",
+            r#type: 2,
+            workers: 0,
+            items: Vec::new(),
+            timeout: 30,
+        }
+    );
+
+    let strict = Strict { workers: 4, .. };
+    assert_eq!((strict.port, strict.workers), (8080, 4));
+
+    assert_eq!(
+        Mode::default(),
+        Mode::Custom {
+            level: 3,
+            retries: 0
+        }
+    );
+    assert_ne!(Mode::default(), Mode::Fast);
+
+    assert_eq!(
+        Derived::default(),
+        Derived {
+            host: "localhost",
+            name: String::new(),
+        }
+    );
+}
@@ -0,0 +1,39 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+// `#[auto_default(debug)]` prints the expanded item, but does not change it
+
+use auto_default::auto_default;
+
+#[auto_default(debug)]
+#[derive(PartialEq, Debug)]
+struct X<T> {
+    /// Documented
+    #[auto_default(skip)]
+    skipped: T,
+    inserted: Option<T>,
+    custom: u8 = 4,
+}
+
+#[auto_default(debug, infer_literals)]
+#[derive(PartialEq, Debug)]
+#[repr(u8)]
+enum Foo {
+    A { a: u8 } = 1,
+    B(u8) = 2,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        X { skipped: (), .. },
+        X {
+            skipped: (),
+            inserted: None,
+            custom: 4
+        }
+    );
+    assert_eq!(Foo::A { .. }, Foo::A { a: 0 });
+    assert_ne!(Foo::B(1), Foo::A { .. });
+}
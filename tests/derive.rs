@@ -0,0 +1,104 @@
+#![feature(default_field_values)]
+use auto_default::AutoDefault;
+
+// `#[derive(AutoDefault)]` implements `Default` without adding default field values,
+// so `const_default` isn't needed
+
+#[derive(PartialEq, Debug)]
+struct NoDefault;
+
+#[derive(AutoDefault, PartialEq, Debug)]
+struct Config {
+    name: &'static str = "app",
+    port: u16,
+    tags: Vec<String>,
+    #[auto_default(runtime = vec![80, 443])]
+    ports: Vec<u16>,
+}
+
+#[derive(AutoDefault, PartialEq, Debug)]
+#[auto_default(infer_literals)]
+struct Inferred {
+    retries: u8,
+    verbose: bool,
+    ratio: f32,
+}
+
+#[derive(AutoDefault, PartialEq, Debug)]
+struct Generic<T, U> {
+    items: Vec<T>,
+    extra: Option<U>,
+}
+
+#[derive(AutoDefault, PartialEq, Debug)]
+enum Mode<T> {
+    Fast,
+    #[default]
+    Custom {
+        items: Vec<T>,
+        level: u8 = 3,
+    },
+}
+
+#[derive(AutoDefault)]
+#[auto_default(finalize = Validated::validate)]
+struct Validated {
+    low: u8 = 10,
+    high: u8,
+}
+
+impl Validated {
+    fn validate(&mut self) {
+        self.high = self.high.max(self.low);
+    }
+}
+
+#[test]
+fn derive() {
+    assert_eq!(
+        Config::default(),
+        Config {
+            name: "app",
+            port: 0,
+            tags: Vec::new(),
+            ports: vec![80, 443],
+        }
+    );
+    assert_eq!(
+        Inferred::default(),
+        Inferred {
+            retries: 0,
+            verbose: false,
+            ratio: 0.0,
+        }
+    );
+
+    // Only the types of the fields get a bound, so `U` doesn't have to implement `Default`
+    assert_eq!(
+        Generic::<u8, NoDefault>::default(),
+        Generic {
+            items: Vec::new(),
+            extra: None,
+        }
+    );
+    assert_eq!(
+        Mode::<u8>::default(),
+        Mode::Custom {
+            items: Vec::new(),
+            level: 3,
+        }
+    );
+    assert_ne!(Mode::<u8>::default(), Mode::Fast);
+
+    let validated = Validated::default();
+    assert_eq!((validated.low, validated.high), (10, 10));
+
+    // The item is not changed, so the fields without a default value must be written
+    let config = Config {
+        port: 8080,
+        tags: Vec::new(),
+        ports: Vec::new(),
+        ..
+    };
+    assert_eq!(config.name, "app");
+}
@@ -0,0 +1,45 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![deny(missing_docs)]
+
+//! Every field gets documentation, so `missing_docs` is satisfied
+
+use auto_default::auto_default;
+
+/// Struct
+#[auto_default(doc_defaults)]
+#[derive(PartialEq, Debug)]
+pub struct X {
+    pub inserted: u32,
+    pub custom: Option<u32> = Some(4),
+    /// Documented
+    pub documented: bool,
+}
+
+/// Enum
+#[auto_default(doc_defaults, infer_literals)]
+#[derive(PartialEq, Debug)]
+pub enum Foo {
+    /// Variant
+    A { literal: f32, array: [u8; 2] = [1, 2] },
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        X { .. },
+        X {
+            inserted: 0,
+            custom: Some(4),
+            documented: false
+        }
+    );
+    assert_eq!(
+        Foo::A { .. },
+        Foo::A {
+            literal: 0.0,
+            array: [1, 2]
+        }
+    );
+}
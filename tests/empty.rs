@@ -4,3 +4,12 @@ use auto_default::auto_default;
 
 #[auto_default]
 struct X {}
+
+#[auto_default]
+struct Unit;
+
+#[auto_default]
+#[derive(Debug)]
+pub(crate) struct UnitWhere
+where
+    u8: Copy;
@@ -0,0 +1,54 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `#[auto_default(extern_c)]` generates a `#[no_mangle] extern "C"` function that returns
+// `Foo { .. }`, so C callers get the same default values
+
+#[auto_default(extern_c)]
+#[repr(C)]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Config {
+    port: u16 = 8080,
+    retries: u8 = 3,
+    verbose: bool,
+}
+
+#[auto_default(extern_c = "limits_new")]
+#[repr(C, align(8))]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Limits {
+    max_connections: u32 = 64,
+    timeout_ms: u64 = 5000,
+}
+
+// Called like C would call them, through the symbols
+mod ffi {
+    unsafe extern "C" {
+        pub fn config_default() -> super::Config;
+        pub fn limits_new() -> super::Limits;
+    }
+}
+
+#[test]
+fn extern_c() {
+    assert_eq!(
+        config_default(),
+        Config {
+            port: 8080,
+            retries: 3,
+            verbose: false,
+        }
+    );
+    assert_eq!(
+        limits_new(),
+        Limits {
+            max_connections: 64,
+            timeout_ms: 5000,
+        }
+    );
+
+    assert_eq!(unsafe { ffi::config_default() }, Config { .. });
+    assert_eq!(unsafe { ffi::limits_new() }, Limits { .. });
+}
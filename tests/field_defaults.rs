@@ -0,0 +1,56 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![deny(missing_docs)]
+
+//! `#[auto_default(field_defaults)]` generates a `FIELD_DEFAULTS` constant
+
+use auto_default::auto_default;
+
+/// Settings
+#[auto_default(field_defaults, infer_literals)]
+pub struct Settings {
+    /// Volume
+    pub volume: u8,
+    /// Name
+    pub name: String,
+    /// Theme
+    pub theme: &'static str = "dark",
+    /// Id
+    #[auto_default(skip)]
+    pub id: u64,
+    /// Keyword
+    pub r#type: bool,
+}
+
+#[auto_default(field_defaults)]
+#[allow(dead_code)]
+struct WithCfg<T> {
+    #[cfg(any())]
+    disabled: u8,
+    #[cfg(not(any()))]
+    #[cfg(not(all(any(), any())))]
+    enabled: Option<T>,
+    always: u8,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        Settings::FIELD_DEFAULTS,
+        [
+            ("volume", "0"),
+            ("name", "::core::default::Default::default()"),
+            ("theme", "custom"),
+            ("id", "required"),
+            ("type", "false"),
+        ]
+    );
+    assert_eq!(
+        WithCfg::<u8>::FIELD_DEFAULTS,
+        [
+            ("enabled", "::core::default::Default::default()"),
+            ("always", "::core::default::Default::default()"),
+        ]
+    );
+}
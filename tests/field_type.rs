@@ -0,0 +1,29 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use std::marker::PhantomData;
+
+use auto_default::auto_default;
+
+// commas inside of `<...>` are part of the type
+
+#[auto_default]
+#[derive(PartialEq, Debug)]
+struct X {
+    assoc: Option<Result<u8, ()>>,
+    fn_ptr: PhantomData<fn(u8, u8) -> u8>,
+    last: Option<(u8, u8)>,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        X { .. },
+        X {
+            assoc: None,
+            fn_ptr: PhantomData,
+            last: None,
+        }
+    );
+}
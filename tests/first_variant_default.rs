@@ -0,0 +1,66 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::{AutoDefault, auto_default};
+
+// `#[auto_default(first_variant_default)]` makes the first variant the default,
+// without naming it
+
+#[auto_default(first_variant_default)]
+#[derive(PartialEq, Debug)]
+enum Shape {
+    Circle { radius: f32, filled: bool = true },
+    Square { side: f32 },
+}
+
+#[auto_default(first_variant_default)]
+#[derive(PartialEq, Debug)]
+enum Level {
+    Low,
+    High,
+}
+
+// With `#[derive(Default)]`, the first variant is marked `#[default]`
+#[auto_default(first_variant_default)]
+#[derive(Default, PartialEq, Debug)]
+enum Mode {
+    Auto { retries: u8 },
+    Manual,
+}
+
+// The fields of a skipped first variant get `Default::default()` from `impl Default`
+#[auto_default(first_variant_default)]
+#[derive(PartialEq, Debug)]
+enum Token<T> {
+    #[auto_default(skip)]
+    Value { value: T, len: usize = 1 },
+    End,
+}
+
+#[derive(AutoDefault, PartialEq, Debug)]
+#[auto_default(first_variant_default)]
+enum Derived {
+    Start { count: u32 = 3 },
+    Stop,
+}
+
+#[test]
+fn first_variant_default() {
+    assert_eq!(
+        Shape::default(),
+        Shape::Circle {
+            radius: 0.0,
+            filled: true
+        }
+    );
+    assert_eq!(Level::default(), Level::Low);
+    assert_eq!(Mode::default(), Mode::Auto { retries: 0 });
+    assert_eq!(Token::<u8>::default(), Token::Value { value: 0, len: 1 });
+    assert_eq!(Derived::default(), Derived::Start { count: 3 });
+
+    assert_ne!(Shape::default(), Shape::Square { side: 0.0 });
+    assert_ne!(Level::default(), Level::High);
+    assert_ne!(Mode::default(), Mode::Manual);
+    assert_ne!(Token::<u8>::default(), Token::End);
+    assert_ne!(Derived::default(), Derived::Stop);
+}
@@ -0,0 +1,47 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use std::time::Duration;
+
+use auto_default::auto_default;
+
+#[auto_default(from_derives)]
+#[derive(Debug, PartialEq, SmartDefault)]
+struct Config {
+    #[default(8080)]
+    port: u16,
+    #[default(_code = "Duration::from_secs(30)")]
+    timeout: Duration,
+    verbose: bool,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        Config::default(),
+        Config {
+            port: 8080,
+            timeout: Duration::from_secs(30),
+            verbose: false,
+        }
+    );
+    assert_eq!(Config { .. }, Config::default());
+}
+
+// `vec!` is not `const`, so it is set by the implementation of `Default`
+#[auto_default(from_derives, impl_default)]
+#[derive(Debug, SmartDefault)]
+struct Runtime {
+    #[default(_code = "u8::MAX")]
+    max: u8,
+    #[auto_default(runtime = vec![1])]
+    retries: Vec<u8>,
+}
+
+#[test]
+fn runtime() {
+    let runtime = Runtime::default();
+    assert_eq!(runtime.max, u8::MAX);
+    assert_eq!(runtime.retries, [1]);
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "full-parse")]
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// Fragments of `macro_rules!` such as `$vis:vis` and `$meta:meta` are wrapped in invisible groups
+
+macro_rules! item {
+    ($vis:vis $name:ident<$t:ident> {
+        $($(#[$meta:meta])* $field_vis:vis $field:ident: $ty:ty),*
+    }) => {
+        #[auto_default(is_default)]
+        #[derive(PartialEq, Debug)]
+        $vis struct $name<$t: PartialEq> where $t: Clone {
+            $($(#[$meta])* $field_vis $field: $ty),*
+        }
+    };
+}
+
+item!(pub Foo<T> {
+    pub a: u8,
+    #[auto_default(skip)]
+    b: Vec<T>
+});
+
+#[test]
+fn test() {
+    let foo = Foo::<u8> { b: vec![1], .. };
+    assert!(foo.is_default());
+    assert_eq!(foo, Foo { a: 0, b: vec![1] });
+}
@@ -0,0 +1,178 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `#[auto_default(impl_default)]` implements `Default` with only the bounds that are needed
+
+#[derive(PartialEq, Debug)]
+struct NoDefault;
+
+#[auto_default(impl_default)]
+#[derive(PartialEq, Debug)]
+struct Generic<T, U>
+where
+    T: PartialEq,
+{
+    items: Vec<T>,
+    #[auto_default(skip)]
+    extra: Option<U>,
+    #[auto_default(skip)]
+    count: u8,
+    #[cfg(any())]
+    #[auto_default(skip)]
+    disabled: U,
+}
+
+#[auto_default(impl_default, bound = "T: Clone")]
+#[derive(PartialEq, Debug)]
+struct Bound<T: Clone> {
+    items: Vec<T>,
+}
+
+#[auto_default(impl_default)]
+#[derive(PartialEq, Debug)]
+enum Mode<T> {
+    Fast,
+    #[default]
+    Custom {
+        items: Vec<T>,
+        #[auto_default(skip)]
+        level: u8,
+    },
+}
+
+// `runtime = ...` values don't need to be `const`, and their types don't need `Default`
+#[auto_default(impl_default)]
+#[derive(PartialEq, Debug)]
+struct Runtime {
+    #[auto_default(runtime = Box::new(4))]
+    backend: Box<u8>,
+    #[auto_default(runtime = std::collections::HashMap::from([(1, 2)]))]
+    map: std::collections::HashMap<u8, u8>,
+    #[auto_default(runtime = NoDefault)]
+    marker: NoDefault,
+    count: u8,
+}
+
+// `boxed = Type` creates the default value of `Type` in a `Box`, `Arc` or `Rc`
+trait Plugin {
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Default)]
+struct Logger;
+
+impl Plugin for Logger {
+    fn name(&self) -> &'static str {
+        "logger"
+    }
+}
+
+#[auto_default(impl_default)]
+struct Plugins {
+    #[auto_default(boxed = Logger)]
+    boxed: Box<dyn Plugin>,
+    #[auto_default(boxed = Logger)]
+    shared: std::sync::Arc<dyn Plugin + Send + Sync>,
+    count: u8,
+}
+
+// `finalize` runs after the fields get their default values
+#[auto_default(impl_default, finalize = Self::update_total)]
+#[derive(PartialEq, Debug)]
+struct Finalized {
+    #[auto_default(runtime = vec![1, 2])]
+    items: Vec<u8>,
+    #[auto_default(skip)]
+    total: u8,
+}
+
+impl Finalized {
+    fn update_total(&mut self) {
+        self.total = self.items.iter().sum();
+    }
+}
+
+// `value = ...` can use the default values of the fields before it
+#[auto_default(impl_default, finalize = Self::check)]
+#[derive(PartialEq, Debug)]
+struct Sizes {
+    buffer_size: usize = 1024,
+    #[auto_default(value = buffer_size * 2)]
+    cache_size: usize,
+    #[auto_default(runtime = format!("{cache_size} bytes"))]
+    label: String,
+    #[auto_default(value = label.len() + buffer_size)]
+    total: usize,
+}
+
+impl Sizes {
+    fn check(&mut self) {
+        assert!(self.cache_size >= self.buffer_size);
+    }
+}
+
+#[auto_default(impl_default)]
+#[derive(PartialEq, Debug)]
+enum Unit {
+    #[default]
+    A,
+    B {
+        b: u8,
+    },
+}
+
+#[test]
+fn test() {
+    // `NoDefault` does not implement `Default`, but it is only used in `Vec<NoDefault>`
+    assert_eq!(
+        Generic::<NoDefault, u8>::default(),
+        Generic {
+            items: Vec::new(),
+            extra: None,
+            count: 0,
+        }
+    );
+    assert_eq!(Bound::<u8>::default(), Bound { items: Vec::new() });
+    assert_eq!(
+        Mode::<NoDefault>::default(),
+        Mode::Custom {
+            items: Vec::new(),
+            level: 0
+        }
+    );
+    assert_eq!(
+        Runtime::default(),
+        Runtime {
+            backend: Box::new(4),
+            map: std::collections::HashMap::from([(1, 2)]),
+            marker: NoDefault,
+            count: 0,
+        }
+    );
+    assert_eq!(
+        Finalized::default(),
+        Finalized {
+            items: vec![1, 2],
+            total: 3,
+        }
+    );
+    assert_eq!(Unit::default(), Unit::A);
+    assert_eq!(
+        Sizes::default(),
+        Sizes {
+            buffer_size: 1024,
+            cache_size: 2048,
+            label: "2048 bytes".to_string(),
+            total: 1034,
+        }
+    );
+
+    let plugins = Plugins::default();
+    assert_eq!(plugins.boxed.name(), "logger");
+    assert_eq!(plugins.shared.name(), "logger");
+    assert_eq!(plugins.count, 0);
+
+    let _ = (Mode::<NoDefault>::Fast, Unit::B { .. });
+}
@@ -0,0 +1,74 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `#[auto_default(include)]` adds a default value to a field that its variant
+// or the container skips
+
+#[auto_default(skip_variants(Custom))]
+#[derive(PartialEq, Debug)]
+enum Event {
+    #[auto_default(skip)]
+    Error {
+        code: u32,
+        #[auto_default(include)]
+        retries: u8,
+    },
+    Custom {
+        name: String,
+        #[auto_default(include, with = default_level)]
+        level: u8,
+    },
+}
+
+const fn default_level() -> u8 {
+    3
+}
+
+#[auto_default(skip_fields(prefix = "raw_"), skip_underscored)]
+#[derive(PartialEq, Debug)]
+struct Handles {
+    raw_fd: i32,
+    #[auto_default(include)]
+    raw_count: u8,
+    _marker: (),
+    #[auto_default(include)]
+    _id: u8,
+    name: String,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        Event::Error { code: 4, .. },
+        Event::Error {
+            code: 4,
+            retries: 0
+        }
+    );
+    assert_eq!(
+        Event::Custom {
+            name: String::new(),
+            ..
+        },
+        Event::Custom {
+            name: String::new(),
+            level: 3
+        }
+    );
+    assert_eq!(
+        Handles {
+            raw_fd: 1,
+            _marker: (),
+            ..
+        },
+        Handles {
+            raw_fd: 1,
+            raw_count: 0,
+            _marker: (),
+            _id: 0,
+            name: String::new(),
+        }
+    );
+}
@@ -0,0 +1,41 @@
+#![feature(default_field_values)]
+
+// `const_trait_impl` and `const_default` are not enabled:
+// the literals are const-evaluable without `const Default`
+
+use auto_default::auto_default;
+
+#[auto_default(infer_literals)]
+#[derive(PartialEq, Debug)]
+struct X {
+    unsigned: u64,
+    signed: i8,
+    pointer_sized: usize,
+    float: f32,
+    boolean: bool,
+    character: char,
+    custom: u8 = 4,
+}
+
+#[auto_default(infer_literals)]
+#[derive(PartialEq, Debug)]
+enum Foo {
+    A { a: f64, b: bool },
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        X { .. },
+        X {
+            unsigned: 0,
+            signed: 0,
+            pointer_sized: 0,
+            float: 0.0,
+            boolean: false,
+            character: '\0',
+            custom: 4,
+        }
+    );
+    assert_eq!(Foo::A { .. }, Foo::A { a: 0.0, b: false });
+}
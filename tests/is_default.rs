@@ -0,0 +1,49 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use auto_default::auto_default;
+
+const fn default() -> u8 {
+    9
+}
+
+#[auto_default(is_default)]
+struct Settings<T> {
+    volume: u8 = default(),
+    name: String,
+    // not compared, since it has no default value
+    #[auto_default(skip)]
+    id: T,
+}
+
+#[test]
+fn test() {
+    let mut settings = Settings { id: 1, .. };
+    assert!(settings.is_default());
+
+    settings.volume = 1;
+    assert!(!settings.is_default());
+
+    settings.volume = 9;
+    settings.name.push('a');
+    assert!(!settings.is_default());
+
+    settings.name.clear();
+    settings.id = 2;
+    assert!(settings.is_default());
+}
+
+#[auto_default(is_default)]
+struct Generic<T: Copy> {
+    value: Option<T>,
+}
+
+#[test]
+fn generic() {
+    let mut generic = Generic::<u8> { .. };
+    assert!(generic.is_default());
+
+    generic.value = Some(1);
+    assert!(!generic.is_default());
+}
@@ -0,0 +1,50 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use auto_default::auto_default_item;
+
+auto_default_item! {
+    #[derive(PartialEq, Debug)]
+    #[auto_default(infer_literals)]
+    struct User {
+        age: u8,
+        name: String,
+        #[auto_default(skip)]
+        id: u32,
+    }
+}
+
+macro_rules! settings {
+    ($name:ident { $($field:ident: $ty:ty),* }) => {
+        auto_default_item! {
+            #[auto_default(is_default)]
+            struct $name {
+                $($field: $ty),*
+            }
+        }
+    };
+}
+
+settings!(Settings {
+    volume: u8,
+    muted: bool
+});
+
+#[test]
+fn test() {
+    assert_eq!(
+        User { id: 1, .. },
+        User {
+            age: 0,
+            name: String::new(),
+            id: 1
+        }
+    );
+
+    let mut settings = Settings { .. };
+    assert!(settings.is_default());
+    settings.muted = true;
+    assert!(!settings.is_default());
+    assert_eq!(settings.volume, 0);
+}
@@ -0,0 +1,38 @@
+#![feature(default_field_values)]
+
+// `const_trait_impl` and `const_default` are not enabled:
+// the constructors are const-evaluable without `const Default`
+
+use std::marker::PhantomData;
+
+use auto_default::auto_default;
+
+#[auto_default(known_types, infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Config<T> {
+    name: Option<String>,
+    items: Vec<T>,
+    path: String,
+    theme: &'static str,
+    marker: PhantomData<T>,
+    retries: u8,
+    custom: Option<u8> = Some(4),
+}
+
+static CONFIG: Config<u8> = Config { .. };
+
+#[test]
+fn test() {
+    assert_eq!(
+        CONFIG,
+        Config {
+            name: None,
+            items: Vec::new(),
+            path: String::new(),
+            theme: "",
+            marker: PhantomData,
+            retries: 0,
+            custom: Some(4),
+        }
+    );
+}
@@ -0,0 +1,59 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use auto_default::{AutoDefault, auto_default};
+
+// `lazy = expr` evaluates `expr` once, and `impl Default` clones the value after that
+
+static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+fn build_template() -> BTreeMap<String, String> {
+    BUILDS.fetch_add(1, Ordering::Relaxed);
+    BTreeMap::from([("greeting".to_string(), "hello {name}".to_string())])
+}
+
+#[auto_default(impl_default)]
+#[derive(PartialEq, Debug)]
+struct Renderer<T> {
+    #[auto_default(lazy = build_template())]
+    template: BTreeMap<String, String>,
+    // the type of a `lazy` field can't use `T`, but other fields can
+    items: Vec<T>,
+}
+
+static WORDS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(AutoDefault, PartialEq, Debug)]
+struct Dictionary {
+    #[auto_default(lazy = {
+        WORDS.fetch_add(1, Ordering::Relaxed);
+        vec!["a".to_string(), "b".to_string()]
+    })]
+    words: Vec<String>,
+    count: usize,
+}
+
+#[test]
+fn lazy() {
+    let first = Renderer::<u8>::default();
+    let second = Renderer::<u16>::default();
+    assert_eq!(first.template, build_template());
+    assert_eq!(first.template, second.template);
+    assert!(first.items.is_empty() && second.items.is_empty());
+    // once for the `static`, and once above
+    assert_eq!(BUILDS.load(Ordering::Relaxed), 2);
+
+    for _ in 0..3 {
+        assert_eq!(
+            Dictionary::default(),
+            Dictionary {
+                words: vec!["a".to_string(), "b".to_string()],
+                count: 0,
+            }
+        );
+    }
+    assert_eq!(WORDS.load(Ordering::Relaxed), 1);
+}
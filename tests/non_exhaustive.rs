@@ -0,0 +1,42 @@
+#![feature(default_field_values)]
+
+// A `#[non_exhaustive]` struct can't have default field values,
+// so they are used by the implementation of `Default` instead
+
+mod config {
+    use auto_default::auto_default;
+
+    #[auto_default(infer_literals)]
+    #[non_exhaustive]
+    #[derive(PartialEq, Debug)]
+    pub struct Config {
+        pub port: u16 = 8080,
+        pub verbose: bool,
+        pub name: String,
+        #[auto_default(skip)]
+        pub retries: u8,
+    }
+
+    #[auto_default]
+    #[non_exhaustive]
+    #[derive(PartialEq, Debug)]
+    pub struct Generic<T> {
+        pub items: Vec<T>,
+        pub extra: Option<T> = None,
+    }
+}
+
+use config::{Config, Generic};
+
+#[test]
+fn non_exhaustive() {
+    let config = Config::default();
+    assert_eq!(config.port, 8080);
+    assert!(!config.verbose);
+    assert_eq!(config.name, "");
+    assert_eq!(config.retries, 0);
+
+    let generic = Generic::<u8>::default();
+    assert_eq!(generic.items, []);
+    assert_eq!(generic.extra, None);
+}
@@ -0,0 +1,28 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(normalize, doc_defaults)]
+#[derive(PartialEq, Debug)]
+struct User<T> {
+    age: u8 = Default::default(),
+    name: String = String::default(),
+    items: Vec<T> = <Vec<T> as Default>::default(),
+    is_admin: bool = true,
+    level: u32,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        User::<u8> { .. },
+        User {
+            age: 0,
+            name: String::new(),
+            items: Vec::new(),
+            is_admin: true,
+            level: 0,
+        }
+    );
+}
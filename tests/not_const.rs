@@ -0,0 +1,33 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![feature(const_convert)]
+
+use auto_default::auto_default;
+
+mod boxed {
+    #[derive(Debug, PartialEq)]
+    pub struct Box(pub u8);
+
+    impl Box {
+        pub const fn new() -> Self {
+            Self(4)
+        }
+    }
+}
+
+use boxed::Box;
+
+// Calls that look like they are not `const`, but are
+#[auto_default]
+struct Values {
+    a: u16 = 3u8.into(),
+    b: Box = Box::new(),
+}
+
+#[test]
+fn test() {
+    let values = Values { .. };
+    assert_eq!(values.a, 3);
+    assert_eq!(values.b, Box(4));
+}
@@ -0,0 +1,36 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `macro_rules!` passes fragments such as `$vis:vis` and `$ty:ty` in groups without delimiters
+
+macro_rules! item {
+    ($vis:vis struct $name:ident { $($field_vis:vis $field:ident: $ty:ty),* $(,)? }) => {
+        #[auto_default(infer_literals)]
+        #[derive(Debug)]
+        $vis struct $name {
+            $($field_vis $field: $ty),*
+        }
+    };
+}
+
+item!(pub struct Public { pub a: u8, b: Vec<u8> });
+item!(struct Private { pub(crate) a: bool });
+
+macro_rules! whole {
+    ($item:item) => {
+        #[auto_default]
+        $item
+    };
+}
+
+whole!(
+    struct Whole {
+        a: u8,
+    }
+);
+
+fn main() {
+    let _ = (Public { .. }.b, Private { .. }.a, Whole { .. }.a, Public { .. }.a);
+}
@@ -0,0 +1,135 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+// Every path emitted by the macros must resolve without the prelude,
+// and without picking up items named `Default`, `core` or `default`
+
+#[no_implicit_prelude]
+mod hygiene {
+    use ::auto_default::{AutoDefault, auto_default};
+    use ::core::cmp::PartialEq;
+    use ::core::fmt::Debug;
+    use ::std::string::String;
+    use ::std::vec::Vec;
+
+    #[allow(dead_code)]
+    pub struct Default;
+
+    #[allow(dead_code)]
+    pub fn default() {}
+
+    #[allow(dead_code)]
+    mod core {}
+
+    #[auto_default(
+        impl_default,
+        is_default,
+        changed_fields,
+        field_defaults,
+        doc_defaults,
+        assert_default,
+        known_types,
+        infer_literals,
+        finalize = Config::validate
+    )]
+    #[derive(PartialEq, Debug)]
+    pub struct Config {
+        pub name: String,
+        pub retries: u8 = 3,
+        pub tags: Vec<String>,
+        pub ratio: f32,
+        pub id: ::core::option::Option<u32>,
+        #[auto_default(runtime = ::std::vec![80, 443])]
+        pub ports: Vec<u16>,
+        #[auto_default(value = retries * 2)]
+        pub max_retries: u8,
+        #[cfg(any())]
+        pub disabled: u8,
+        #[auto_default(skip)]
+        pub marker: ::core::marker::PhantomData<u8>,
+    }
+
+    impl Config {
+        fn validate(&mut self) {
+            if self.max_retries < self.retries {
+                self.max_retries = self.retries;
+            }
+        }
+    }
+
+    #[auto_default(prefer_new)]
+    #[derive(PartialEq, Debug)]
+    pub struct Buffers {
+        pub bytes: Vec<u8>,
+        #[auto_default(assoc_const)]
+        pub limit: Limit,
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Limit(u32);
+
+    impl Limit {
+        const DEFAULT: Self = Self(64);
+    }
+
+    #[auto_default(self_test, impl_default, reset, with_fn)]
+    pub struct Tested {
+        pub size: usize = 4,
+        pub name: &'static str = "tested",
+    }
+
+    #[auto_default(const_default_trait, impl_const_default)]
+    pub struct Constants {
+        pub count: u32,
+        pub flag: bool = true,
+    }
+
+    #[auto_default(first_variant_default, impl_default)]
+    #[derive(PartialEq, Debug)]
+    pub enum Mode {
+        Fast { level: u8 },
+        Slow,
+    }
+
+    #[derive(AutoDefault, PartialEq, Debug)]
+    #[auto_default(bound = "T: ::core::default::Default")]
+    pub struct Derived<T> {
+        pub items: Vec<T>,
+        pub label: &'static str = "derived",
+    }
+
+    pub fn check() {
+        let config = <Config as ::core::default::Default>::default();
+        ::core::assert_eq!(config.max_retries, 6);
+        ::core::assert!(config.is_default());
+        ::core::assert_eq!(
+            ::core::iter::Iterator::count(config.changed_fields()),
+            0
+        );
+        ::core::assert!(Config::FIELD_DEFAULTS.len() > 0);
+
+        let mut tested = Tested::with(|tested| tested.size = 8);
+        tested.reset_size();
+        ::core::assert_eq!(tested.size, 4);
+
+        ::core::assert_eq!(Buffers { .. }.bytes.len(), 0);
+        ::core::assert_eq!(
+            <Constants as ::const_default::ConstDefault>::DEFAULT.count,
+            0
+        );
+        ::core::assert_eq!(
+            <Mode as ::core::default::Default>::default(),
+            Mode::Fast { level: 0 }
+        );
+        ::core::assert_ne!(Mode::Fast { .. }, Mode::Slow);
+        ::core::assert_eq!(
+            <Derived<u8> as ::core::default::Default>::default().label,
+            "derived"
+        );
+    }
+}
+
+fn main() {
+    hygiene::check();
+}
@@ -0,0 +1,83 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use std::marker::PhantomData;
+
+use auto_default::auto_default;
+
+// `PhantomData` fields get `PhantomData` even when the container or the variant skips them,
+// since skipping them only means that `Foo { .. }` can't be written
+
+#[auto_default(skip_underscored)]
+#[derive(PartialEq, Debug)]
+struct Handle<T> {
+    _raw: u32,
+    _marker: PhantomData<T>,
+    id: u32,
+}
+
+#[auto_default]
+#[derive(PartialEq, Debug)]
+enum Message<T> {
+    #[auto_default(skip)]
+    Data {
+        len: usize,
+        marker: PhantomData<fn() -> T>,
+    },
+}
+
+// The skip of the field itself is kept
+#[auto_default]
+#[derive(PartialEq, Debug)]
+struct Explicit<T> {
+    #[auto_default(skip)]
+    marker: PhantomData<T>,
+    id: u32,
+}
+
+// `skip_phantom_data` skips them like the other fields
+#[auto_default(skip_underscored, skip_phantom_data)]
+#[derive(PartialEq, Debug)]
+struct Skipped<T> {
+    _marker: PhantomData<T>,
+    id: u32,
+}
+
+#[test]
+fn phantom_data() {
+    assert_eq!(
+        Handle::<u8> { _raw: 4, .. },
+        Handle {
+            _raw: 4,
+            _marker: PhantomData,
+            id: 0,
+        }
+    );
+    assert_eq!(
+        Message::<u8>::Data { len: 3, .. },
+        Message::Data {
+            len: 3,
+            marker: PhantomData,
+        }
+    );
+    assert_eq!(
+        Explicit::<u8> {
+            marker: PhantomData,
+            ..
+        },
+        Explicit {
+            marker: PhantomData,
+            id: 0,
+        }
+    );
+    assert_eq!(
+        Skipped::<u8> {
+            _marker: PhantomData,
+            ..
+        },
+        Skipped {
+            _marker: PhantomData,
+            id: 0,
+        }
+    );
+}
@@ -0,0 +1,59 @@
+#![feature(default_field_values)]
+
+// `const_trait_impl` and `const_default` are not enabled:
+// `<Type>::new()` is const-evaluable if `new` is a `const fn`
+
+use std::collections::BTreeMap;
+
+use auto_default::auto_default;
+
+#[derive(PartialEq, Debug)]
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    const fn new() -> Self {
+        Self { count: 1 }
+    }
+}
+
+#[auto_default(infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Stats {
+    #[auto_default(prefer_new)]
+    counter: Counter,
+    #[auto_default(prefer_new)]
+    names: BTreeMap<u8, String>,
+    total: u64,
+}
+
+#[auto_default(prefer_new)]
+#[derive(PartialEq, Debug)]
+enum Log {
+    Lines {
+        lines: Vec<String>,
+        counter: Counter,
+    },
+}
+
+const STATS: Stats = Stats { .. };
+
+#[test]
+fn test() {
+    assert_eq!(
+        STATS,
+        Stats {
+            counter: Counter { count: 1 },
+            names: BTreeMap::new(),
+            total: 0,
+        }
+    );
+    assert_eq!(
+        Log::Lines { .. },
+        Log::Lines {
+            lines: Vec::new(),
+            counter: Counter { count: 1 },
+        }
+    );
+}
@@ -0,0 +1,105 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![deny(missing_docs)]
+
+//! `#[auto_default(reset)]` generates methods which reset fields to their defaults
+
+use auto_default::auto_default;
+
+/// Settings
+#[auto_default(reset, infer_literals)]
+#[derive(PartialEq, Debug)]
+pub struct Settings {
+    /// Volume
+    pub volume: u8 = 50,
+    /// Name
+    pub name: String,
+    /// Keyword
+    pub r#type: bool,
+}
+
+#[auto_default(reset)]
+#[derive(PartialEq, Debug)]
+struct Generic<'a, T: Copy = u8, const N: usize = 2>
+where
+    T: Clone,
+{
+    items: [u8; N] = [1; N],
+    name: &'a str = "generic",
+    count: Option<T>,
+}
+
+/// Settings with sections
+#[auto_default(reset)]
+#[derive(PartialEq, Debug)]
+pub struct Sections {
+    /// Host
+    #[auto_default(group = "network")]
+    pub host: &'static str = "localhost",
+    /// Port
+    #[auto_default(group = "network")]
+    pub port: u16 = 8080,
+    /// Theme
+    #[auto_default(group = "appearance")]
+    pub theme: &'static str = "dark",
+    /// Font size
+    pub font_size: u8 = 12,
+}
+
+#[test]
+fn test() {
+    let mut settings = Settings {
+        volume: 10,
+        name: "custom".to_string(),
+        r#type: true,
+    };
+
+    settings.reset_volume();
+    assert_eq!(settings.volume, 50);
+    assert_eq!(settings.name, "custom");
+
+    settings.reset_type();
+    assert!(!settings.r#type);
+
+    settings.reset_name();
+    assert_eq!(settings.name, "");
+
+    settings.name = "custom".to_string();
+    settings.volume = 3;
+    settings.reset_to_default();
+    assert_eq!(settings, Settings { .. });
+
+    let mut generic = Generic::<u16, 3> {
+        items: [4, 5, 6],
+        name: "custom",
+        count: Some(4),
+    };
+    generic.reset_count();
+    assert_eq!(generic.count, None);
+    generic.reset_to_default();
+    assert_eq!(
+        generic,
+        Generic {
+            items: [1; 3],
+            name: "generic",
+            count: None
+        }
+    );
+
+    let mut sections = Sections {
+        host: "example.com",
+        port: 443,
+        theme: "light",
+        font_size: 16,
+    };
+    sections.reset_network();
+    assert_eq!((sections.host, sections.port), ("localhost", 8080));
+    assert_eq!(sections.theme, "light");
+
+    sections.reset_group("unknown");
+    assert_eq!(sections.theme, "light");
+    sections.reset_group("appearance");
+    assert_eq!(sections.theme, "dark");
+    assert_eq!(sections.font_size, 16);
+}
@@ -0,0 +1,42 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `#[auto_default(self_test)]` generates a test that checks `Foo { .. } == Foo::default()`
+
+#[auto_default(self_test, infer_literals)]
+#[derive(Default, PartialEq)]
+struct HttpConfig {
+    retries: u8,
+    verbose: bool,
+}
+
+#[auto_default(self_test)]
+#[derive(Default, PartialEq)]
+enum Mode {
+    Fast,
+    #[default]
+    Custom {
+        level: u8,
+    },
+}
+
+#[auto_default(self_test)]
+#[derive(Default, PartialEq)]
+enum Unit {
+    #[default]
+    A,
+    B {
+        b: u8,
+    },
+}
+
+#[test]
+fn generates_tests() {
+    auto_default_self_test_http_config();
+    auto_default_self_test_mode();
+    auto_default_self_test_unit();
+
+    let _ = (Mode::Fast, Unit::B { .. }, HttpConfig { .. }.retries);
+}
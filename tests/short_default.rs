@@ -0,0 +1,30 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(short_default, reset)]
+#[derive(PartialEq, Debug)]
+struct User {
+    age: u8,
+    name: String,
+    is_admin: bool = true,
+}
+
+#[test]
+fn test() {
+    let mut user = User {
+        age: 4,
+        name: "x".to_string(),
+        is_admin: false,
+    };
+    user.reset_to_default();
+    assert_eq!(
+        user,
+        User {
+            age: 0,
+            name: String::new(),
+            is_admin: true
+        }
+    );
+}
@@ -0,0 +1,76 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `#[auto_default(skip_fields(...))]` skips fields by their name
+
+#[auto_default(skip_fields(prefix = "raw_", prefix = r"ptr_", names(handle, r#type)))]
+#[derive(PartialEq, Debug)]
+struct Ffi {
+    raw_flags: u32,
+    ptr_data: usize,
+    handle: i32,
+    r#type: u8,
+    #[auto_default(with = seven)]
+    raw_version: u32,
+    size: u32,
+}
+
+const fn seven() -> u32 {
+    7
+}
+
+#[auto_default(skip_fields(names(fd)))]
+#[derive(PartialEq, Debug)]
+enum Resource {
+    File { fd: i32, offset: u64 },
+}
+
+// `#[auto_default(skip_underscored)]` skips marker fields, such as of sealed structs
+#[auto_default(skip_underscored)]
+#[derive(PartialEq, Debug)]
+struct Sealed {
+    _private: (),
+    _marker: std::marker::PhantomData<u8>,
+    _custom: u8 = 4,
+    size: u32,
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        Ffi {
+            raw_flags: 1,
+            ptr_data: 2,
+            handle: 3,
+            r#type: 4,
+            ..
+        },
+        Ffi {
+            raw_flags: 1,
+            ptr_data: 2,
+            handle: 3,
+            r#type: 4,
+            raw_version: 7,
+            size: 0,
+        }
+    );
+    assert_eq!(
+        Sealed {
+            _private: (),
+            _marker: std::marker::PhantomData,
+            ..
+        },
+        Sealed {
+            _private: (),
+            _marker: std::marker::PhantomData,
+            _custom: 4,
+            size: 0,
+        }
+    );
+    assert_eq!(
+        Resource::File { fd: 3, .. },
+        Resource::File { fd: 3, offset: 0 }
+    );
+}
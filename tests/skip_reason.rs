@@ -0,0 +1,50 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![deny(missing_docs)]
+
+//! The reason of `#[auto_default(skip)]` documents the field, so `missing_docs` is satisfied
+
+use auto_default::auto_default;
+
+/// User
+#[auto_default]
+#[derive(PartialEq, Debug)]
+pub struct User {
+    #[auto_default(skip, reason = "assigned by the database")]
+    pub id: u64,
+    /// Documented
+    #[auto_default(skip)]
+    #[auto_default(reason = r#"has no "default" name"#)]
+    pub name: String,
+    /// Admin
+    pub is_admin: bool,
+}
+
+/// Shape
+#[auto_default]
+#[derive(PartialEq, Debug)]
+pub enum Shape {
+    #[auto_default(skip, reason = "size must be chosen")]
+    Square {
+        /// Size
+        size: u32,
+    },
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        User {
+            id: 1,
+            name: String::new(),
+            ..
+        },
+        User {
+            id: 1,
+            name: String::new(),
+            is_admin: false
+        }
+    );
+    assert_eq!(Shape::Square { size: 2 }, Shape::Square { size: 2 });
+}
@@ -0,0 +1,82 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![deny(missing_docs)]
+
+//! `#[auto_default::skip]` and the `alias` of `#[auto_default(skip)]`
+
+use auto_default::auto_default;
+
+/// User
+#[auto_default(alias = adskip)]
+#[derive(PartialEq, Debug)]
+pub struct User {
+    /// Id
+    #[auto_default::skip]
+    pub id: u64,
+    #[adskip(reason = "chosen by the user")]
+    pub name: String,
+    /// Admin
+    pub is_admin: bool,
+}
+
+/// Shape
+#[auto_default(alias = adskip)]
+#[derive(PartialEq, Debug)]
+pub enum Shape {
+    /// Square
+    #[adskip]
+    Square {
+        /// Size
+        size: u32,
+    },
+    /// Circle
+    Circle {
+        /// Radius
+        #[auto_default::skip(reason = "circles have no default radius")]
+        radius: u32,
+        /// Filled
+        filled: bool,
+    },
+}
+
+/// Settings
+#[auto_default(helper = adf)]
+#[derive(PartialEq, Debug)]
+pub struct Settings {
+    /// Id
+    #[adf(skip)]
+    pub id: u64,
+    /// Level
+    #[adf(with = seven)]
+    pub level: u8,
+}
+
+const fn seven() -> u8 {
+    7
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        User {
+            id: 1,
+            name: String::new(),
+            ..
+        },
+        User {
+            id: 1,
+            name: String::new(),
+            is_admin: false
+        }
+    );
+    assert_eq!(
+        Shape::Circle { radius: 2, .. },
+        Shape::Circle {
+            radius: 2,
+            filled: false
+        }
+    );
+    assert_eq!(Shape::Square { size: 2 }, Shape::Square { size: 2 });
+    assert_eq!(Settings { id: 1, .. }, Settings { id: 1, level: 7 });
+}
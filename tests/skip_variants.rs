@@ -0,0 +1,26 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+#[auto_default(skip_variants(Error, Custom))]
+#[derive(PartialEq, Debug)]
+enum Event {
+    Click { x: u32, y: u32 },
+    Error { code: u32 },
+    Custom { name: String },
+}
+
+#[test]
+fn test() {
+    assert_eq!(Event::Click { .. }, Event::Click { x: 0, y: 0 });
+    assert_eq!(Event::Error { code: 4 }, Event::Error { code: 4 });
+    assert_eq!(
+        Event::Custom {
+            name: String::new()
+        },
+        Event::Custom {
+            name: String::new()
+        }
+    );
+}
@@ -0,0 +1,61 @@
+#![feature(default_field_values)]
+
+// `const_trait_impl` and `const_default` are not enabled:
+// `Type { .. }` is const-evaluable without `const Default`
+
+use auto_default::auto_default;
+
+#[auto_default(infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Inner {
+    value: u32,
+    custom: u32 = 4,
+}
+
+#[auto_default(infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Generic<'a> {
+    value: &'a str = "generic",
+}
+
+#[auto_default(infer_literals)]
+#[derive(PartialEq, Debug)]
+struct Outer {
+    #[auto_default(struct_default)]
+    inner: Inner,
+    #[auto_default(struct_default)]
+    generic: Generic<'static>,
+    primitive: u8,
+}
+
+#[auto_default(struct_default, infer_literals)]
+#[derive(PartialEq, Debug)]
+enum Container {
+    A { inner: Inner, primitive: bool },
+}
+
+const OUTER: Outer = Outer { .. };
+
+#[test]
+fn test() {
+    let inner = Inner {
+        value: 0,
+        custom: 4,
+    };
+
+    assert_eq!(
+        OUTER,
+        Outer {
+            inner: Inner { ..inner },
+            generic: Generic { value: "generic" },
+            primitive: 0,
+        }
+    );
+    assert_eq!(
+        Container::A { .. },
+        Container::A {
+            inner,
+            primitive: false
+        }
+    );
+}
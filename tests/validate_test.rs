@@ -0,0 +1,55 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use auto_default::auto_default;
+
+// `#[auto_default(validate_test)]` generates a test that calls `Foo { .. }.validate()`,
+// like the `Validate` traits of `validator` and `garde`
+
+trait Validate {
+    fn validate(&self) -> Result<(), String>;
+}
+
+#[auto_default(validate_test)]
+struct Retry {
+    attempts: u8 = 3,
+    max_attempts: u8 = 5,
+}
+
+impl Validate for Retry {
+    fn validate(&self) -> Result<(), String> {
+        if self.attempts <= self.max_attempts {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} attempts is more than {}",
+                self.attempts, self.max_attempts
+            ))
+        }
+    }
+}
+
+#[auto_default(validate_test)]
+#[derive(Default)]
+enum Level {
+    Low,
+    #[default]
+    High { value: u8 = 10 },
+}
+
+impl Validate for Level {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Level::High { value: 0 } => Err("`value` can't be 0".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn generates_tests() {
+    auto_default_validate_test_retry();
+    auto_default_validate_test_level();
+
+    let _ = (Level::Low, Retry { .. }.attempts);
+}
@@ -0,0 +1,75 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+use std::time::Duration;
+
+use auto_default::{AutoDefault, auto_default};
+
+// `#[auto_default(values(...))]` lists the default values of fields by their names,
+// and the other fields get `Default::default()`
+
+#[auto_default(values(timeout = Duration::from_secs(30), retries = 3))]
+#[derive(PartialEq, Debug)]
+struct Client {
+    timeout: Duration,
+    retries: u8,
+    verbose: bool,
+}
+
+#[auto_default]
+#[derive(PartialEq, Debug)]
+enum Transport {
+    #[auto_default(values(port = 80, secure = false))]
+    Tcp {
+        port: u16,
+        secure: bool,
+        name: String,
+    },
+    // Fields in `values(...)` get a default value even if the variant skips its fields
+    #[auto_default(skip, values(path = "/tmp/socket"))]
+    Unix { path: &'static str, mode: u32 },
+}
+
+// Only the implementation of `Default` uses them, so they don't have to be `const`
+#[derive(AutoDefault, PartialEq, Debug)]
+#[auto_default(values(name = String::from("admin"), id = 1))]
+struct User {
+    name: String,
+    id: u32,
+    active: bool = true,
+}
+
+#[test]
+fn values() {
+    assert_eq!(
+        Client { .. },
+        Client {
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            verbose: false,
+        }
+    );
+    assert_eq!(
+        Transport::Tcp { .. },
+        Transport::Tcp {
+            port: 80,
+            secure: false,
+            name: String::new(),
+        }
+    );
+    assert_eq!(
+        Transport::Unix { mode: 0o600, .. },
+        Transport::Unix {
+            path: "/tmp/socket",
+            mode: 0o600,
+        }
+    );
+    assert_eq!(
+        User::default(),
+        User {
+            name: "admin".to_string(),
+            id: 1,
+            active: true,
+        }
+    );
+}
@@ -0,0 +1,85 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+#![feature(macro_attr)]
+use auto_default::auto_default;
+
+// `#[auto_default(wasm)]` generates `#[wasm_bindgen] impl` with a constructor, which
+// `wasm-bindgen` can't be compiled for here. The attribute is replaced by a macro that
+// removes the `#[wasm_bindgen(...)]` of the methods, so the methods can be called
+
+extern crate self as wasm_bindgen;
+
+pub mod prelude {
+    macro_rules! wasm_bindgen {
+        attr() (impl $ty:ident { $(#[wasm_bindgen $attr:tt] $method:item)* }) => {
+            impl $ty { $($method)* }
+        };
+    }
+    pub(crate) use wasm_bindgen;
+}
+
+#[auto_default(wasm)]
+#[derive(PartialEq, Debug)]
+pub struct Config {
+    port: u16 = 8080,
+    name: String,
+}
+
+#[auto_default(wasm(setters), impl_default)]
+#[derive(PartialEq, Debug)]
+pub struct Session {
+    timeout: u32 = 30,
+    #[auto_default(skip)]
+    token: String,
+    #[auto_default(skip)]
+    r#type: u8,
+    #[auto_default(runtime = vec![1, 2])]
+    ids: Vec<u8>,
+}
+
+#[auto_default(wasm, impl_default)]
+#[derive(PartialEq, Debug)]
+pub struct Buffers {
+    buffer_size: usize = 1024,
+    #[auto_default(value = buffer_size * 2)]
+    cache_size: usize,
+}
+
+#[test]
+fn wasm() {
+    assert_eq!(
+        Config::new(),
+        Config {
+            port: 8080,
+            name: String::new(),
+        }
+    );
+
+    // Skipped fields get `Default::default()`, and can be set by JavaScript
+    let mut session = Session::new();
+    assert_eq!(
+        session,
+        Session {
+            timeout: 30,
+            token: String::new(),
+            r#type: 0,
+            ids: vec![1, 2],
+        }
+    );
+    session.set_token(String::from("secret"));
+    session.set_type(4);
+    session.set_ids(vec![3]);
+    assert_eq!(
+        session,
+        Session {
+            timeout: 30,
+            token: String::from("secret"),
+            r#type: 4,
+            ids: vec![3],
+        }
+    );
+
+    assert_eq!(Buffers::new(), Buffers::default());
+    assert_eq!(Buffers::new().cache_size, 2048);
+}
@@ -0,0 +1,67 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+
+use auto_default::auto_default;
+
+mod reset {
+    pub const trait Reset {
+        fn reset() -> Self;
+    }
+
+    impl const Reset for u32 {
+        fn reset() -> Self {
+            7
+        }
+    }
+
+    impl const Reset for bool {
+        fn reset() -> Self {
+            true
+        }
+    }
+}
+
+const fn ten() -> u32 {
+    10
+}
+
+#[auto_default(with = reset::Reset::reset)]
+#[derive(PartialEq, Debug)]
+struct X {
+    number: u32,
+    boolean: bool,
+    #[auto_default(with = ten)]
+    custom: u32,
+    explicit: u32 = 4,
+}
+
+#[auto_default(with = reset::Reset::reset, infer_literals)]
+#[derive(PartialEq, Debug)]
+enum Foo {
+    A {
+        // literals take precedence over the container's `with`
+        number: u32,
+        #[auto_default(with = ten)]
+        custom: u32,
+    },
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        X { .. },
+        X {
+            number: 7,
+            boolean: true,
+            custom: 10,
+            explicit: 4,
+        }
+    );
+    assert_eq!(
+        Foo::A { .. },
+        Foo::A {
+            number: 0,
+            custom: 10
+        }
+    );
+}
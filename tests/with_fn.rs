@@ -0,0 +1,47 @@
+#![feature(default_field_values)]
+#![feature(const_trait_impl)]
+#![feature(const_default)]
+
+use auto_default::auto_default;
+
+mod config {
+    use auto_default::auto_default;
+
+    #[auto_default(with_fn)]
+    #[derive(PartialEq, Debug)]
+    pub struct Config {
+        id: u32 = 1,
+        pub verbose: bool,
+        pub retries: u8 = 3,
+        pub name: String,
+    }
+
+    impl Config {
+        pub fn id(&self) -> u32 {
+            self.id
+        }
+    }
+}
+
+#[auto_default(with_fn)]
+#[derive(PartialEq, Debug)]
+struct Generic<T> {
+    items: Vec<T>,
+}
+
+#[test]
+fn test() {
+    let config = config::Config::with(|config| {
+        config.verbose = true;
+        config.name.push_str("name");
+    });
+    assert!(config.verbose);
+    assert_eq!(config.retries, 3);
+    assert_eq!(config.name, "name");
+    assert_eq!(config.id(), 1);
+
+    assert_eq!(
+        Generic::with(|generic| generic.items.push(1)),
+        Generic { items: vec![1] }
+    );
+}